@@ -18,7 +18,7 @@ pub const BUILD_MARKER_FILE: &str = "build/.yogurt-build";
 /// Rust release builds are usually under 100KB.
 const MAX_EXPECTED_WASM_SIZE: u64 = 500 * 1024; // 500 KB
 
-pub fn run(release: bool) -> Result<()> {
+pub fn run(release: bool, alloc_profile: bool) -> Result<()> {
     println!("{}", style("yogurt build").bold().cyan());
     println!();
 
@@ -44,6 +44,14 @@ pub fn run(release: bool) -> Result<()> {
         }
     }
 
+    // Mappings declaring `language: wasm/rust` use the native Rust ABI
+    let rust_abi = if manifest_path.exists() {
+        let manifest = yogurt_codegen::Manifest::parse(&fs::read_to_string(manifest_path)?)?;
+        manifest.uses_rust_abi()?
+    } else {
+        false
+    };
+
     // Run cargo build
     let profile = if release { "release" } else { "debug" };
     println!(
@@ -60,6 +68,16 @@ pub fn run(release: bool) -> Result<()> {
         cmd.arg("--release");
     }
 
+    if rust_abi {
+        println!("  Using the Rust ABI ({})", yogurt_codegen::RUST_ABI_LANGUAGE);
+        cmd.arg("--features").arg("yogurt-runtime/rust-abi");
+    }
+
+    if alloc_profile {
+        println!("  Profiling allocations (alloc-profile)");
+        cmd.arg("--features").arg("yogurt-runtime/alloc-profile");
+    }
+
     let status = cmd.status()?;
 
     if !status.success() {
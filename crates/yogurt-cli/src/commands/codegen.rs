@@ -21,7 +21,16 @@ pub fn run(manifest_path: &str) -> Result<()> {
 
     println!("  Reading {}...", manifest_path);
 
-    yogurt_codegen::generate(manifest, &output_dir)?;
+    let handler_types = yogurt_codegen::generate(manifest, &output_dir)?;
+
+    for ht in &handler_types {
+        println!(
+            "  {} {} → {}",
+            style(&ht.handler).yellow(),
+            ht.signature,
+            style(&ht.type_name).cyan()
+        );
+    }
 
     println!();
     println!("{}", style("✓ Code generation complete").green());
@@ -432,6 +432,11 @@ fn resolve_manifest(
     if let Some(templates) = resolved.get_mut("templates") {
         if let Some(tmpl_seq) = templates.as_sequence_mut() {
             for tmpl in tmpl_seq {
+                // The typed context declaration is a yogurt extension
+                if let Some(tmpl_map) = tmpl.as_mapping_mut() {
+                    tmpl_map.remove("context");
+                }
+
                 if let Some(mapping) = tmpl.get_mut("mapping") {
                     // Replace ABI files
                     if let Some(abis) = mapping.get_mut("abis") {
@@ -128,7 +128,7 @@ fn run_build_cycle(manifest: &str) -> Result<()> {
 
     // Run debug build
     print!("  Building (debug)... ");
-    match build::run(false) {
+    match build::run(false, false) {
         Ok(_) => {
             let elapsed = start.elapsed();
             println!(
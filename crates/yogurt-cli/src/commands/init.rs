@@ -85,7 +85,7 @@ dataSources:
     mapping:
       kind: ethereum/events
       apiVersion: 0.0.7
-      language: wasm/rust
+      language: wasm/assemblyscript
       entities:
         - Entity
       abis:
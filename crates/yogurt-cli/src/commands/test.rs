@@ -1,26 +1,19 @@
 //! Test command — run mapping handler tests.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use console::style;
+use std::path::Path;
 use std::process::Command;
+use yogurt_runtime::wasm_host::{WasmHost, ALLOC_PROFILE_ENV, WASM_MODULE_ENV};
 
-pub fn run(wasm: bool) -> Result<()> {
+const WASM_PATH: &str = "build/subgraph.wasm";
+
+pub fn run(wasm: bool, alloc_profile: bool) -> Result<()> {
     println!("{}", style("yogurt test").bold().cyan());
     println!();
 
     if wasm {
-        println!("  Running tests in WASM mode...");
-        println!();
-        println!(
-            "  {} WASM test mode runs handlers in actual WASM for higher fidelity.",
-            style("Note:").yellow()
-        );
-        println!("  This feature is planned but not yet implemented.");
-        println!();
-        println!("  For now, use native tests (without --wasm flag) which provide");
-        println!("  fast iteration with the full testing framework.");
-        println!();
-        anyhow::bail!("WASM test mode not yet implemented. Use native tests instead.");
+        return run_wasm(alloc_profile);
     }
 
     // Run native tests via cargo test
@@ -41,3 +34,55 @@ pub fn run(wasm: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Run the test suite against the compiled module in the embedded WASM host.
+///
+/// Tests still compile natively. With the module path in the environment,
+/// every `#[handler]` function a test calls runs its export inside the
+/// interpreter, and `WasmHost::from_env()` loads the module for tests that
+/// drive the host themselves.
+///
+/// With `alloc_profile`, every host prints its allocation report when the
+/// test drops it; output capture is disabled so the reports are shown.
+fn run_wasm(alloc_profile: bool) -> Result<()> {
+    let wasm_path = Path::new(WASM_PATH);
+    if !wasm_path.exists() {
+        anyhow::bail!("{} not found. Run `yogurt build` first.", WASM_PATH);
+    }
+
+    print!("  Loading {} into host emulator... ", style(WASM_PATH).yellow());
+    let host = WasmHost::load(wasm_path)
+        .with_context(|| format!("{} failed to instantiate", WASM_PATH))?;
+    println!("{}", style("ok").green());
+
+    if alloc_profile && host.alloc_report().is_none() {
+        anyhow::bail!(
+            "{} does not count allocations. Run `yogurt build --alloc-profile` first.",
+            WASM_PATH
+        );
+    }
+
+    println!("  Compiling tests (native target, WASM host)...");
+
+    let mut cmd = Command::new("cargo");
+    cmd.arg("test")
+        .arg("--features")
+        .arg("yogurt-runtime/wasm-host")
+        .env(WASM_MODULE_ENV, wasm_path.canonicalize()?);
+
+    if alloc_profile {
+        cmd.env(ALLOC_PROFILE_ENV, "1")
+            .args(["--", "--nocapture", "--test-threads=1"]);
+    }
+
+    let status = cmd.status()?;
+
+    if !status.success() {
+        anyhow::bail!("Tests failed");
+    }
+
+    println!();
+    println!("{}", style("✓ Tests passed in WASM host").green());
+
+    Ok(())
+}
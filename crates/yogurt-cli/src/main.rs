@@ -38,6 +38,10 @@ enum Commands {
         /// Release mode (optimised)
         #[arg(short, long)]
         release: bool,
+
+        /// Count allocations per class id and handler, for `yogurt test --wasm --alloc-profile`
+        #[arg(long)]
+        alloc_profile: bool,
     },
 
     /// Run mapping handler tests
@@ -45,6 +49,10 @@ enum Commands {
         /// Run tests in WASM (slower, higher fidelity)
         #[arg(long)]
         wasm: bool,
+
+        /// Print each test's allocation report (needs `yogurt build --alloc-profile`)
+        #[arg(long, requires = "wasm")]
+        alloc_profile: bool,
     },
 
     /// Watch for changes and auto-rebuild
@@ -104,8 +112,8 @@ async fn main() -> Result<()> {
     match cli.command {
         Commands::Init { name } => commands::init::run(name).await,
         Commands::Codegen { manifest } => commands::codegen::run(&manifest),
-        Commands::Build { release } => commands::build::run(release),
-        Commands::Test { wasm } => commands::test::run(wasm),
+        Commands::Build { release, alloc_profile } => commands::build::run(release, alloc_profile),
+        Commands::Test { wasm, alloc_profile } => commands::test::run(wasm, alloc_profile),
         Commands::Dev { manifest } => commands::dev::run(&manifest),
         Commands::Deploy { name, node, ipfs, version, studio } => {
             commands::deploy::run(node, ipfs, name, version, studio).await
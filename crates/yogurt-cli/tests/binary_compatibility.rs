@@ -6,6 +6,10 @@
 use std::path::PathBuf;
 use std::process::Command;
 use walrus::{ExportItem, Module, ValType};
+use yogurt_runtime::ethereum::{EventParam, ToEventParams, Token};
+use yogurt_runtime::testing::{EventBuilder, clear_store, store_get};
+use yogurt_runtime::types::{Address, BigInt};
+use yogurt_runtime::wasm_host::WasmHost;
 
 /// Path to the PoC subgraph WASM (built by CI or manually).
 fn poc_wasm_path() -> PathBuf {
@@ -365,6 +369,84 @@ fn test_memory_is_exported() {
     );
 }
 
+// ============================================================================
+// Handler Execution Tests
+// ============================================================================
+
+/// Params of the PoC's `Transfer(address,address,uint256)` event.
+struct TransferParams {
+    from: Address,
+    to: Address,
+    value: BigInt,
+}
+
+impl Default for TransferParams {
+    fn default() -> Self {
+        Self {
+            from: Address::zero(),
+            to: Address::zero(),
+            value: BigInt::zero(),
+        }
+    }
+}
+
+impl ToEventParams for TransferParams {
+    fn to_event_params(&self) -> Vec<EventParam> {
+        vec![
+            EventParam { name: "from".into(), value: Token::Address(self.from.clone()) },
+            EventParam { name: "to".into(), value: Token::Address(self.to.clone()) },
+            EventParam { name: "value".into(), value: Token::Uint(self.value.clone()) },
+        ]
+    }
+}
+
+/// Instantiate the PoC module in the WASM host.
+fn load_poc_host() -> WasmHost {
+    ensure_poc_built();
+    WasmHost::load(poc_wasm_path()).expect("Failed to instantiate WASM")
+}
+
+/// Run `handleTransfer` `invocations` times, asserting the heap and memory
+/// end where the first invocation left them.
+fn assert_arena_flat(invocations: u32) {
+    clear_store();
+    let mut host = load_poc_host();
+    let event = EventBuilder::new()
+        .block_number(12345678)
+        .transaction_hash([0xDE; 32])
+        .params(TransferParams {
+            from: Address::from([0x11; 20]),
+            to: Address::from([0x22; 20]),
+            value: BigInt::from_u64(1_000_000),
+        })
+        .build();
+
+    // The first invocation marks the heap after its argument, which stays
+    // allocated; every later one releases its argument with the arena
+    host.handle_event("handleTransfer", &event).unwrap();
+    let memory_size = host.memory_size();
+    let heap_top = host.heap_top().unwrap();
+
+    for _ in 1..invocations {
+        host.handle_event("handleTransfer", &event).unwrap();
+    }
+    assert_eq!(host.heap_top().unwrap(), heap_top, "heap grew over {} invocations", invocations);
+    assert_eq!(host.memory_size(), memory_size);
+    let id = "0xdededededededededededededededededededededededededededededededede-0";
+    assert!(store_get("Transfer", id).is_some());
+}
+
+#[test]
+fn test_handler_arena_keeps_memory_flat() {
+    assert_arena_flat(2_000);
+}
+
+#[test]
+#[ignore = "soak test, slow without optimisations"]
+fn test_handler_arena_keeps_memory_flat_soak() {
+    assert_arena_flat(100_000);
+}
+
 // ============================================================================
 // Summary Test
 // ============================================================================
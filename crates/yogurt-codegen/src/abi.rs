@@ -1,5 +1,7 @@
 //! Ethereum ABI parsing and Rust code generation.
 
+use std::collections::HashMap;
+
 use alloy_json_abi::JsonAbi;
 use crate::error::Result;
 
@@ -8,14 +10,22 @@ use crate::error::Result;
 pub struct AbiParser {
     pub events: Vec<ParsedEvent>,
     pub functions: Vec<ParsedFunction>,
+    pub errors: Vec<ParsedError>,
 }
 
 /// A parsed event from an ABI.
 #[derive(Debug)]
 pub struct ParsedEvent {
     pub name: String,
+    /// Name of the generated types, with an overload suffix (see [`disambiguate`]).
+    pub alias: String,
     pub inputs: Vec<EventInput>,
     pub signature: String,
+    /// Anonymous events have no signature topic, so graph-node cannot
+    /// trigger handlers on them; they are decoded from raw logs only.
+    pub anonymous: bool,
+    /// keccak256 of the signature, the first topic of non-anonymous logs.
+    pub topic0: [u8; 32],
 }
 
 /// An event input parameter.
@@ -33,10 +43,30 @@ pub struct EventInput {
 #[derive(Debug)]
 pub struct ParsedFunction {
     pub name: String,
+    /// Name of the generated binding method or call types, with an overload
+    /// suffix (see [`disambiguate`]).
+    pub alias: String,
     pub inputs: Vec<FunctionParam>,
     pub outputs: Vec<FunctionParam>,
     pub signature: String,
     pub state_mutability: String,
+    /// First four bytes of the keccak256 of the signature, which start the
+    /// call data.
+    pub selector: [u8; 4],
+}
+
+/// A custom error from an ABI, such as
+/// `error InsufficientBalance(uint256 available, uint256 required)`.
+#[derive(Debug)]
+pub struct ParsedError {
+    pub name: String,
+    /// Name of the generated enum variant, with an overload suffix.
+    pub alias: String,
+    pub inputs: Vec<FunctionParam>,
+    pub signature: String,
+    /// First four bytes of the keccak256 of the signature, which start the
+    /// revert data.
+    pub selector: [u8; 4],
 }
 
 /// A function input/output parameter.
@@ -53,24 +83,33 @@ impl AbiParser {
     pub fn parse(content: &str) -> Result<Self> {
         let abi: JsonAbi = serde_json::from_str(content)?;
 
-        let events: Vec<ParsedEvent> = abi
+        let mut events: Vec<ParsedEvent> = abi
             .events()
             .map(|e| ParsedEvent {
                 name: e.name.clone(),
+                alias: e.name.clone(),
                 inputs: e
                     .inputs
                     .iter()
-                    .map(|i| parse_event_input(i))
+                    .map(parse_event_input)
                     .collect(),
                 signature: e.signature(),
+                anonymous: e.anonymous,
+                topic0: e.selector().0,
             })
             .collect();
 
-        let functions: Vec<ParsedFunction> = abi
+        let aliases = disambiguate(events.iter().map(|e| e.name.as_str()));
+        for (event, alias) in events.iter_mut().zip(aliases) {
+            event.alias = alias;
+        }
+
+        let mut functions: Vec<ParsedFunction> = abi
             .functions()
             .filter(|f| !f.name.is_empty())
             .map(|f| ParsedFunction {
                 name: f.name.clone(),
+                alias: f.name.clone(),
                 inputs: f
                     .inputs
                     .iter()
@@ -85,13 +124,83 @@ impl AbiParser {
                     .collect(),
                 signature: f.signature(),
                 state_mutability: format!("{:?}", f.state_mutability),
+                selector: f.selector().0,
+            })
+            .collect();
+
+        // Bound view functions and call handler functions generate different
+        // items, so each group is disambiguated on its own, as graph-cli does
+        for bound in [true, false] {
+            let group: Vec<&mut ParsedFunction> = functions
+                .iter_mut()
+                .filter(|f| f.is_bound() == bound)
+                .collect();
+            let aliases = disambiguate(group.iter().map(|f| f.name.as_str()));
+            for (func, alias) in group.into_iter().zip(aliases) {
+                func.alias = alias;
+            }
+        }
+
+        let mut errors: Vec<ParsedError> = abi
+            .errors()
+            .map(|e| ParsedError {
+                name: e.name.clone(),
+                alias: e.name.clone(),
+                inputs: e
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, i)| parse_function_param(i, idx, "param"))
+                    .collect(),
+                signature: e.signature(),
+                selector: e.selector().0,
             })
             .collect();
 
-        Ok(AbiParser { events, functions })
+        let aliases = disambiguate(errors.iter().map(|e| e.name.as_str()));
+        for (error, alias) in errors.iter_mut().zip(aliases) {
+            error.alias = alias;
+        }
+
+        Ok(AbiParser { events, functions, errors })
+    }
+}
+
+impl ParsedFunction {
+    /// Whether the contract binding gets a method for this function.
+    pub(crate) fn is_bound(&self) -> bool {
+        self.state_mutability == "View" || self.state_mutability == "Pure"
     }
 }
 
+/// Name overloads the way graph-cli does: the first `Transfer` keeps its
+/// name and later ones become `Transfer1`, `Transfer2` and so on, in ABI order.
+fn disambiguate<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    names
+        .map(|name| {
+            let count = seen.entry(name).or_insert(0);
+            let alias = match *count {
+                0 => name.to_string(),
+                n => format!("{}{}", name, n),
+            };
+            *count += 1;
+            alias
+        })
+        .collect()
+}
+
+/// Normalise a manifest or ABI signature for comparison: drops `indexed`
+/// and whitespace, so `Transfer(indexed address, uint256)` matches
+/// `Transfer(address,uint256)`.
+fn normalize_signature(signature: &str) -> String {
+    signature
+        .replace("indexed ", "")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
 /// Parse an event input parameter, recursively handling tuple components.
 fn parse_event_input(param: &alloy_json_abi::EventParam) -> EventInput {
     EventInput {
@@ -143,6 +252,9 @@ impl AbiParser {
     }
 
     /// Generate Rust code for this ABI, including call handler types for specified functions.
+    ///
+    /// Functions are given by signature, or by name when not overloaded
+    /// (see [`AbiParser::find_function`]).
     pub fn generate_rust_with_call_handlers(
         &self,
         contract_name: &str,
@@ -157,7 +269,7 @@ impl AbiParser {
              #[allow(unused_imports)]\n\
              use alloc::vec::Vec;\n\
              use yogurt_runtime::prelude::*;\n\
-             use yogurt_runtime::ethereum::{Call, Event, SmartContractCall, Token};\n\n",
+             use yogurt_runtime::ethereum::{Call, CallError, ContractError, Event, FromLog, Log, SmartContractCall, Token};\n\n",
         );
 
         // Generate event structs
@@ -167,44 +279,96 @@ impl AbiParser {
         }
 
         // Generate call handler structs for specified functions
-        for func_name in call_handler_functions {
-            if let Some(func) = self.functions.iter().find(|f| &f.name == func_name) {
+        for function in call_handler_functions {
+            if let Some(func) = self.find_function(function) {
                 code.push_str(&generate_call_struct(func));
                 code.push('\n');
             }
         }
 
-        // Generate contract binding
+        // Generate the custom error enum and contract binding
+        code.push_str(&generate_error_enum(contract_name, &self.errors));
+        code.push('\n');
         code.push_str(&generate_contract_binding(contract_name, &self.functions));
 
         code
     }
 
-    /// Find a function by name (used for call handler lookups).
-    pub fn find_function(&self, name: &str) -> Option<&ParsedFunction> {
-        self.functions.iter().find(|f| f.name == name)
+    /// Find a function by signature, such as a manifest call handler's
+    /// `safeTransferFrom(address,address,uint256)`. A bare name finds the
+    /// first function with that name.
+    pub fn find_function(&self, function: &str) -> Option<&ParsedFunction> {
+        if function.contains('(') {
+            let signature = normalize_signature(function);
+            self.functions.iter().find(|f| normalize_signature(&f.signature) == signature)
+        } else {
+            self.functions.iter().find(|f| f.name == function)
+        }
+    }
+
+    /// Find an event by a manifest event handler's signature, such as
+    /// `Transfer(indexed address,indexed address,uint256)`.
+    pub fn find_event(&self, signature: &str) -> Option<&ParsedEvent> {
+        let signature = normalize_signature(signature);
+        self.events.iter().find(|e| normalize_signature(&e.signature) == signature)
     }
 }
 
 fn generate_event_struct(event: &ParsedEvent) -> String {
-    let event_name = &event.name;
-    let params_struct_name = format!("{}Params", event_name);
-    let event_struct_name = format!("{}Event", event_name);
+    let params_struct_name = format!("{}Params", event.alias);
+    let event_struct_name = format!("{}Event", event.alias);
 
     let mut code = format!(
         "/// {}({})\n",
-        event_name,
+        event.name,
         event.inputs.iter().map(|i| format!("{} {}", i.solidity_type, i.name)).collect::<Vec<_>>().join(", ")
     );
+    if event.alias != event.name {
+        code.push_str(&format!("///\n/// Overload of `{}`, generated as `{}`.\n", event.name, event.alias));
+    }
+    if event.anonymous {
+        code.push_str(&format!(
+            "///\n/// Anonymous: no handler can be triggered by it, decode logs with `{}::from_log`.\n",
+            params_struct_name
+        ));
+    }
+
+    // Convert EventInput to FunctionParam for reuse of the params generators.
+    // Indexed strings, bytes, arrays and tuples are only a hash in the topic.
+    let params_as_func_params: Vec<FunctionParam> = event.inputs.iter().map(|i| {
+        if is_hashed_topic(i) {
+            FunctionParam {
+                name: i.name.clone(),
+                solidity_type: "bytes32".to_string(),
+                components: Vec::new(),
+            }
+        } else {
+            FunctionParam {
+                name: i.name.clone(),
+                solidity_type: i.solidity_type.clone(),
+                components: i.components.clone(),
+            }
+        }
+    }).collect();
 
     // Params struct
     code.push_str(&format!("pub struct {} {{\n", params_struct_name));
-    for input in &event.inputs {
-        let rust_type = solidity_to_rust_type_with_components(&input.solidity_type, &input.components);
-        code.push_str(&format!("    pub {}: {},\n", to_snake_case(&input.name), rust_type));
+    for param in &params_as_func_params {
+        let rust_type = solidity_to_rust_type_with_components(&param.solidity_type, &param.components);
+        code.push_str(&format!("    pub {}: {},\n", to_snake_case(&param.name), rust_type));
     }
     code.push_str("}\n\n");
 
+    code.push_str(&generate_from_log(&params_struct_name, event, &params_as_func_params));
+    code.push('\n');
+    code.push_str(&generate_to_log(&params_struct_name, event, &params_as_func_params));
+    code.push('\n');
+
+    // graph-node never passes anonymous events to handlers
+    if event.anonymous {
+        return code;
+    }
+
     // Event type alias using the generic Event<P>
     code.push_str(&format!(
         "pub type {} = Event<{}>;\n\n",
@@ -212,19 +376,174 @@ fn generate_event_struct(event: &ParsedEvent) -> String {
     ));
 
     // FromAscPtr implementation for the params struct
-    // Convert EventInput to FunctionParam for reuse of generate_params_from_asc_ptr
-    let params_as_func_params: Vec<FunctionParam> = event.inputs.iter().map(|i| {
-        FunctionParam {
-            name: i.name.clone(),
-            solidity_type: i.solidity_type.clone(),
-            components: i.components.clone(),
-        }
-    }).collect();
     code.push_str(&generate_params_from_asc_ptr(&params_struct_name, &params_as_func_params));
+    code.push('\n');
+    code.push_str(&generate_to_event_params(&params_struct_name, &params_as_func_params));
+    code.push('\n');
+    code.push_str(&generate_rust_abi_decode(&params_struct_name, &params_as_func_params));
 
     code
 }
 
+/// Whether an indexed event input is stored as the keccak256 hash of its
+/// value rather than the value itself.
+pub(crate) fn is_hashed_topic(input: &EventInput) -> bool {
+    input.indexed
+        && (input.solidity_type == "string"
+            || input.solidity_type == "bytes"
+            || input.solidity_type.starts_with("tuple")
+            || input.solidity_type.contains('['))
+}
+
+/// Generate the `FromLog` implementation, which decodes a raw log of the event with the ABI
+/// decoder: indexed params from the topics, the others from the data.
+///
+/// `params` are the event inputs with hashed topics already typed `bytes32`.
+fn generate_from_log(struct_name: &str, event: &ParsedEvent, params: &[FunctionParam]) -> String {
+    let mut indexed = Vec::new();
+    let mut data = Vec::new();
+    let mut fields = String::new();
+    for (input, param) in event.inputs.iter().zip(params) {
+        let abi_type = to_abi_type(&param.solidity_type, &param.components);
+        let token = if input.indexed {
+            indexed.push(format!(
+                "    abi::decode_value(&ParamType::parse(\"{}\")?, topics[{}].as_slice())?,\n",
+                abi_type,
+                indexed.len()
+            ));
+            format!("indexed.get({})", indexed.len() - 1)
+        } else {
+            data.push(format!("ParamType::parse(\"{}\")?", abi_type));
+            format!("data.get({})", data.len() - 1)
+        };
+        fields.push_str(&format!(
+            "    {}: {},\n",
+            to_snake_case(&param.name),
+            token_to_rust_extraction_with_components(&token, &param.solidity_type, &param.components)
+        ));
+    }
+
+    let (topics, topic0) = if event.anonymous {
+        ("let topics = log.topics.as_slice();".to_string(), String::new())
+    } else {
+        (
+            "let topics = match log.topics.split_first() {\n\
+                         Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,\n\
+                         _ => return None,\n\
+                     };"
+                .to_string(),
+            format!(
+                "impl {} {{\n\
+                 /// First topic of its logs, the keccak256 hash of `{}`.\n\
+                 pub const TOPIC0: [u8; 32] = [{}];\n\
+                 }}\n\n",
+                struct_name,
+                event.signature,
+                event.topic0.iter().map(|b| format!("{:#04x}", b)).collect::<Vec<_>>().join(", ")
+            ),
+        )
+    };
+    let indexed_tokens = if indexed.is_empty() {
+        String::new()
+    } else {
+        format!("let indexed = [\n{}];\n", indexed.concat())
+    };
+    let data_tokens = if data.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "let data = abi::decode(&[{}], log.data.as_slice())?;\n",
+            data.join(", ")
+        )
+    };
+
+    format!(
+        "{topic0}\
+        impl FromLog for {name} {{\n\
+            fn from_log(log: &Log) -> Option<Self> {{\n\
+                #[allow(unused_imports)]\n\
+                use yogurt_runtime::ethereum::abi::{{self, ParamType}};\n\
+                \n\
+                {topics}\n\
+                if topics.len() != {indexed_count} {{\n\
+                    return None;\n\
+                }}\n\
+{indexed_tokens}\
+{data_tokens}\
+                Some(Self {{\n\
+{fields}\
+                }})\n\
+            }}\n\
+        }}\n",
+        name = struct_name,
+        topic0 = topic0,
+        topics = topics,
+        indexed_count = indexed.len(),
+        indexed_tokens = indexed_tokens,
+        data_tokens = data_tokens,
+        fields = fields,
+    )
+}
+
+/// Generate the `ToLog` implementation, the inverse of `from_log`, which
+/// native tests use to match events against topic filters.
+fn generate_to_log(struct_name: &str, event: &ParsedEvent, params: &[FunctionParam]) -> String {
+    let mut topics = String::new();
+    if !event.anonymous {
+        topics.push_str("    Bytes::from(Self::TOPIC0),\n");
+    }
+    let mut data = Vec::new();
+    for (input, param) in event.inputs.iter().zip(params) {
+        let token = solidity_to_token_conversion_with_components(
+            &format!("self.{}", to_snake_case(&param.name)),
+            &param.solidity_type,
+            &param.components,
+        );
+        if input.indexed {
+            topics.push_str(&format!("    Bytes::from(abi::encode(&[{}]).unwrap_or_default()),\n", token));
+        } else {
+            data.push(token);
+        }
+    }
+
+    format!(
+        "#[cfg(not(target_arch = \"wasm32\"))]\n\
+        impl yogurt_runtime::ethereum::ToLog for {name} {{\n\
+            fn to_log(&self, address: Address) -> Log {{\n\
+                #[allow(unused_imports)]\n\
+                use yogurt_runtime::ethereum::abi;\n\
+                \n\
+                Log {{\n\
+                    address,\n\
+                    topics: vec![\n{topics}],\n\
+                    data: Bytes::from(abi::encode(&[{data}]).unwrap_or_default()),\n\
+                    ..Default::default()\n\
+                }}\n\
+            }}\n\
+        }}\n",
+        name = struct_name,
+        topics = topics,
+        data = data.join(", "),
+    )
+}
+
+/// The Solidity type string `ParamType::parse` expects, with tuples spelled
+/// out: `tuple[]` with components `uint128, int24` is `(uint128,int24)[]`.
+pub(crate) fn to_abi_type(sol_type: &str, components: &[FunctionParam]) -> String {
+    match sol_type.strip_prefix("tuple") {
+        Some(suffix) => format!(
+            "({}){}",
+            components
+                .iter()
+                .map(|c| to_abi_type(&c.solidity_type, &c.components))
+                .collect::<Vec<_>>()
+                .join(","),
+            suffix
+        ),
+        None => sol_type.to_string(),
+    }
+}
+
 /// Generate a call handler struct for a function.
 ///
 /// Creates:
@@ -232,7 +551,7 @@ fn generate_event_struct(event: &ParsedEvent) -> String {
 /// - `{FunctionName}Outputs` struct with typed fields for function outputs
 /// - `{FunctionName}Call` type alias for `Call<Inputs, Outputs>`
 fn generate_call_struct(func: &ParsedFunction) -> String {
-    let func_name = to_pascal_case(&func.name);
+    let func_name = to_pascal_case(&func.alias);
     let inputs_struct_name = format!("{}Inputs", func_name);
     let outputs_struct_name = format!("{}Outputs", func_name);
     let call_type_name = format!("{}Call", func_name);
@@ -270,10 +589,98 @@ fn generate_call_struct(func: &ParsedFunction) -> String {
 
     // FromAscPtr implementation for outputs
     code.push_str(&generate_params_from_asc_ptr(&outputs_struct_name, &func.outputs));
+    code.push('\n');
+
+    // ToEventParams implementations, used by the WASM host emulator in tests
+    code.push_str(&generate_to_event_params(&inputs_struct_name, &func.inputs));
+    code.push('\n');
+    code.push_str(&generate_to_event_params(&outputs_struct_name, &func.outputs));
+    code.push('\n');
+
+    // Rust ABI decoding, used when the mapping is built for `language: wasm/rust`
+    code.push_str(&generate_rust_abi_decode(&inputs_struct_name, &func.inputs));
+    code.push('\n');
+    code.push_str(&generate_rust_abi_decode(&outputs_struct_name, &func.outputs));
+    code.push('\n');
+
+    // ABI call data, used by the native chain simulator in tests
+    code.push_str(&generate_call_data(&inputs_struct_name, &func.inputs, Some(func.selector)));
+    code.push('\n');
+    code.push_str(&generate_call_data(&outputs_struct_name, &func.outputs, None));
 
     code
 }
 
+/// Generate the native-only `CallData` implementation of call handler
+/// inputs, which start with the function's `selector`, or outputs.
+fn generate_call_data(struct_name: &str, params: &[FunctionParam], selector: Option<[u8; 4]>) -> String {
+    let types = params
+        .iter()
+        .map(|p| format!("ParamType::parse(\"{}\")?", to_abi_type(&p.solidity_type, &p.components)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let fields: String = params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            format!(
+                "    {}: {},\n",
+                to_snake_case(&p.name),
+                token_to_rust_extraction_with_components(&format!("values.get({})", i), &p.solidity_type, &p.components)
+            )
+        })
+        .collect();
+    let tokens = params
+        .iter()
+        .map(|p| {
+            solidity_to_token_conversion_with_components(
+                &format!("self.{}", to_snake_case(&p.name)),
+                &p.solidity_type,
+                &p.components,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let (strip, prefix) = match selector {
+        Some(selector) => {
+            let bytes = selector.iter().map(|b| format!("{:#04x}", b)).collect::<Vec<_>>().join(", ");
+            (
+                format!("let data = data.strip_prefix(&[{}])?;\n", bytes),
+                format!("vec![{}]", bytes),
+            )
+        }
+        None => (String::new(), "Vec::new()".to_string()),
+    };
+
+    format!(
+        "#[cfg(not(target_arch = \"wasm32\"))]\n\
+        impl yogurt_runtime::ethereum::CallData for {name} {{\n\
+            fn from_call_data(data: &[u8]) -> Option<Self> {{\n\
+                #[allow(unused_imports)]\n\
+                use yogurt_runtime::ethereum::abi::{{self, ParamType}};\n\
+                \n\
+                {strip}\
+                #[allow(unused_variables)]\n\
+                let values = abi::decode(&[{types}], data)?;\n\
+                Some(Self {{\n\
+{fields}\
+                }})\n\
+            }}\n\n\
+            fn to_call_data(&self) -> Vec<u8> {{\n\
+                let mut data = {prefix};\n\
+                data.extend(yogurt_runtime::ethereum::abi::encode(&[{tokens}]).unwrap_or_default());\n\
+                data\n\
+            }}\n\
+        }}\n",
+        name = struct_name,
+        strip = strip,
+        types = types,
+        fields = fields,
+        prefix = prefix,
+        tokens = tokens,
+    )
+}
+
 /// Generate FromAscPtr implementation for a params struct (inputs or outputs).
 ///
 /// The params come as Array<EventParam> from graph-node.
@@ -286,14 +693,7 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
     if params.is_empty() {
         // Empty struct - trivial implementation
         return format!(
-            "#[cfg(target_arch = \"wasm32\")]\n\
-            impl FromAscPtr for {name} {{\n\
-                fn from_asc_ptr(_ptr: u32) -> Self {{\n\
-                    Self {{}}\n\
-                }}\n\
-            }}\n\n\
-            #[cfg(not(target_arch = \"wasm32\"))]\n\
-            impl FromAscPtr for {name} {{\n\
+            "impl FromAscPtr for {name} {{\n\
                 fn from_asc_ptr(_ptr: u32) -> Self {{\n\
                     Self {{}}\n\
                 }}\n\
@@ -309,12 +709,12 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
     for (i, param) in params.iter().enumerate() {
         let field_name = to_snake_case(&param.name);
         let offset = i * 4;
-        let extraction = generate_field_extraction(&field_name, &param.solidity_type, i, offset);
+        let extraction = generate_field_extraction(&field_name, param, i, offset);
         field_extractions.push_str(&extraction);
         field_names.push(field_name);
     }
 
-    // Generate default values for non-wasm target
+    // Default values for null pointers
     let mut default_fields = String::new();
     for param in params {
         let field_name = to_snake_case(&param.name);
@@ -325,21 +725,20 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
     let field_list = field_names.join(", ");
 
     format!(
-        "#[cfg(target_arch = \"wasm32\")]\n\
-        impl FromAscPtr for {name} {{\n\
+        "impl FromAscPtr for {name} {{\n\
             fn from_asc_ptr(ptr: u32) -> Self {{\n\
-                use yogurt_runtime::asc::{{asc_to_bytes, read_u32_at, read_u64_at, AscArrayHeader, AscPtr}};\n\
+                #[allow(unused_imports)]\n\
+                use yogurt_runtime::asc::{{asc_to_bytes, read_u32_at, read_u64_at, AscPtr}};\n\
                 \n\
                 if ptr == 0 {{\n\
                     return Self::default();\n\
                 }}\n\
                 \n\
                 unsafe {{\n\
-                    // ptr points to Array<EventParam>\n\
-                    let array_header = ptr as *const AscArrayHeader;\n\
+                    // ptr points to Array<EventParam>: {{buffer, buffer_data_start, ...}}\n\
                     // Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,\n\
                     // buffer_data_start points directly to the data\n\
-                    let buffer_ptr = (*array_header).buffer_data_start;\n\
+                    let buffer_ptr = read_u32_at(ptr, 4);\n\
                     \n\
                     // Defensive null check on buffer\n\
                     if buffer_ptr == 0 {{\n\
@@ -350,6 +749,7 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
                     // value points to ethereum.Value enum (graph-node's AscEnum):\n\
                     // kind (i32) + _padding (u32) + payload (u64)\n\
                     const EVENT_PARAM_VALUE_OFFSET: usize = 4;\n\
+                    #[allow(dead_code)]\n\
                     const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;\n\
                     \n\
 {field_extractions}\
@@ -357,12 +757,6 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
                 }}\n\
             }}\n\
         }}\n\n\
-        #[cfg(not(target_arch = \"wasm32\"))]\n\
-        impl FromAscPtr for {name} {{\n\
-            fn from_asc_ptr(_ptr: u32) -> Self {{\n\
-                Self::default()\n\
-            }}\n\
-        }}\n\n\
         impl Default for {name} {{\n\
             fn default() -> Self {{\n\
                 Self {{\n\
@@ -377,6 +771,80 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
     )
 }
 
+/// Generate a `ToEventParams` implementation for a params struct.
+///
+/// This is the inverse of `generate_params_from_asc_ptr`: it turns the typed
+/// fields back into named tokens so `yogurt test --wasm` can hand test events
+/// to the compiled handlers. Only needed on the native target.
+fn generate_to_event_params(struct_name: &str, params: &[FunctionParam]) -> String {
+    let body = if params.is_empty() {
+        "Vec::new()".to_string()
+    } else {
+        let entries: Vec<String> = params
+            .iter()
+            .map(|p| {
+                let field = format!("self.{}", to_snake_case(&p.name));
+                format!(
+                    "                EventParam {{ name: \"{}\".into(), value: {} }},\n",
+                    p.name,
+                    solidity_to_token_conversion_with_components(&field, &p.solidity_type, &p.components)
+                )
+            })
+            .collect();
+        format!("vec![\n{}            ]", entries.concat())
+    };
+
+    format!(
+        "#[cfg(not(target_arch = \"wasm32\"))]\n\
+        impl yogurt_runtime::ethereum::ToEventParams for {name} {{\n\
+            fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {{\n\
+                #[allow(unused_imports)]\n\
+                use yogurt_runtime::ethereum::EventParam;\n\
+                {body}\n\
+            }}\n\
+        }}\n",
+        name = struct_name,
+        body = body,
+    )
+}
+
+/// Generate a `rust_abi::Decode` implementation for a params struct.
+///
+/// Under the Rust ABI the params arrive as a list of named tokens, so each
+/// field is extracted positionally with the same conversions as
+/// the contract binding return values.
+fn generate_rust_abi_decode(struct_name: &str, params: &[FunctionParam]) -> String {
+    let fields: String = params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            format!(
+                "                {}: {},\n",
+                to_snake_case(&p.name),
+                token_to_rust_extraction_with_components(
+                    &format!("params.get({})", i),
+                    &p.solidity_type,
+                    &p.components
+                )
+            )
+        })
+        .collect();
+
+    format!(
+        "impl yogurt_runtime::rust_abi::Decode for {name} {{\n\
+            fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {{\n\
+                #[allow(unused_variables)]\n\
+                let params = yogurt_runtime::rust_abi::decode_event_params(input)?;\n\
+                Ok(Self {{\n\
+{fields}\
+                }})\n\
+            }}\n\
+        }}\n",
+        name = struct_name,
+        fields = fields,
+    )
+}
+
 /// Generate extraction code for a single field from EventParam array.
 ///
 /// The EventParam.value field points to an ethereum.Value enum (graph-node's AscEnum):
@@ -384,64 +852,69 @@ fn generate_params_from_asc_ptr(struct_name: &str, params: &[FunctionParam]) ->
 ///   - _padding (u32) at offset 4
 ///   - payload (u64) at offset 8 — pointer in low 32 bits
 ///
+/// Scalars are read straight from the payload. Sized integers, tuples and
+/// arrays are decoded into a `Token` first and converted the same way as
+/// contract call results, recursing through their components.
+///
 /// Each extraction includes null checks at every pointer level to prevent
 /// panics from invalid memory access.
-fn generate_field_extraction(field_name: &str, solidity_type: &str, index: usize, offset: usize) -> String {
+fn generate_field_extraction(field_name: &str, param: &FunctionParam, index: usize, offset: usize) -> String {
+    let solidity_type = param.solidity_type.as_str();
+    let read_payload = |cast: &str| {
+        format!(
+            "let {name}_payload = read_u64_at({name}_value_ptr, ETHEREUM_VALUE_DATA_OFFSET){cast};\n",
+            name = field_name,
+            cast = cast
+        )
+    };
+
     // Generate null-safe pointer reads with default value fallback
-    let (default_value, value_extraction) = match solidity_type {
-        "address" => (
-            "Address::zero()",
-            format!(
-                "let {name}_bytes = asc_to_bytes(AscPtr::new({name}_payload));\n\
+    let value_extraction = match solidity_type {
+        "address" => format!(
+            "{read}let {name}_bytes = asc_to_bytes(AscPtr::new({name}_payload));\n\
                                  Address::from({name}_bytes.as_slice())",
-                name = field_name
-            ),
+            read = read_payload(" as u32"),
+            name = field_name
         ),
-        "bool" => (
-            "false",
-            format!("{name}_payload != 0", name = field_name),
-        ),
-        "string" => (
-            "String::new()",
-            format!(
-                "yogurt_runtime::asc::asc_to_string(AscPtr::new({name}_payload as u32))",
-                name = field_name
-            ),
-        ),
-        "bytes" => (
-            "Bytes::new()",
-            format!(
-                "Bytes::from_vec(asc_to_bytes(AscPtr::new({name}_payload)))",
-                name = field_name
-            ),
+        // For bool, we use the raw u64 payload directly
+        "bool" => format!(
+            "{read}{name}_payload != 0",
+            read = read_payload(""),
+            name = field_name
         ),
-        t if t.starts_with("uint") || t.starts_with("int") => (
-            "BigInt::zero()",
-            format!(
-                "BigInt::from_ptr(AscPtr::new({name}_payload))",
-                name = field_name
-            ),
+        "string" => format!(
+            "{read}yogurt_runtime::asc::asc_to_string(AscPtr::new({name}_payload))",
+            read = read_payload(" as u32"),
+            name = field_name
         ),
-        t if t.starts_with("bytes") => (
-            "Bytes::new()",
+        t if (t.starts_with("uint") || t.starts_with("int"))
+            && !t.contains('[')
+            && get_default_value(t) == "BigInt::zero()" =>
+        {
             format!(
-                "Bytes::from_vec(asc_to_bytes(AscPtr::new({name}_payload)))",
+                "{read}BigInt::from_asc_ptr({name}_payload)",
+                read = read_payload(" as u32"),
                 name = field_name
-            ),
+            )
+        }
+        t if t.starts_with("bytes") && !t.contains('[') => format!(
+            "{read}Bytes::from_vec(asc_to_bytes(AscPtr::new({name}_payload)))",
+            read = read_payload(" as u32"),
+            name = field_name
         ),
-        // Default: treat as bytes
-        _ => (
-            "Bytes::new()",
-            format!(
-                "Bytes::from_vec(asc_to_bytes(AscPtr::new({name}_payload)))",
-                name = field_name
-            ),
+        // Sized integers, tuples and arrays
+        _ => format!(
+            "let {name}_token = Token::from_asc_ptr({name}_value_ptr);\n\
+                                 {extraction}",
+            name = field_name,
+            extraction = token_to_rust_extraction_with_components(
+                &format!("Some(&{}_token)", field_name),
+                solidity_type,
+                &param.components
+            )
         ),
     };
 
-    // For bool, we use the raw u64 payload directly; for others we cast to u32
-    let payload_cast = if solidity_type == "bool" { "" } else { " as u32" };
-
     format!(
         "                    let param{idx}_ptr = read_u32_at(buffer_ptr, {offset});\n\
                          let {name} = if param{idx}_ptr == 0 {{\n\
@@ -451,34 +924,41 @@ fn generate_field_extraction(field_name: &str, solidity_type: &str, index: usize
                              if {name}_value_ptr == 0 {{\n\
                                  {default}\n\
                              }} else {{\n\
-                                 let {name}_payload = read_u64_at({name}_value_ptr, ETHEREUM_VALUE_DATA_OFFSET){cast};\n\
                                  {extraction}\n\
                              }}\n\
                          }};\n\n",
         idx = index,
         offset = offset,
         name = field_name,
-        default = default_value,
-        cast = payload_cast,
+        default = get_default_value(solidity_type),
         extraction = value_extraction,
     )
 }
 
 /// Get the default value for a Solidity type.
+///
+/// Matches the Rust type from `solidity_to_rust_type_with_components`.
 fn get_default_value(solidity_type: &str) -> &'static str {
     match solidity_type {
         "address" => "Address::zero()",
         "bool" => "false",
         "string" => "String::new()",
         "bytes" => "Bytes::new()",
-        t if t.starts_with("uint") || t.starts_with("int") => "BigInt::zero()",
-        t if t.starts_with("bytes") => "Bytes::new()",
-        _ => "Bytes::new()",
+        t if t.starts_with("uint") && !t.contains('[') => {
+            if t[4..].parse::<u32>().unwrap_or(256) <= 64 { "0" } else { "BigInt::zero()" }
+        }
+        t if t.starts_with("int") && !t.contains('[') => {
+            if t[3..].parse::<u32>().unwrap_or(256) <= 64 { "0" } else { "BigInt::zero()" }
+        }
+        t if t.starts_with("bytes") && !t.contains('[') => "Bytes::new()",
+        // Tuples and arrays
+        _ => "Default::default()",
     }
 }
 
 fn generate_contract_binding(name: &str, functions: &[ParsedFunction]) -> String {
     let struct_name = to_pascal_case(name);
+    let error_enum = format!("{}Error", struct_name);
 
     let mut code = format!(
         "/// Binding for the {name} contract.\n\
@@ -496,25 +976,12 @@ fn generate_contract_binding(name: &str, functions: &[ParsedFunction]) -> String
 
     // Generate method for each view/pure function
     for func in functions {
-        if func.state_mutability != "View" && func.state_mutability != "Pure" {
+        if !func.is_bound() {
             continue;
         }
 
-        let method_name = to_snake_case(&func.name);
-        let return_type = if func.outputs.is_empty() {
-            "()".to_string()
-        } else if func.outputs.len() == 1 {
-            solidity_to_rust_type_with_components(&func.outputs[0].solidity_type, &func.outputs[0].components)
-        } else {
-            format!(
-                "({})",
-                func.outputs
-                    .iter()
-                    .map(|o| solidity_to_rust_type_with_components(&o.solidity_type, &o.components))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        };
+        let method_name = to_snake_case(&func.alias);
+        let return_type = binding_return_type(func);
 
         // Build parameter list
         let params: Vec<String> = func
@@ -550,9 +1017,14 @@ fn generate_contract_binding(name: &str, functions: &[ParsedFunction]) -> String
 
         let call_args = func.inputs.iter().map(|i| to_snake_case(&i.name)).collect::<Vec<_>>().join(", ");
         code.push_str(&format!(
-            "    /// Call `{func_name}`\n\
+            "    /// Call `{func_doc}`\n\
             pub fn {method_name}({params_str}) -> Result<{return_type}, String> {{\n\
-                use yogurt_runtime::ethereum::{{SmartContractCall, Token, call}};\n\
+                self.try_{method_name}({call_args}).map_err(|e| e.to_string())\n\
+            }}\n\n\
+            /// Try to call `{func_doc}`, telling a revert reason, a custom error\n\
+            /// and an unknown revert apart.\n\
+            pub fn try_{method_name}({params_str}) -> Result<{return_type}, CallError<{error_enum}>> {{\n\
+                use yogurt_runtime::ethereum::{{SmartContractCall, Token, try_call}};\n\
                 \n\
                 let call_data = SmartContractCall {{\n\
                     contract_name: \"{contract_name}\".into(),\n\
@@ -562,14 +1034,13 @@ fn generate_contract_binding(name: &str, functions: &[ParsedFunction]) -> String
                     function_params: {tokens_array},\n\
                 }};\n\
                 \n\
-                let result = call(call_data).ok_or_else(|| \"Call reverted\".to_string())?;\n\
+                let result = try_call(call_data)\n\
+                    .map_err(|data| CallError::from_revert_data(data.as_slice()))?;\n\
                 {return_extraction}\n\
-            }}\n\n\
-            /// Try to call `{func_name}`, returning None on revert.\n\
-            pub fn try_{method_name}({params_str}) -> Option<{return_type}> {{\n\
-                self.{method_name}({call_args}).ok()\n\
             }}\n\n",
+            error_enum = error_enum,
             func_name = func.name,
+            func_doc = if func.alias == func.name { &func.name } else { &func.signature },
             method_name = method_name,
             params_str = params_str,
             return_type = return_type,
@@ -585,8 +1056,156 @@ fn generate_contract_binding(name: &str, functions: &[ParsedFunction]) -> String
     code
 }
 
-fn solidity_to_rust_type(sol_type: &str) -> String {
-    solidity_to_rust_type_with_components(sol_type, &[])
+/// The Rust type a binding method returns for the function's outputs.
+pub(crate) fn binding_return_type(func: &ParsedFunction) -> String {
+    if func.outputs.is_empty() {
+        "()".to_string()
+    } else if func.outputs.len() == 1 {
+        solidity_to_rust_type_with_components(&func.outputs[0].solidity_type, &func.outputs[0].components)
+    } else {
+        format!(
+            "({})",
+            func.outputs
+                .iter()
+                .map(|o| solidity_to_rust_type_with_components(&o.solidity_type, &o.components))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Generate the `<Contract>Error` enum of the ABI's custom errors, which
+/// `try_*` bindings decode revert data into.
+fn generate_error_enum(name: &str, errors: &[ParsedError]) -> String {
+    let enum_name = format!("{}Error", to_pascal_case(name));
+
+    let mut variants = String::new();
+    let mut decode_arms = String::new();
+    let mut encode_arms = String::new();
+    for error in errors {
+        let selector = error.selector.iter().map(|b| format!("{:#04x}", b)).collect::<Vec<_>>().join(", ");
+        let fields: Vec<String> = error.inputs.iter().map(|i| to_snake_case(&i.name)).collect();
+        variants.push_str(&format!("    /// `{}`\n", error.signature));
+
+        if error.inputs.is_empty() {
+            variants.push_str(&format!("    {},\n", error.alias));
+            decode_arms.push_str(&format!("[{}] => Some(Self::{}),\n", selector, error.alias));
+            encode_arms.push_str(&format!("Self::{} => ([{}], vec![]),\n", error.alias, selector));
+            continue;
+        }
+
+        variants.push_str(&format!(
+            "    {} {{\n{}    }},\n",
+            error.alias,
+            error
+                .inputs
+                .iter()
+                .zip(&fields)
+                .map(|(i, field)| format!(
+                    "        {}: {},\n",
+                    field,
+                    solidity_to_rust_type_with_components(&i.solidity_type, &i.components)
+                ))
+                .collect::<String>()
+        ));
+
+        let types = error
+            .inputs
+            .iter()
+            .map(|i| format!("ParamType::parse(\"{}\")?", to_abi_type(&i.solidity_type, &i.components)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let extractions = error
+            .inputs
+            .iter()
+            .zip(&fields)
+            .enumerate()
+            .map(|(idx, (i, field))| format!(
+                "    {}: {},\n",
+                field,
+                token_to_rust_extraction_with_components(&format!("values.get({})", idx), &i.solidity_type, &i.components)
+            ))
+            .collect::<String>();
+        decode_arms.push_str(&format!(
+            "[{selector}] => {{\n\
+            let values = abi::decode(&[{types}], args)?;\n\
+            Some(Self::{alias} {{\n\
+{extractions}\
+            }})\n\
+            }}\n",
+            selector = selector,
+            types = types,
+            alias = error.alias,
+            extractions = extractions,
+        ));
+
+        let tokens = error
+            .inputs
+            .iter()
+            .zip(&fields)
+            .map(|(i, field)| solidity_to_token_conversion_with_components(field, &i.solidity_type, &i.components))
+            .collect::<Vec<_>>()
+            .join(", ");
+        encode_arms.push_str(&format!(
+            "Self::{} {{ {} }} => ([{}], vec![{}]),\n",
+            error.alias,
+            fields.join(", "),
+            selector,
+            tokens
+        ));
+    }
+
+    // A contract without custom errors gets an empty enum, so the `try_*`
+    // signatures stay the same
+    let (decode_body, encode_body) = if errors.is_empty() {
+        ("let _ = data;\nNone\n".to_string(), "match *self {}\n".to_string())
+    } else {
+        (
+            format!(
+                "#[allow(unused_imports)]\n\
+                use yogurt_runtime::ethereum::abi::{{self, ParamType}};\n\
+                \n\
+                let (selector, {args}) = data.split_at_checked(4)?;\n\
+                match selector {{\n\
+{decode_arms}\
+                _ => None,\n\
+                }}\n",
+                args = if errors.iter().any(|e| !e.inputs.is_empty()) { "args" } else { "_" },
+                decode_arms = decode_arms,
+            ),
+            format!(
+                "let (selector, tokens): ([u8; 4], Vec<Token>) = match self.clone() {{\n\
+{encode_arms}\
+                }};\n\
+                let mut data = selector.to_vec();\n\
+                data.extend(yogurt_runtime::ethereum::abi::encode(&tokens).unwrap_or_default());\n\
+                data\n",
+                encode_arms = encode_arms,
+            ),
+        )
+    };
+
+    format!(
+        "/// Custom errors of the {name} contract, decoded from the revert data of\n\
+        /// its `try_*` calls.\n\
+        #[derive(Clone, Debug, PartialEq)]\n\
+        pub enum {enum_name} {{\n\
+{variants}\
+        }}\n\n\
+        impl ContractError for {enum_name} {{\n\
+            fn decode(data: &[u8]) -> Option<Self> {{\n\
+{decode_body}\
+            }}\n\n\
+            fn encode(&self) -> Vec<u8> {{\n\
+{encode_body}\
+            }}\n\
+        }}\n",
+        name = name,
+        enum_name = enum_name,
+        variants = variants,
+        decode_body = decode_body,
+        encode_body = encode_body,
+    )
 }
 
 /// Convert a Solidity type to a Rust type, with optional tuple components.
@@ -633,11 +1252,7 @@ fn solidity_to_rust_type_with_components(sol_type: &str, components: &[FunctionP
                 let inner = &t[..bracket_pos];
                 let size_str = &t[bracket_pos + 1..t.len() - 1];
                 if let Ok(size) = size_str.parse::<usize>() {
-                    let inner_type = if inner == "tuple" {
-                        solidity_to_rust_type_with_components(inner, components)
-                    } else {
-                        solidity_to_rust_type(inner)
-                    };
+                    let inner_type = solidity_to_rust_type_with_components(inner, components);
                     return format!("[{}; {}]", inner_type, size);
                 }
             }
@@ -647,18 +1262,13 @@ fn solidity_to_rust_type_with_components(sol_type: &str, components: &[FunctionP
         // Dynamic array: "type[]"
         t if t.ends_with("[]") => {
             let inner = &t[..t.len() - 2];
-            let inner_type = if inner == "tuple" {
-                solidity_to_rust_type_with_components(inner, components)
-            } else {
-                solidity_to_rust_type(inner)
-            };
-            format!("Vec<{}>", inner_type)
+            format!("Vec<{}>", solidity_to_rust_type_with_components(inner, components))
         }
         _ => "Bytes".to_string(), // Fallback for unknown types
     }
 }
 
-fn to_snake_case(s: &str) -> String {
+pub(crate) fn to_snake_case(s: &str) -> String {
     if s.is_empty() {
         return String::new();
     }
@@ -708,8 +1318,21 @@ fn solidity_to_token_conversion_with_components(
         "bool" => format!("Token::Bool({})", var_name),
         "string" => format!("Token::String({}.clone())", var_name),
         "bytes" => format!("Token::Bytes({}.clone())", var_name),
-        t if t.starts_with("uint") && !t.contains('[') => format!("Token::Uint({}.clone())", var_name),
-        t if t.starts_with("int") && !t.contains('[') => format!("Token::Int({}.clone())", var_name),
+        // Integers of 64 bits or fewer are plain u64/i64 fields (see solidity_to_rust_type)
+        t if t.starts_with("uint") && !t.contains('[') => {
+            if t[4..].parse::<u32>().unwrap_or(256) <= 64 {
+                format!("Token::Uint(BigInt::from_u64({}))", var_name)
+            } else {
+                format!("Token::Uint({}.clone())", var_name)
+            }
+        }
+        t if t.starts_with("int") && !t.contains('[') => {
+            if t[3..].parse::<u32>().unwrap_or(256) <= 64 {
+                format!("Token::Int(BigInt::from_i64({}))", var_name)
+            } else {
+                format!("Token::Int({}.clone())", var_name)
+            }
+        }
         t if t.starts_with("bytes") && !t.contains('[') && t.len() > 5 => {
             // Fixed bytes like bytes32
             format!("Token::FixedBytes({}.as_slice().to_vec())", var_name)
@@ -751,7 +1374,7 @@ fn solidity_to_token_conversion_with_components(
             format!(
                 "Token::Array({}.iter().map(|v| {}).collect())",
                 var_name,
-                solidity_to_token_conversion_with_components("v", inner, components)
+                solidity_to_token_conversion_with_components("(*v)", inner, components)
             )
         }
         _ => format!("Token::Bytes({}.clone())", var_name), // Fallback
@@ -929,3 +1552,127 @@ fn token_to_rust_extraction_with_components(
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// OpenZeppelin ERC721 overloads, plus a view `safeTransferFrom` to
+    /// check that bound and call handler functions are named separately.
+    const ERC721: &str = r#"[
+        {"type": "event", "name": "Approval", "anonymous": false, "inputs": [
+            {"name": "owner", "type": "address", "indexed": true},
+            {"name": "approved", "type": "address", "indexed": true},
+            {"name": "tokenId", "type": "uint256", "indexed": true}
+        ]},
+        {"type": "event", "name": "Approval", "anonymous": false, "inputs": [
+            {"name": "owner", "type": "address", "indexed": true},
+            {"name": "approved", "type": "address", "indexed": true},
+            {"name": "tokenId", "type": "uint256", "indexed": true},
+            {"name": "data", "type": "bytes", "indexed": false}
+        ]},
+        {"type": "function", "name": "safeTransferFrom", "stateMutability": "nonpayable",
+         "inputs": [{"name": "from", "type": "address"}, {"name": "to", "type": "address"},
+                    {"name": "tokenId", "type": "uint256"}],
+         "outputs": []},
+        {"type": "function", "name": "safeTransferFrom", "stateMutability": "view",
+         "inputs": [{"name": "tokenId", "type": "uint256"}],
+         "outputs": [{"name": "", "type": "bool"}]},
+        {"type": "function", "name": "safeTransferFrom", "stateMutability": "nonpayable",
+         "inputs": [{"name": "from", "type": "address"}, {"name": "to", "type": "address"},
+                    {"name": "tokenId", "type": "uint256"}, {"name": "data", "type": "bytes"}],
+         "outputs": []},
+        {"type": "function", "name": "safeTransferFrom", "stateMutability": "view",
+         "inputs": [{"name": "tokenId", "type": "uint256"}, {"name": "data", "type": "bytes"}],
+         "outputs": [{"name": "", "type": "bool"}]}
+    ]"#;
+
+    #[test]
+    fn test_disambiguate_suffixes_in_abi_order() {
+        let names = ["Transfer", "Approval", "Transfer", "Approval", "Transfer"];
+        assert_eq!(
+            disambiguate(names.into_iter()),
+            ["Transfer", "Approval", "Transfer1", "Approval1", "Transfer2"]
+        );
+    }
+
+    #[test]
+    fn test_overloads_are_named_like_graph_cli() {
+        let abi = AbiParser::parse(ERC721).unwrap();
+
+        let events: Vec<_> = abi.events.iter().map(|e| e.alias.as_str()).collect();
+        assert_eq!(events, ["Approval", "Approval1"]);
+
+        // Each group counts from the unsuffixed name
+        let functions: Vec<_> = abi
+            .functions
+            .iter()
+            .map(|f| (f.signature.as_str(), f.is_bound(), f.alias.as_str()))
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("safeTransferFrom(address,address,uint256)", false, "safeTransferFrom"),
+                ("safeTransferFrom(uint256)", true, "safeTransferFrom"),
+                ("safeTransferFrom(address,address,uint256,bytes)", false, "safeTransferFrom1"),
+                ("safeTransferFrom(uint256,bytes)", true, "safeTransferFrom1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_function_by_signature_or_name() {
+        let abi = AbiParser::parse(ERC721).unwrap();
+        let find = |function: &str| abi.find_function(function).map(|f| f.alias.as_str());
+
+        assert_eq!(find("safeTransferFrom(address,address,uint256,bytes)"), Some("safeTransferFrom1"));
+        assert_eq!(find("safeTransferFrom( address, address, uint256 )"), Some("safeTransferFrom"));
+        assert_eq!(find("safeTransferFrom(uint256,bytes)"), Some("safeTransferFrom1"));
+        assert_eq!(
+            abi.find_function("safeTransferFrom").map(|f| f.signature.as_str()),
+            Some("safeTransferFrom(address,address,uint256)")
+        );
+        assert_eq!(find("safeTransferFrom(address,address)"), None);
+        assert_eq!(find("transferFrom"), None);
+    }
+
+    #[test]
+    fn test_find_event_ignores_indexed_and_whitespace() {
+        let abi = AbiParser::parse(ERC721).unwrap();
+        let find = |signature: &str| abi.find_event(signature).map(|e| e.alias.as_str());
+
+        assert_eq!(
+            find("Approval(indexed address,indexed address,indexed uint256,bytes)"),
+            Some("Approval1")
+        );
+        assert_eq!(find("Approval(indexed address, indexed address, indexed uint256)"), Some("Approval"));
+        assert_eq!(find("Approval(address,address,uint256)"), Some("Approval"));
+        assert_eq!(find("Approval(address,address)"), None);
+        assert_eq!(
+            normalize_signature("Transfer(indexed address from, uint256\tvalue)"),
+            "Transfer(addressfrom,uint256value)"
+        );
+    }
+
+    #[test]
+    fn test_overloads_generate_distinct_items() {
+        let code = AbiParser::parse(ERC721)
+            .unwrap()
+            .generate_rust_with_call_handlers(
+                "ERC721",
+                &["safeTransferFrom(address,address,uint256)", "safeTransferFrom(address,address,uint256,bytes)"],
+            );
+
+        for item in [
+            "pub struct ApprovalParams",
+            "pub struct Approval1Params",
+            "pub type SafeTransferFromCall",
+            "pub type SafeTransferFrom1Call",
+            "pub fn safe_transfer_from(",
+            "pub fn safe_transfer_from1(",
+        ] {
+            assert_eq!(code.matches(item).count(), 1, "{}", item);
+        }
+        assert!(code.contains("/// Overload of `Approval`, generated as `Approval1`."));
+    }
+}
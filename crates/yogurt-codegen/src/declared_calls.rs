@@ -0,0 +1,332 @@
+//! Accessors for declared eth_calls, the `calls:` of event handlers.
+//!
+//! graph-node runs the declared calls in parallel before the handler and
+//! caches their results, so the same call made from the handler is answered
+//! from the cache. Each accessor makes that call through the generated
+//! contract binding, so its result is typed after the ABI.
+
+use crate::abi::{binding_return_type, is_hashed_topic, to_abi_type, to_snake_case, AbiParser, ParsedEvent};
+use crate::error::{CodegenError, Result};
+use crate::manifest::{CallArg, DeclaredCall, EventHandler};
+
+/// The declared calls of one event handler.
+pub(crate) struct HandlerCalls {
+    pub(crate) handler: String,
+    event_type: String,
+    accessors: Vec<Accessor>,
+}
+
+/// A declared call, resolved against the ABIs of its data source.
+struct Accessor {
+    label: String,
+    expr: String,
+    method: String,
+    contract: String,
+    binding_method: String,
+    address: String,
+    args: Vec<String>,
+    return_type: String,
+}
+
+/// Validate the `calls` of an event handler against the event it handles
+/// and the ABIs of its data source or template.
+pub(crate) fn resolve(
+    data_source: &str,
+    handler: &EventHandler,
+    event: &ParsedEvent,
+    abis: &[&(String, AbiParser)],
+) -> Result<HandlerCalls> {
+    let mut accessors = Vec::new();
+
+    for call in handler.declared_calls()? {
+        let invalid = |message: String| {
+            CodegenError::InvalidManifest(format!(
+                "event handler `{}`, call `{}`: {}",
+                handler.handler, call.label, message
+            ))
+        };
+
+        let abi = abis
+            .iter()
+            .find(|(name, _)| *name == call.contract)
+            .map(|(_, abi)| abi)
+            .ok_or_else(|| invalid(format!("`{}` is not an ABI of data source `{}`", call.contract, data_source)))?;
+
+        let address = event_arg(event, &call.address).map_err(&invalid)?;
+        if address.1 != "address" {
+            return Err(invalid(format!("`{}` is a `{}`, not an address", call.address, address.1)));
+        }
+
+        let args = call
+            .args
+            .iter()
+            .map(|arg| event_arg(event, arg))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(&invalid)?;
+
+        let func = find_function(abi, &call, &args).map_err(&invalid)?;
+
+        accessors.push(Accessor {
+            label: call.label.clone(),
+            expr: handler.calls[&call.label].clone(),
+            method: label_to_method(&call.label),
+            contract: call.contract.clone(),
+            binding_method: to_snake_case(&func.alias),
+            address: address.0,
+            args: args.into_iter().map(|(expr, _)| expr).collect(),
+            return_type: binding_return_type(func),
+        });
+    }
+
+    Ok(HandlerCalls {
+        handler: handler.handler.clone(),
+        event_type: format!("{}Event", event.alias),
+        accessors,
+    })
+}
+
+/// The Rust expression reading an event value, and its ABI type.
+fn event_arg(event: &ParsedEvent, arg: &CallArg) -> std::result::Result<(String, String), String> {
+    match arg {
+        CallArg::Address => Ok(("self.event.address.clone()".to_string(), "address".to_string())),
+        CallArg::Param(name) => {
+            let input = event
+                .inputs
+                .iter()
+                .find(|i| i.name == *name)
+                .ok_or_else(|| format!("event `{}` has no parameter `{}`", event.signature, name))?;
+            let abi_type = if is_hashed_topic(input) {
+                "bytes32".to_string()
+            } else {
+                to_abi_type(&input.solidity_type, &input.components)
+            };
+            Ok((format!("self.event.params.{}.clone()", to_snake_case(name)), abi_type))
+        }
+    }
+}
+
+/// Find the view or pure function a call names, by its name and argument
+/// types.
+fn find_function<'a>(
+    abi: &'a AbiParser,
+    call: &DeclaredCall,
+    args: &[(String, String)],
+) -> std::result::Result<&'a crate::abi::ParsedFunction, String> {
+    let named: Vec<_> = abi.functions.iter().filter(|f| f.name == call.function).collect();
+    if named.is_empty() {
+        return Err(format!("`{}` is not a function of ABI `{}`", call.function, call.contract));
+    }
+
+    let matching: Vec<_> = named
+        .into_iter()
+        .filter(|f| {
+            f.inputs.len() == args.len()
+                && f.inputs
+                    .iter()
+                    .zip(args)
+                    .all(|(input, (_, ty))| to_abi_type(&input.solidity_type, &input.components) == *ty)
+        })
+        .collect();
+
+    match matching.as_slice() {
+        [func] if func.is_bound() => Ok(func),
+        [func] => Err(format!("`{}` is not a view or pure function", func.signature)),
+        [] => Err(format!(
+            "no `{}` of ABI `{}` takes ({})",
+            call.function,
+            call.contract,
+            args.iter().map(|(_, ty)| ty.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+        _ => Err(format!("`{}` is ambiguous in ABI `{}`", call.function, call.contract)),
+    }
+}
+
+/// `ERC20.balanceOf` becomes `erc20_balance_of`.
+fn label_to_method(label: &str) -> String {
+    label
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            if part.chars().all(|c| !c.is_ascii_lowercase()) {
+                part.to_ascii_lowercase()
+            } else {
+                to_snake_case(part)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Generate the calls module, with a `<Handler>Calls` struct per handler.
+pub(crate) fn generate(handlers: &[HandlerCalls]) -> String {
+    let mut code = String::from(
+        "//! Auto-generated accessors for declared eth_calls — do not edit\n\n\
+         #[allow(unused_imports)]\n\
+         use alloc::string::String;\n\
+         #[allow(unused_imports)]\n\
+         use alloc::vec::Vec;\n\
+         use yogurt_runtime::prelude::*;\n\
+         use super::*;\n\n",
+    );
+
+    for handler in handlers {
+        let struct_name = format!("{}Calls", crate::to_pascal_case(&handler.handler));
+        code.push_str(&format!(
+            "/// Declared calls of `{handler}`, which graph-node prefetches before\n\
+             /// the handler runs.\n\
+             pub struct {struct_name}<'a> {{\n\
+                 event: &'a {event_type},\n\
+             }}\n\n\
+             impl<'a> {struct_name}<'a> {{\n\
+                 pub fn new(event: &'a {event_type}) -> Self {{\n\
+                     Self {{ event }}\n\
+                 }}\n",
+            handler = handler.handler,
+            struct_name = struct_name,
+            event_type = handler.event_type,
+        ));
+
+        for accessor in &handler.accessors {
+            code.push_str(&format!(
+                "\n    /// `{label}: {expr}`\n\
+                 pub fn {method}(&self) -> Result<{return_type}, CallError<{contract_struct}Error>> {{\n\
+                     {contract_struct}::bind({address}).try_{binding_method}({args})\n\
+                 }}\n",
+                label = accessor.label,
+                expr = accessor.expr,
+                method = accessor.method,
+                return_type = accessor.return_type,
+                contract_struct = crate::to_pascal_case(&accessor.contract),
+                address = accessor.address,
+                binding_method = accessor.binding_method,
+                args = accessor.args.join(", "),
+            ));
+        }
+
+        code.push_str("}\n\n");
+    }
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERC20: &str = r#"[
+        {"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+            {"name": "from", "type": "address", "indexed": true},
+            {"name": "to", "type": "address", "indexed": true},
+            {"name": "value", "type": "uint256", "indexed": false}
+        ]},
+        {"type": "function", "name": "balanceOf", "stateMutability": "view",
+         "inputs": [{"name": "owner", "type": "address"}],
+         "outputs": [{"name": "", "type": "uint256"}]},
+        {"type": "function", "name": "balanceOf", "stateMutability": "view",
+         "inputs": [{"name": "owner", "type": "address"}, {"name": "id", "type": "uint256"}],
+         "outputs": [{"name": "", "type": "uint256"}]},
+        {"type": "function", "name": "transfer", "stateMutability": "nonpayable",
+         "inputs": [{"name": "to", "type": "address"}, {"name": "value", "type": "uint256"}],
+         "outputs": [{"name": "", "type": "bool"}]},
+        {"type": "function", "name": "version", "stateMutability": "view",
+         "inputs": [], "outputs": [{"name": "", "type": "string"}]},
+        {"type": "function", "name": "version", "stateMutability": "pure",
+         "inputs": [], "outputs": [{"name": "", "type": "uint8"}]}
+    ]"#;
+
+    fn resolve_calls(calls: &str) -> Result<HandlerCalls> {
+        let abis = [("ERC20".to_string(), AbiParser::parse(ERC20).unwrap())];
+        let handler: EventHandler = serde_yaml::from_str(&format!(
+            "event: Transfer(indexed address,indexed address,uint256)\n\
+             handler: handleTransfer\n\
+             calls:\n{}",
+            calls
+        ))
+        .unwrap();
+        let event = abis[0].1.find_event(&handler.event).unwrap();
+        resolve("Token", &handler, event, &abis.iter().collect::<Vec<_>>())
+    }
+
+    fn error(calls: &str) -> String {
+        resolve_calls(calls).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_resolve_picks_overload_by_argument_types() {
+        let calls = resolve_calls(
+            "  ERC20.balanceOf: ERC20[event.address].balanceOf(event.params.to)\n\
+             \x20 ERC20.balanceOfId: ERC20[event.params.from].balanceOf(event.params.to, event.params.value)\n",
+        )
+        .unwrap();
+
+        assert_eq!(calls.handler, "handleTransfer");
+        assert_eq!(calls.event_type, "TransferEvent");
+        let [single, overload] = &calls.accessors[..] else {
+            panic!("expected two accessors");
+        };
+        assert_eq!(single.method, "erc20_balance_of");
+        assert_eq!(single.binding_method, "balance_of");
+        assert_eq!(single.address, "self.event.address.clone()");
+        assert_eq!(single.args, ["self.event.params.to.clone()"]);
+        assert_eq!(single.return_type, "BigInt");
+        assert_eq!(overload.method, "erc20_balance_of_id");
+        assert_eq!(overload.binding_method, "balance_of1");
+        assert_eq!(overload.address, "self.event.params.from.clone()");
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_abi() {
+        assert_eq!(
+            error("  Pair.getReserves: Pair[event.address].getReserves()\n"),
+            "Invalid manifest: event handler `handleTransfer`, call `Pair.getReserves`: \
+             `Pair` is not an ABI of data source `Token`"
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_non_address_contract() {
+        assert_eq!(
+            error("  ERC20.balanceOf: ERC20[event.params.value].balanceOf(event.params.to)\n"),
+            "Invalid manifest: event handler `handleTransfer`, call `ERC20.balanceOf`: \
+             `event.params.value` is a `uint256`, not an address"
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_event_param() {
+        assert!(error("  ERC20.balanceOf: ERC20[event.address].balanceOf(event.params.owner)\n")
+            .ends_with("event `Transfer(address,address,uint256)` has no parameter `owner`"));
+    }
+
+    #[test]
+    fn test_find_function_reject_paths() {
+        assert!(error("  ERC20.mint: ERC20[event.address].mint(event.params.to)\n")
+            .ends_with("`mint` is not a function of ABI `ERC20`"));
+        assert!(error("  ERC20.balanceOf: ERC20[event.address].balanceOf(event.params.value)\n")
+            .ends_with("no `balanceOf` of ABI `ERC20` takes (uint256)"));
+        assert!(error("  ERC20.transfer: ERC20[event.address].transfer(event.params.to, event.params.value)\n")
+            .ends_with("`transfer(address,uint256)` is not a view or pure function"));
+        // Two `version()` entries, as in ABIs merged from two releases
+        assert!(error("  ERC20.version: ERC20[event.address].version()\n")
+            .ends_with("`version` is ambiguous in ABI `ERC20`"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_unparseable_expression() {
+        assert_eq!(
+            error("  ERC20.balanceOf: ERC20.balanceOf(event.params.to)\n"),
+            "Invalid manifest: event handler `handleTransfer`, call `ERC20.balanceOf`: \
+             expected `Contract[event.address].function(event.params.x, ...)`, \
+             found `ERC20.balanceOf(event.params.to)`"
+        );
+    }
+
+    #[test]
+    fn test_label_to_method() {
+        assert_eq!(label_to_method("ERC20.balanceOf"), "erc20_balance_of");
+        assert_eq!(label_to_method("Pair.getReserves"), "pair_get_reserves");
+        assert_eq!(label_to_method("token0"), "token0");
+        assert_eq!(label_to_method("USDC balance"), "usdc_balance");
+        assert_eq!(label_to_method("pool.fee_tier"), "pool_fee_tier");
+    }
+}
@@ -0,0 +1,194 @@
+//! The dispatch table of a subgraph's handlers, for native tests.
+//!
+//! Codegen emits a `dispatch_table()` function building a
+//! `testing::DispatchTable` of the manifest's data sources and templates,
+//! which calls the handler functions in `crate::mappings`, where
+//! `yogurt init` puts them. `testing::Chain` feeds blocks of raw logs and
+//! calls through it. The handler functions' signatures pick the types logs
+//! and calls decode into, so the table needs no ABI types of its own.
+
+use crate::abi::to_snake_case;
+use crate::manifest::{DataSource, DataSourceTemplate, Mapping};
+
+/// Generate the dispatch module, with the `dispatch_table()` function.
+pub(crate) fn generate(data_sources: &[DataSource], templates: &[&DataSourceTemplate]) -> String {
+    let data_sources: String = data_sources
+        .iter()
+        .map(|ds| {
+            let address = if ds.source.address.is_empty() {
+                "None".to_string()
+            } else {
+                format!(
+                    "Some(yogurt_runtime::prelude::Address::from_string(\"{address}\")\n\
+                     .expect(\"data source `{name}` has an invalid address\"))",
+                    address = ds.source.address,
+                    name = ds.name,
+                )
+            };
+            generate_spec(&ds.name, &address, ds.source.start_block.unwrap_or(0), &ds.mapping)
+        })
+        .collect();
+    let templates: String = templates
+        .iter()
+        .map(|t| generate_spec(&t.name, "None", 0, &t.mapping))
+        .collect();
+
+    format!(
+        "//! Auto-generated dispatch table of the manifest's handlers — do not edit\n\n\
+         /// The manifest's data sources and templates as a\n\
+         /// `yogurt_runtime::testing::DispatchTable`, for `testing::Chain`.\n\
+         ///\n\
+         /// Runs the handler functions of `crate::mappings`, which are named\n\
+         /// after the manifest's handlers in snake case:\n\
+         ///\n\
+         /// ```ignore\n\
+         /// let mut chain = Chain::new(dispatch_table());\n\
+         /// ```\n\
+         #[allow(dead_code)]\n\
+         pub fn dispatch_table() -> yogurt_runtime::testing::DispatchTable {{\n\
+         yogurt_runtime::testing::DispatchTable {{\n\
+         data_sources: alloc::vec![\n\
+         {data_sources}\
+         ],\n\
+         templates: alloc::vec![\n\
+         {templates}\
+         ],\n\
+         }}\n\
+         }}\n",
+        data_sources = data_sources,
+        templates = templates,
+    )
+}
+
+/// A `DataSourceSpec` with the handlers of a data source or template.
+fn generate_spec(name: &str, address: &str, start_block: u64, mapping: &Mapping) -> String {
+    let events: String = mapping
+        .event_handlers
+        .iter()
+        .map(|eh| {
+            let filter = if eh.has_topic_filters() {
+                format!("super::{}_TOPIC_FILTER", to_snake_case(&eh.handler).to_uppercase())
+            } else {
+                "yogurt_runtime::prelude::TopicFilter::ANY".to_string()
+            };
+            format!(
+                "yogurt_runtime::testing::EventHandlerSpec {{\n\
+                 handler: \"{handler}\",\n\
+                 filter: {filter},\n\
+                 run: |log, block, transaction| {{\n\
+                 yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::{function})\n\
+                 }},\n\
+                 }},\n",
+                handler = eh.handler,
+                filter = filter,
+                function = to_snake_case(&eh.handler),
+            )
+        })
+        .collect();
+    let calls: String = mapping
+        .call_handlers
+        .iter()
+        .map(|ch| {
+            format!(
+                "yogurt_runtime::testing::CallHandlerSpec {{\n\
+                 handler: \"{handler}\",\n\
+                 run: |call, block| yogurt_runtime::testing::run_call(call, block, crate::mappings::{function}),\n\
+                 }},\n",
+                handler = ch.handler,
+                function = to_snake_case(&ch.handler),
+            )
+        })
+        .collect();
+    let blocks: String = mapping
+        .block_handlers
+        .iter()
+        .map(|bh| {
+            format!(
+                "yogurt_runtime::testing::BlockHandlerSpec {{\n\
+                 handler: \"{handler}\",\n\
+                 filter: super::{constant}_BLOCK_FILTER,\n\
+                 run: |block| yogurt_runtime::testing::run_block(block, crate::mappings::{function}),\n\
+                 }},\n",
+                handler = bh.handler,
+                constant = to_snake_case(&bh.handler).to_uppercase(),
+                function = to_snake_case(&bh.handler),
+            )
+        })
+        .collect();
+
+    format!(
+        "yogurt_runtime::testing::DataSourceSpec {{\n\
+         name: \"{name}\",\n\
+         address: {address},\n\
+         start_block: {start_block},\n\
+         events: alloc::vec![\n\
+         {events}\
+         ],\n\
+         calls: alloc::vec![\n\
+         {calls}\
+         ],\n\
+         blocks: alloc::vec![\n\
+         {blocks}\
+         ],\n\
+         }},\n",
+        name = name,
+        address = address,
+        start_block = start_block,
+        events = events,
+        calls = calls,
+        blocks = blocks,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA_SOURCE: &str = r#"
+kind: ethereum
+name: Token
+network: mainnet
+source:
+  address: "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f"
+  abi: ERC20
+  startBlock: 100
+mapping:
+  kind: ethereum/events
+  apiVersion: 0.0.7
+  entities: [Transfer]
+  abis:
+    - name: ERC20
+      file: ./abis/ERC20.json
+  eventHandlers:
+    - event: Transfer(indexed address,indexed address,uint256)
+      handler: handleTransfer
+    - event: Transfer(indexed address,indexed address,uint256)
+      handler: handleMint
+      topic1: ["0x0000000000000000000000000000000000000000"]
+  callHandlers:
+    - function: transfer(address,uint256)
+      handler: handleTransferCall
+  blockHandlers:
+    - handler: handleBlock
+      filter:
+        kind: polling
+        every: 10
+  file: ./build/subgraph.wasm
+"#;
+
+    #[test]
+    fn test_generate_refers_to_generated_items_relatively() {
+        let data_source: DataSource = serde_yaml::from_str(DATA_SOURCE).unwrap();
+        let code = generate(&[data_source], &[]);
+
+        assert!(code.contains("pub fn dispatch_table() -> yogurt_runtime::testing::DispatchTable {"));
+        assert!(code.contains("filter: yogurt_runtime::prelude::TopicFilter::ANY,"));
+        assert!(code.contains("filter: super::HANDLE_MINT_TOPIC_FILTER,"));
+        assert!(code.contains("filter: super::HANDLE_BLOCK_BLOCK_FILTER,"));
+        for function in ["handle_transfer", "handle_mint", "handle_transfer_call", "handle_block"] {
+            assert!(code.contains(&format!("crate::mappings::{})", function)), "{}", function);
+        }
+        assert!(code.contains("start_block: 100,"));
+        assert!(!code.contains("$crate"));
+    }
+}
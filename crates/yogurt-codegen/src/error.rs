@@ -27,6 +27,9 @@ pub enum CodegenError {
     #[error("Invalid manifest: {0}")]
     InvalidManifest(String),
 
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+
     #[error("Unsupported type: {0}")]
     UnsupportedType(String),
 }
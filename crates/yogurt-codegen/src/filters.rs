@@ -0,0 +1,345 @@
+//! Filters of event and block handlers.
+//!
+//! graph-node only triggers an event handler with `topic1` to `topic3` on
+//! logs whose indexed parameters take one of the listed values, and a block
+//! handler with a `filter` on some blocks only. Codegen emits each as a
+//! constant named after the handler, which native tests dispatch through.
+
+use crate::abi::{is_hashed_topic, to_snake_case, ParsedEvent};
+use crate::error::{CodegenError, Result};
+use crate::manifest::{BlockFilter, BlockHandler, EventHandler};
+
+/// The topic filters of one event handler, padded to 32-byte topics.
+#[derive(PartialEq)]
+pub(crate) struct EventFilter {
+    handler: String,
+    signature: String,
+    topics: [Option<Vec<[u8; 32]>>; 3],
+}
+
+/// Validate the topic filters of an event handler against the indexed
+/// parameters of its event.
+pub(crate) fn resolve_event(handler: &EventHandler, event: &ParsedEvent) -> Result<EventFilter> {
+    let indexed: Vec<_> = event.inputs.iter().filter(|i| i.indexed).collect();
+    let mut topics = [None, None, None];
+
+    for (i, values) in handler.topic_filters()?.into_iter().enumerate() {
+        let Some(values) = values else {
+            continue;
+        };
+        let invalid = |message: String| {
+            CodegenError::InvalidManifest(format!(
+                "event handler `{}`, topic{}: {}",
+                handler.handler,
+                i + 1,
+                message
+            ))
+        };
+
+        let input = indexed.get(i).ok_or_else(|| {
+            invalid(format!(
+                "`{}` has {} indexed parameter{}",
+                event.signature,
+                indexed.len(),
+                if indexed.len() == 1 { "" } else { "s" }
+            ))
+        })?;
+
+        let solidity_type = if is_hashed_topic(input) {
+            "bytes32"
+        } else {
+            input.solidity_type.as_str()
+        };
+        topics[i] = Some(
+            values
+                .iter()
+                .map(|value| {
+                    check_length(solidity_type, value)
+                        .map(|()| pad_topic(value))
+                        .map_err(|e| invalid(format!("`{}` ({}): {}", input.name, input.solidity_type, e)))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        );
+    }
+
+    Ok(EventFilter {
+        handler: handler.handler.clone(),
+        signature: event.signature.clone(),
+        topics,
+    })
+}
+
+/// Add the filter of an event handler, once per handler name.
+///
+/// A handler shared by several data sources or templates gets one constant,
+/// so it must have the same filter in each.
+pub(crate) fn push_event(filters: &mut Vec<EventFilter>, filter: EventFilter) -> Result<()> {
+    match filters.iter().find(|f| f.handler == filter.handler) {
+        Some(f) if *f == filter => Ok(()),
+        Some(_) => Err(CodegenError::InvalidManifest(format!(
+            "event handler `{}` has different topic filters in different data sources; \
+             give each filter its own handler",
+            filter.handler
+        ))),
+        None => {
+            filters.push(filter);
+            Ok(())
+        }
+    }
+}
+
+/// Check that a value fits the type of the indexed parameter once
+/// graph-node left-pads it to 32 bytes.
+fn check_length(solidity_type: &str, value: &[u8]) -> std::result::Result<(), String> {
+    let hex = format!("0x{}", value.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+    let significant = value.iter().skip_while(|&&b| b == 0).count();
+
+    match solidity_type {
+        "address" if value.len() == 20 || (value.len() == 32 && significant <= 20) => Ok(()),
+        "address" => Err(format!("`{}` is not a 20-byte address", hex)),
+        "bool" if significant <= 1 && value.last().copied().unwrap_or(0) <= 1 => Ok(()),
+        "bool" => Err(format!("`{}` is not 0x00 or 0x01", hex)),
+        // bytesN is right-padded, so the left padding graph-node applies
+        // would change it
+        ty if ty.starts_with("bytes") && value.len() != 32 => Err(format!(
+            "`{}` is {} bytes, but a {} topic must be given as the full 32 bytes",
+            hex,
+            value.len(),
+            ty
+        )),
+        ty if ty.starts_with("uint") || (ty.starts_with("int") && value.len() < 32) => {
+            let bits: usize = ty.trim_start_matches(|c: char| c.is_ascii_alphabetic()).parse().unwrap_or(256);
+            if significant * 8 <= bits {
+                Ok(())
+            } else {
+                Err(format!("`{}` does not fit in {} bits", hex, bits))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+fn pad_topic(value: &[u8]) -> [u8; 32] {
+    let mut topic = [0u8; 32];
+    topic[32 - value.len()..].copy_from_slice(value);
+    topic
+}
+
+/// Collect the block handlers of all mappings, one per handler name.
+///
+/// A handler shared by several data sources gets one constant, so it must
+/// have the same filter in each.
+pub(crate) fn block_handlers<'a>(
+    mappings: impl Iterator<Item = &'a [BlockHandler]>,
+) -> Result<Vec<&'a BlockHandler>> {
+    let mut handlers: Vec<&BlockHandler> = Vec::new();
+    for bh in mappings.flatten() {
+        match handlers.iter().find(|h| h.handler == bh.handler) {
+            Some(h) if h.filter == bh.filter => {}
+            Some(_) => {
+                return Err(CodegenError::InvalidManifest(format!(
+                    "block handler `{}` has different filters in different data sources; \
+                     give each filter its own handler",
+                    bh.handler
+                )))
+            }
+            None => handlers.push(bh),
+        }
+    }
+    Ok(handlers)
+}
+
+/// Generate the filters module, with a `<HANDLER>_TOPIC_FILTER` constant per
+/// filtered event handler and a `<HANDLER>_BLOCK_FILTER` per block handler.
+pub(crate) fn generate(event_filters: &[EventFilter], block_handlers: &[&BlockHandler]) -> String {
+    let mut code = String::from(
+        "//! Auto-generated filters of event and block handlers — do not edit\n\n\
+         use yogurt_runtime::prelude::*;\n\n",
+    );
+
+    for filter in event_filters {
+        let topics: Vec<String> = filter
+            .topics
+            .iter()
+            .enumerate()
+            .map(|(i, values)| match values {
+                None => format!("    topic{}: None,\n", i + 1),
+                Some(values) => format!(
+                    "    topic{}: Some(&[\n{}    ]),\n",
+                    i + 1,
+                    values
+                        .iter()
+                        .map(|topic| format!(
+                            "        [{}],\n",
+                            topic.iter().map(|b| format!("{:#04x}", b)).collect::<Vec<_>>().join(", ")
+                        ))
+                        .collect::<String>()
+                ),
+            })
+            .collect();
+
+        code.push_str(&format!(
+            "/// Topic filters of `{handler}` on `{signature}`.\n\
+             pub const {name}_TOPIC_FILTER: TopicFilter = TopicFilter {{\n\
+             {topics}\
+             }};\n\n",
+            handler = filter.handler,
+            signature = filter.signature,
+            name = to_snake_case(&filter.handler).to_uppercase(),
+            topics = topics.concat(),
+        ));
+    }
+
+    for bh in block_handlers {
+        let (doc, value) = match bh.filter {
+            None => ("on every block from the start block".to_string(), "BlockFilter::All".to_string()),
+            Some(BlockFilter::Call) => (
+                "on blocks with a call to the data source's contract".to_string(),
+                "BlockFilter::Call".to_string(),
+            ),
+            Some(BlockFilter::Once) => ("on the start block only".to_string(), "BlockFilter::Once".to_string()),
+            Some(BlockFilter::Polling { every }) => (
+                format!("every {} blocks from the start block", every),
+                format!("BlockFilter::Polling {{ every: {} }}", every),
+            ),
+        };
+        code.push_str(&format!(
+            "/// `{handler}` runs {doc}.\n\
+             pub const {name}_BLOCK_FILTER: BlockFilter = {value};\n\n",
+            handler = bh.handler,
+            doc = doc,
+            name = to_snake_case(&bh.handler).to_uppercase(),
+            value = value,
+        ));
+    }
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::AbiParser;
+
+    const ABI: &str = r#"[
+        {"type": "event", "name": "Transfer", "anonymous": false, "inputs": [
+            {"name": "from", "type": "address", "indexed": true},
+            {"name": "to", "type": "address", "indexed": true},
+            {"name": "value", "type": "uint256", "indexed": false}
+        ]},
+        {"type": "event", "name": "Tagged", "anonymous": false, "inputs": [
+            {"name": "tag", "type": "bytes32", "indexed": true},
+            {"name": "level", "type": "uint8", "indexed": true},
+            {"name": "name", "type": "string", "indexed": true}
+        ]}
+    ]"#;
+
+    fn resolve(event: &str, topics: &str) -> Result<EventFilter> {
+        let abi = AbiParser::parse(ABI).unwrap();
+        let handler: EventHandler = serde_yaml::from_str(&format!(
+            "event: {}\nhandler: handleEvent\n{}",
+            event, topics
+        ))
+        .unwrap();
+        resolve_event(&handler, abi.find_event(event).unwrap())
+    }
+
+    fn address(byte: u8) -> String {
+        format!("0x{}", format!("{:02x}", byte).repeat(20))
+    }
+
+    #[test]
+    fn test_resolve_event_pads_topics() {
+        let filter = resolve(
+            "Transfer(indexed address,indexed address,uint256)",
+            &format!("topic2: ['{}']\n", address(0x22)),
+        )
+        .unwrap();
+
+        let mut topic = [0u8; 32];
+        topic[12..].copy_from_slice(&[0x22; 20]);
+        assert_eq!(filter.signature, "Transfer(address,address,uint256)");
+        assert_eq!(filter.topics, [None, Some(vec![topic]), None]);
+    }
+
+    #[test]
+    fn test_resolve_event_rejects_filter_on_non_indexed_param() {
+        let err = resolve(
+            "Transfer(indexed address,indexed address,uint256)",
+            "topic3: ['0x01']\n",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Invalid manifest: event handler `handleEvent`, topic3: \
+             `Transfer(address,address,uint256)` has 2 indexed parameters"
+        );
+    }
+
+    #[test]
+    fn test_resolve_event_rejects_values_that_do_not_fit() {
+        let transfer = "Transfer(indexed address,indexed address,uint256)";
+        let err = resolve(transfer, &format!("topic1: ['{}ff']\n", address(0x11))).err().unwrap();
+        assert!(err.to_string().ends_with(&format!(
+            "topic1: `from` (address): `{}ff` is not a 20-byte address",
+            address(0x11)
+        )));
+
+        let tagged = "Tagged(indexed bytes32,indexed uint8,indexed string)";
+        let err = resolve(tagged, "topic1: ['0x1234']\n").err().unwrap();
+        assert!(err.to_string().ends_with(
+            "topic1: `tag` (bytes32): `0x1234` is 2 bytes, but a bytes32 topic must be given as the full 32 bytes"
+        ));
+        let err = resolve(tagged, "topic2: ['0x0100']\n").err().unwrap();
+        assert!(err.to_string().ends_with("topic2: `level` (uint8): `0x0100` does not fit in 8 bits"));
+
+        // Indexed strings are hashed, so their topic is any 32-byte value
+        let hash = format!("0x{}", "ab".repeat(32));
+        assert!(resolve(tagged, &format!("topic3: ['{}']\n", hash)).is_ok());
+        assert!(resolve(tagged, "topic3: ['0xab']\n").is_err());
+    }
+
+    #[test]
+    fn test_push_event_rejects_conflicting_filters() {
+        let transfer = "Transfer(indexed address,indexed address,uint256)";
+        let topic1 = |byte| format!("topic1: ['{}']\n", address(byte));
+        let mut filters = Vec::new();
+        push_event(&mut filters, resolve(transfer, &topic1(0x01)).unwrap()).unwrap();
+        push_event(&mut filters, resolve(transfer, &topic1(0x01)).unwrap()).unwrap();
+        assert_eq!(filters.len(), 1);
+
+        let err = push_event(&mut filters, resolve(transfer, &topic1(0x02)).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("`handleEvent` has different topic filters"));
+    }
+
+    fn block_handler(yaml: &str) -> BlockHandler {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_block_handlers_collects_each_handler_once() {
+        let pool = [
+            block_handler("handler: handleBlock"),
+            block_handler("{handler: handleOnce, filter: {kind: once}}"),
+        ];
+        let pair = [block_handler("handler: handleBlock")];
+        let handlers = block_handlers([&pool[..], &pair[..]].into_iter()).unwrap();
+        let names: Vec<_> = handlers.iter().map(|bh| bh.handler.as_str()).collect();
+        assert_eq!(names, ["handleBlock", "handleOnce"]);
+    }
+
+    #[test]
+    fn test_block_handlers_rejects_handler_with_different_filters() {
+        let pool = [block_handler("{handler: handlePoll, filter: {kind: polling, every: 10}}")];
+        for other in ["handler: handlePoll", "{handler: handlePoll, filter: {kind: polling, every: 20}}"] {
+            let pair = [block_handler(other)];
+            let err = block_handlers([&pool[..], &pair[..]].into_iter()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Invalid manifest: block handler `handlePoll` has different filters in different data sources; \
+                 give each filter its own handler"
+            );
+        }
+    }
+}
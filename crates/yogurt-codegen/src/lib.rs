@@ -4,23 +4,45 @@
 //! to generate type-safe Rust code.
 
 mod abi;
+mod declared_calls;
+mod dispatch;
 mod error;
 mod manifest;
 mod schema;
+mod filters;
 
 pub use abi::AbiParser;
 pub use error::{CodegenError, Result};
-pub use manifest::{DataSource, Manifest};
+pub use manifest::{
+    BlockFilter, CallArg, ContextField, ContextType, DataSource, DeclaredCall, Manifest,
+    BLOCK_FILTERS_SPEC_VERSION, DECLARED_CALLS_SPEC_VERSION, RUST_ABI_LANGUAGE,
+    TOPIC_FILTERS_SPEC_VERSION,
+};
 pub use schema::SchemaParser;
 
+use manifest::Mapping;
 use sha2::{Sha256, Digest};
 use std::fs;
 use std::path::Path;
 
 const HASH_FILE_NAME: &str = ".codegen-hash";
 
+/// A manifest handler and the generated type its trigger decodes into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerType {
+    pub handler: String,
+    /// The manifest's `event:` or `function:` signature, or `block` with
+    /// its filter.
+    pub signature: String,
+    /// Such as `TransferEvent`, or `SafeTransferFrom1Call` for an overload.
+    pub type_name: String,
+}
+
 /// Generate all Rust code for a subgraph.
-pub fn generate(manifest_path: &Path, output_dir: &Path) -> Result<()> {
+///
+/// Returns the generated type for each event, call and block handler of the
+/// data sources and templates, so overloaded names can be reported.
+pub fn generate(manifest_path: &Path, output_dir: &Path) -> Result<Vec<HandlerType>> {
     // Read and parse the manifest
     let manifest_content = fs::read_to_string(manifest_path)?;
     let manifest = Manifest::parse(&manifest_content)?;
@@ -33,41 +55,114 @@ pub fn generate(manifest_path: &Path, output_dir: &Path) -> Result<()> {
     {
         let schema_content = fs::read_to_string(&schema_path)?;
         let schema = SchemaParser::parse(&schema_content)?;
-        let schema_code = schema.generate_rust();
+        let schema_code = schema.generate_rust()?;
         fs::write(output_dir.join("schema.rs"), schema_code)?;
     }
 
-    // Parse ABIs and generate event/contract types
+    // Data sources and contract templates, whose handlers take ABI types
+    let mappings: Vec<(&str, &str, &Mapping)> = manifest
+        .data_sources
+        .iter()
+        .map(|ds| (ds.name.as_str(), ds.source.abi.as_str(), &ds.mapping))
+        .chain(
+            manifest
+                .templates
+                .iter()
+                .filter(|t| !t.kind.starts_with("file/"))
+                .map(|t| (t.name.as_str(), t.source.abi.as_str(), &t.mapping)),
+        )
+        .collect();
+
+    // Parse each ABI once, as several mappings may share it
+    let mut parsed_abis: Vec<(String, AbiParser)> = Vec::new();
+    for (_, _, mapping) in &mappings {
+        for abi in &mapping.abis {
+            if parsed_abis.iter().any(|(name, _)| *name == abi.name) {
+                continue;
+            }
+            if let Some(abi_path) = manifest_path.parent().map(|p| p.join(&abi.file)) {
+                let abi_content = fs::read_to_string(&abi_path)?;
+                parsed_abis.push((abi.name.clone(), AbiParser::parse(&abi_content)?));
+            }
+        }
+    }
+
     let mut abi_modules = Vec::new();
+    let mut handler_types = Vec::new();
+    let mut handler_calls: Vec<declared_calls::HandlerCalls> = Vec::new();
+    let mut event_filters = Vec::new();
 
-    for data_source in &manifest.data_sources {
-        // Collect call handler function names for this data source
-        let call_handler_functions: Vec<String> = data_source
-            .mapping
-            .call_handlers
+    // Resolve the handlers of each mapping against its ABIs
+    for (name, source_abi, mapping) in &mappings {
+        let abis: Vec<&(String, AbiParser)> = parsed_abis
             .iter()
-            .map(|ch| extract_function_name(&ch.function))
+            .filter(|(abi_name, _)| mapping.abis.iter().any(|abi| abi.name == *abi_name))
             .collect();
+        let Some((_, parsed_abi)) = abis.iter().find(|(abi_name, _)| abi_name == source_abi) else {
+            continue;
+        };
 
-        for abi in &data_source.mapping.abis {
-            if let Some(abi_path) = manifest_path.parent().map(|p| p.join(&abi.file)) {
-                let abi_content = fs::read_to_string(&abi_path)?;
-                let parsed_abi = AbiParser::parse(&abi_content)?;
-                let module_name = abi.name.to_lowercase();
-
-                // Convert to &str slice for the API
-                let func_refs: Vec<&str> = call_handler_functions
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect();
-
-                let abi_code = parsed_abi.generate_rust_with_call_handlers(&abi.name, &func_refs);
-                fs::write(output_dir.join(format!("{}.rs", module_name)), abi_code)?;
-                abi_modules.push(module_name);
+        for handler_type in resolve_handlers(source_abi, mapping, parsed_abi)? {
+            if !handler_types.contains(&handler_type) {
+                handler_types.push(handler_type);
+            }
+        }
+        // A handler shared by several mappings gets one accessor struct
+        for calls in resolve_declared_calls(&manifest, name, mapping, parsed_abi, &abis)? {
+            if !handler_calls.iter().any(|c| c.handler == calls.handler) {
+                handler_calls.push(calls);
             }
         }
+        for filter in resolve_event_filters(&manifest, mapping, parsed_abi)? {
+            filters::push_event(&mut event_filters, filter)?;
+        }
     }
 
+    // Generate event/contract types, with the call handler types of every
+    // mapping using the ABI
+    for (name, parsed_abi) in &parsed_abis {
+        // Call handler functions by signature, so overloads resolve
+        let mut func_refs: Vec<&str> = Vec::new();
+        for (_, _, mapping) in mappings.iter().filter(|(_, _, m)| m.abis.iter().any(|abi| abi.name == *name)) {
+            for ch in &mapping.call_handlers {
+                if !func_refs.contains(&ch.function.as_str()) {
+                    func_refs.push(&ch.function);
+                }
+            }
+        }
+
+        let module_name = name.to_lowercase();
+        let abi_code = parsed_abi.generate_rust_with_call_handlers(name, &func_refs);
+        fs::write(output_dir.join(format!("{}.rs", module_name)), abi_code)?;
+        abi_modules.push(module_name);
+    }
+
+    // Generate accessors for declared eth_calls
+    if !handler_calls.is_empty() {
+        fs::write(output_dir.join("calls.rs"), declared_calls::generate(&handler_calls))?;
+        abi_modules.push("calls".to_string());
+    }
+
+    // Generate event and block handler filter constants
+    let block_handlers = filters::block_handlers(
+        manifest
+            .data_sources
+            .iter()
+            .map(|ds| ds.mapping.block_handlers.as_slice())
+            .chain(manifest.templates.iter().map(|t| t.mapping.block_handlers.as_slice())),
+    )?;
+    if !event_filters.is_empty() || !block_handlers.is_empty() {
+        fs::write(output_dir.join("filters.rs"), filters::generate(&event_filters, &block_handlers))?;
+        abi_modules.push("filters".to_string());
+    }
+
+    // Generate the dispatch table of the handlers, for native tests
+    let contract_templates: Vec<_> = manifest.templates.iter().filter(|t| !t.kind.starts_with("file/")).collect();
+    fs::write(
+        output_dir.join("dispatch.rs"),
+        dispatch::generate(&manifest.data_sources, &contract_templates),
+    )?;
+
     // Generate templates if any exist
     let has_templates = !manifest.templates.is_empty();
     if has_templates {
@@ -82,7 +177,113 @@ pub fn generate(manifest_path: &Path, output_dir: &Path) -> Result<()> {
     // Store hash of inputs for freshness checking
     store_codegen_hash(manifest_path, output_dir)?;
 
-    Ok(())
+    Ok(handler_types)
+}
+
+/// Match the event and call handlers of a data source or template to the
+/// generated types of its source ABI.
+fn resolve_handlers(source_abi: &str, mapping: &Mapping, abi: &AbiParser) -> Result<Vec<HandlerType>> {
+    let mut handler_types = Vec::new();
+
+    for eh in &mapping.event_handlers {
+        let event = abi.find_event(&eh.event).ok_or_else(|| {
+            CodegenError::InvalidManifest(format!(
+                "event handler `{}`: `{}` is not an event of ABI `{}`",
+                eh.handler, eh.event, source_abi
+            ))
+        })?;
+        if event.anonymous {
+            return Err(CodegenError::InvalidManifest(format!(
+                "event handler `{}`: `{}` is anonymous, so graph-node cannot match it; \
+                 decode receipt logs with `{}Params::from_log` instead",
+                eh.handler, eh.event, event.alias
+            )));
+        }
+        handler_types.push(HandlerType {
+            handler: eh.handler.clone(),
+            signature: eh.event.clone(),
+            type_name: format!("{}Event", event.alias),
+        });
+    }
+
+    for ch in &mapping.call_handlers {
+        let func = abi.find_function(&ch.function).ok_or_else(|| {
+            CodegenError::InvalidManifest(format!(
+                "call handler `{}`: `{}` is not a function of ABI `{}`",
+                ch.handler, ch.function, source_abi
+            ))
+        })?;
+        handler_types.push(HandlerType {
+            handler: ch.handler.clone(),
+            signature: ch.function.clone(),
+            type_name: format!("{}Call", to_pascal_case(&func.alias)),
+        });
+    }
+
+    for bh in &mapping.block_handlers {
+        handler_types.push(HandlerType {
+            handler: bh.handler.clone(),
+            signature: match &bh.filter {
+                Some(filter) => format!("block ({})", filter),
+                None => "block".to_string(),
+            },
+            type_name: "Block".to_string(),
+        });
+    }
+
+    Ok(handler_types)
+}
+
+/// Validate the declared eth_calls of the event handlers of a data source or
+/// template.
+fn resolve_declared_calls(
+    manifest: &Manifest,
+    data_source: &str,
+    mapping: &Mapping,
+    abi: &AbiParser,
+    abis: &[&(String, AbiParser)],
+) -> Result<Vec<declared_calls::HandlerCalls>> {
+    let mut handler_calls = Vec::new();
+
+    for eh in mapping.event_handlers.iter().filter(|eh| !eh.calls.is_empty()) {
+        if !manifest.spec_version_at_least(DECLARED_CALLS_SPEC_VERSION) {
+            return Err(CodegenError::InvalidManifest(format!(
+                "event handler `{}` declares `calls`, which needs specVersion {} or later, not {}",
+                eh.handler, DECLARED_CALLS_SPEC_VERSION, manifest.spec_version
+            )));
+        }
+        // resolve_handlers already checked the event exists
+        if let Some(event) = abi.find_event(&eh.event) {
+            handler_calls.push(declared_calls::resolve(data_source, eh, event, abis)?);
+        }
+    }
+
+    Ok(handler_calls)
+}
+
+/// Validate the topic filters of the event handlers of a data source or
+/// template.
+fn resolve_event_filters(
+    manifest: &Manifest,
+    mapping: &Mapping,
+    abi: &AbiParser,
+) -> Result<Vec<filters::EventFilter>> {
+    let mut event_filters = Vec::new();
+
+    for eh in mapping.event_handlers.iter().filter(|eh| eh.has_topic_filters()) {
+        if !manifest.spec_version_at_least(TOPIC_FILTERS_SPEC_VERSION) {
+            return Err(CodegenError::InvalidManifest(format!(
+                "event handler `{}` filters on topics, which needs specVersion {} or later, not {}",
+                eh.handler, TOPIC_FILTERS_SPEC_VERSION, manifest.spec_version
+            )));
+        }
+        // resolve_handlers already checked the event exists
+        if let Some(event) = abi.find_event(&eh.event) {
+            event_filters.push(filters::resolve_event(eh, event)?);
+        }
+    }
+
+    Ok(event_filters)
 }
 
 fn generate_mod_rs(abi_modules: &[String], has_templates: bool) -> String {
@@ -94,6 +295,7 @@ fn generate_mod_rs(abi_modules: &[String], has_templates: bool) -> String {
     for module in abi_modules {
         code.push_str(&format!("mod {};\n", module));
     }
+    code.push_str("#[cfg(not(target_arch = \"wasm32\"))]\nmod dispatch;\n");
 
     if has_templates {
         code.push_str("pub mod templates;\n");
@@ -105,6 +307,9 @@ fn generate_mod_rs(abi_modules: &[String], has_templates: bool) -> String {
         code.push_str(&format!("pub use {}::*;\n", module));
     }
 
+    // The dispatch table is for native tests only
+    code.push_str("#[cfg(not(target_arch = \"wasm32\"))]\npub use dispatch::*;\n");
+
     code
 }
 
@@ -112,14 +317,27 @@ fn generate_mod_rs(abi_modules: &[String], has_templates: bool) -> String {
 fn generate_templates(templates: &[manifest::DataSourceTemplate]) -> String {
     let mut code = String::from(
         "//! Auto-generated data source templates — do not edit\n\n\
-         use alloc::string::ToString;\n\
+         #[allow(unused_imports)]\n\
+         use alloc::string::{String, ToString};\n\
          use yogurt_runtime::prelude::Address;\n\
          use yogurt_runtime::data_source;\n\n",
     );
+    if templates.iter().any(|t| !t.context.is_empty()) {
+        code.push_str(
+            "#[allow(unused_imports)]\n\
+             use yogurt_runtime::prelude::{BigDecimal, BigInt, Bytes, Value};\n\
+             use yogurt_runtime::types::EntityData;\n\n",
+        );
+    }
 
     for template in templates {
         let struct_name = to_pascal_case(&template.name);
         let is_file_ds = template.kind.starts_with("file/");
+        let (param, param_doc, params) = if is_file_ds {
+            ("content_id: &str", "content identifier", "content_id.to_string()")
+        } else {
+            ("address: &Address", "contract address", "address.to_hex()")
+        };
 
         code.push_str(&format!(
             "/// Data source template: `{name}`\n\
@@ -155,13 +373,104 @@ fn generate_templates(templates: &[manifest::DataSourceTemplate]) -> String {
             ));
         }
 
+        if !template.context.is_empty() {
+            code.push_str(&format!(
+                "\n    /// Create a new data source instance for the given {param_doc}, with\n\
+                 /// a context its handlers read with `{struct_name}Context::current()`.\n\
+                 pub fn create_with_context({param}, context: {struct_name}Context) {{\n\
+                     data_source::create_with_context(\"{name}\", &[{params}], context.to_entity());\n\
+                 }}\n",
+                param_doc = param_doc,
+                struct_name = struct_name,
+                param = param,
+                name = template.name,
+                params = params,
+            ));
+        }
+
         code.push_str("}\n\n");
+
+        if !template.context.is_empty() {
+            code.push_str(&generate_template_context(template, &struct_name));
+        }
     }
 
     code
 }
 
-fn to_pascal_case(s: &str) -> String {
+/// Generate the `<Template>Context` struct of a template's declared context
+/// fields, with conversions to and from the context entity.
+fn generate_template_context(template: &manifest::DataSourceTemplate, struct_name: &str) -> String {
+    let mut fields = String::new();
+    let mut reads = String::new();
+    let mut writes = String::new();
+
+    for field in &template.context {
+        let rust_name = abi::to_snake_case(&field.name);
+        let (rust_type, variant, read, write) = match field.ty {
+            ContextType::String => ("String", "String", "v.clone()", "self.{}.clone()"),
+            ContextType::Int => ("i32", "Int", "*v", "self.{}"),
+            ContextType::Int8 => ("i64", "Int8", "*v", "self.{}"),
+            ContextType::BigInt => ("BigInt", "BigInt", "v.clone()", "self.{}.clone()"),
+            ContextType::BigDecimal => ("BigDecimal", "BigDecimal", "v.clone()", "self.{}.clone()"),
+            ContextType::Bytes => ("Bytes", "Bytes", "v.clone()", "self.{}.clone()"),
+            ContextType::Boolean => ("bool", "Bool", "*v", "self.{}"),
+            ContextType::Address => ("Address", "Bytes", "Address::from(v.as_slice())", "Bytes::from(&self.{})"),
+        };
+        fields.push_str(&format!("    pub {}: {},\n", rust_name, rust_type));
+        reads.push_str(&format!(
+            "{rust_name}: match context.get(\"{name}\")? {{\n\
+                             Value::{variant}(v) => {read},\n\
+                             _ => return None,\n\
+                         }},\n",
+            rust_name = rust_name,
+            name = field.name,
+            variant = variant,
+            read = read,
+        ));
+        writes.push_str(&format!(
+            "context.set(\"{}\", Value::{}({}));\n",
+            field.name,
+            variant,
+            write.replace("{}", &rust_name),
+        ));
+    }
+
+    format!(
+        "/// Context of `{name}` data sources, declared under `context` in the\n\
+         /// manifest and set by `{struct_name}::create_with_context`.\n\
+         #[derive(Clone, Debug)]\n\
+         pub struct {struct_name}Context {{\n\
+         {fields}\
+         }}\n\n\
+         impl {struct_name}Context {{\n\
+             /// The context of the current data source, or `None` if a field is\n\
+             /// missing or of another type, as in a data source created without it.\n\
+             pub fn current() -> Option<Self> {{\n\
+                 Self::from_entity(&data_source::context())\n\
+             }}\n\n\
+             /// Read the fields from a context entity.\n\
+             pub fn from_entity(context: &EntityData) -> Option<Self> {{\n\
+                 Some(Self {{\n\
+         {reads}\
+                 }})\n\
+             }}\n\n\
+             /// The context entity graph-node stores with the data source.\n\
+             pub fn to_entity(&self) -> EntityData {{\n\
+                 let mut context = EntityData::new();\n\
+         {writes}\
+                 context\n\
+             }}\n\
+         }}\n\n",
+        name = template.name,
+        struct_name = struct_name,
+        fields = fields,
+        reads = reads,
+        writes = writes,
+    )
+}
+
+pub(crate) fn to_pascal_case(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut capitalize_next = true;
 
@@ -199,8 +508,13 @@ pub fn compute_codegen_hash(manifest_path: &Path) -> Result<String> {
     }
 
     // Hash all ABI files
-    for data_source in &manifest.data_sources {
-        for abi in &data_source.mapping.abis {
+    let mappings = manifest
+        .data_sources
+        .iter()
+        .map(|ds| &ds.mapping)
+        .chain(manifest.templates.iter().map(|t| &t.mapping));
+    for mapping in mappings {
+        for abi in &mapping.abis {
             if let Some(abi_path) = manifest_path.parent().map(|p| p.join(&abi.file)) {
                 if abi_path.exists() {
                     hasher.update(fs::read(&abi_path)?);
@@ -239,15 +553,3 @@ fn store_codegen_hash(manifest_path: &Path, output_dir: &Path) -> Result<()> {
     fs::write(hash_file, hash)?;
     Ok(())
 }
-
-/// Extract the function name from a function signature.
-///
-/// e.g., "mint(uint256)" -> "mint"
-///       "transfer(address,uint256)" -> "transfer"
-fn extract_function_name(signature: &str) -> String {
-    signature
-        .split('(')
-        .next()
-        .unwrap_or(signature)
-        .to_string()
-}
@@ -1,8 +1,26 @@
 //! Subgraph manifest (subgraph.yaml) parsing.
 
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
 
-use crate::error::Result;
+use crate::error::{CodegenError, Result};
+
+/// `mapping.language` for mappings built against the native Rust ABI.
+///
+/// Mappings declaring `wasm/assemblyscript` (the default) emulate
+/// AssemblyScript's object layouts instead.
+pub const RUST_ABI_LANGUAGE: &str = "wasm/rust";
+
+/// First `specVersion` whose event handlers may declare `calls`.
+pub const DECLARED_CALLS_SPEC_VERSION: &str = "1.2.0";
+
+/// First `specVersion` whose event handlers may filter on `topic1` to `topic3`.
+pub const TOPIC_FILTERS_SPEC_VERSION: &str = "1.2.0";
+
+/// First `specVersion` whose block handlers may use the `polling` and `once`
+/// filters.
+pub const BLOCK_FILTERS_SPEC_VERSION: &str = "0.0.8";
 
 /// The top-level subgraph manifest.
 #[derive(Debug, Deserialize)]
@@ -13,6 +31,8 @@ pub struct Manifest {
     pub description: Option<String>,
     #[serde(default)]
     pub repository: Option<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
     pub schema: Schema,
     pub data_sources: Vec<DataSource>,
     #[serde(default)]
@@ -21,8 +41,97 @@ pub struct Manifest {
 
 impl Manifest {
     /// Parse a manifest from YAML content.
+    ///
+    /// Rejects block handler combinations graph-node refuses to deploy.
     pub fn parse(content: &str) -> Result<Self> {
-        serde_yaml::from_str(content).map_err(Into::into)
+        let manifest: Manifest = serde_yaml::from_str(content)?;
+        manifest.validate_block_handlers()?;
+        Ok(manifest)
+    }
+
+    /// graph-node allows at most one block handler per filter kind in a data
+    /// source, and no polling handler next to an unfiltered or call one.
+    fn validate_block_handlers(&self) -> Result<()> {
+        let mappings = self
+            .data_sources
+            .iter()
+            .map(|ds| (&ds.name, &ds.mapping))
+            .chain(self.templates.iter().map(|t| (&t.name, &t.mapping)));
+
+        for (name, mapping) in mappings {
+            let invalid = |message: String| {
+                CodegenError::InvalidManifest(format!("data source `{}`: {}", name, message))
+            };
+
+            let mut kinds: Vec<(&str, &str)> = Vec::new();
+            for bh in &mapping.block_handlers {
+                let kind = match bh.filter {
+                    None => "unfiltered",
+                    Some(BlockFilter::Call) => "call",
+                    Some(BlockFilter::Once) => "once",
+                    Some(BlockFilter::Polling { .. }) => "polling",
+                };
+                if matches!(kind, "once" | "polling") && !self.spec_version_at_least(BLOCK_FILTERS_SPEC_VERSION) {
+                    return Err(invalid(format!(
+                        "block handler `{}` uses the `{}` filter, which needs specVersion {} or later, not {}",
+                        bh.handler, kind, BLOCK_FILTERS_SPEC_VERSION, self.spec_version
+                    )));
+                }
+                if let Some((other, _)) = kinds.iter().find(|(_, k)| *k == kind) {
+                    return Err(invalid(format!(
+                        "block handlers `{}` and `{}` are both {}; graph-node allows one per filter kind",
+                        other, bh.handler, kind
+                    )));
+                }
+                kinds.push((&bh.handler, kind));
+            }
+
+            let polling = kinds.iter().find(|(_, k)| *k == "polling");
+            let every_block = kinds.iter().find(|(_, k)| matches!(*k, "unfiltered" | "call"));
+            if let (Some((polling, _)), Some((other, kind))) = (polling, every_block) {
+                return Err(invalid(format!(
+                    "polling block handler `{}` cannot be combined with {} block handler `{}`",
+                    polling,
+                    if *kind == "call" { "call-filtered" } else { "unfiltered" },
+                    other
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the mappings are built for the native Rust ABI.
+    ///
+    /// All data sources and templates share one WASM module, so they must
+    /// agree on the language.
+    pub fn uses_rust_abi(&self) -> Result<bool> {
+        let mut languages = self
+            .data_sources
+            .iter()
+            .map(|ds| (&ds.name, &ds.mapping.language))
+            .chain(self.templates.iter().map(|t| (&t.name, &t.mapping.language)));
+
+        let Some((_, first)) = languages.next() else {
+            return Ok(false);
+        };
+        let rust = first == RUST_ABI_LANGUAGE;
+        if let Some((name, _)) = languages.find(|(_, l)| (*l == RUST_ABI_LANGUAGE) != rust) {
+            return Err(CodegenError::InvalidManifest(format!(
+                "data source `{}` mixes `{}` with other mapping languages; \
+                 all mappings share one WASM module",
+                name, RUST_ABI_LANGUAGE
+            )));
+        }
+        Ok(rust)
+    }
+
+    /// Whether `specVersion` is at least `version`, both `major.minor.patch`.
+    pub fn spec_version_at_least(&self, version: &str) -> bool {
+        fn parse(v: &str) -> Vec<u32> {
+            v.split('.').map(|n| n.trim().parse().unwrap_or(0)).collect()
+        }
+        parse(&self.spec_version) >= parse(version)
     }
 }
 
@@ -86,6 +195,168 @@ pub struct EventHandler {
     pub handler: String,
     #[serde(default)]
     pub receipt: bool,
+    /// Declared eth_calls, label to call expression, which graph-node runs
+    /// in parallel before the handler.
+    #[serde(default)]
+    pub calls: BTreeMap<String, String>,
+    /// Values of the first indexed parameter to index; any value if unset.
+    #[serde(default)]
+    pub topic1: Option<Vec<String>>,
+    #[serde(default)]
+    pub topic2: Option<Vec<String>>,
+    #[serde(default)]
+    pub topic3: Option<Vec<String>>,
+}
+
+impl EventHandler {
+    /// Parse the `calls` expressions, in label order.
+    pub fn declared_calls(&self) -> Result<Vec<DeclaredCall>> {
+        self.calls
+            .iter()
+            .map(|(label, expr)| {
+                DeclaredCall::parse(label, expr).map_err(|e| {
+                    CodegenError::InvalidManifest(format!(
+                        "event handler `{}`, call `{}`: {}",
+                        self.handler, label, e
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Whether any of `topic1` to `topic3` is set.
+    pub fn has_topic_filters(&self) -> bool {
+        self.topic1.is_some() || self.topic2.is_some() || self.topic3.is_some()
+    }
+
+    /// Parse the hex values of `topic1` to `topic3`, which graph-node
+    /// left-pads to 32 bytes.
+    pub fn topic_filters(&self) -> Result<[Option<Vec<Vec<u8>>>; 3]> {
+        let parse = |n: usize, values: &Option<Vec<String>>| -> Result<Option<Vec<Vec<u8>>>> {
+            let Some(values) = values else {
+                return Ok(None);
+            };
+            let invalid = |message: String| {
+                CodegenError::InvalidManifest(format!(
+                    "event handler `{}`, topic{}: {}",
+                    self.handler, n, message
+                ))
+            };
+            if values.is_empty() {
+                return Err(invalid("no values; remove it to match any value".to_string()));
+            }
+            values
+                .iter()
+                .map(|value| parse_topic_value(value).map_err(&invalid))
+                .collect::<Result<Vec<_>>>()
+                .map(Some)
+        };
+
+        Ok([
+            parse(1, &self.topic1)?,
+            parse(2, &self.topic2)?,
+            parse(3, &self.topic3)?,
+        ])
+    }
+}
+
+/// Parse a `0x`-prefixed hex topic value of at most 32 bytes.
+fn parse_topic_value(value: &str) -> std::result::Result<Vec<u8>, String> {
+    let digits = value
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("`{}` is not a 0x-prefixed hex value", value))?;
+    if digits.is_empty() || digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("`{}` is not an even number of hex digits", value));
+    }
+    if digits.len() > 64 {
+        return Err(format!("`{}` is {} bytes, longer than a 32-byte topic", value, digits.len() / 2));
+    }
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// A declared eth_call such as
+/// `ERC20[event.address].balanceOf(event.params.to)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredCall {
+    pub label: String,
+    /// Name of an ABI of the data source.
+    pub contract: String,
+    pub address: CallArg,
+    pub function: String,
+    pub args: Vec<CallArg>,
+}
+
+/// A value taken from the triggering event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallArg {
+    /// `event.address`
+    Address,
+    /// `event.params.<name>`
+    Param(String),
+}
+
+impl DeclaredCall {
+    /// Parse `Contract[address].function(arg, ...)`.
+    pub fn parse(label: &str, expr: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("expected `Contract[event.address].function(event.params.x, ...)`, found `{}`", expr);
+
+        let (contract, rest) = expr.trim().split_once('[').ok_or_else(invalid)?;
+        let (address, rest) = rest.split_once(']').ok_or_else(invalid)?;
+        let (function, rest) = rest.strip_prefix('.').ok_or_else(invalid)?.split_once('(').ok_or_else(invalid)?;
+        let args = rest.trim_end().strip_suffix(')').ok_or_else(invalid)?;
+
+        let contract = contract.trim();
+        let function = function.trim();
+        if !is_identifier(contract) || !is_identifier(function) {
+            return Err(invalid());
+        }
+
+        Ok(DeclaredCall {
+            label: label.to_string(),
+            contract: contract.to_string(),
+            address: CallArg::parse(address)?,
+            function: function.to_string(),
+            args: if args.trim().is_empty() {
+                Vec::new()
+            } else {
+                args.split(',').map(CallArg::parse).collect::<std::result::Result<_, _>>()?
+            },
+        })
+    }
+}
+
+impl CallArg {
+    fn parse(arg: &str) -> std::result::Result<Self, String> {
+        let arg = arg.trim();
+        if arg == "event.address" {
+            return Ok(CallArg::Address);
+        }
+        match arg.strip_prefix("event.params.") {
+            Some(name) if is_identifier(name) => Ok(CallArg::Param(name.to_string())),
+            _ => Err(format!(
+                "`{}` is not `event.address` or `event.params.<name>`",
+                arg
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for CallArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallArg::Address => f.write_str("event.address"),
+            CallArg::Param(name) => write!(f, "event.params.{}", name),
+        }
+    }
+}
+
+pub(crate) fn is_identifier(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 /// Call handler definition.
@@ -104,10 +375,51 @@ pub struct BlockHandler {
     pub filter: Option<BlockFilter>,
 }
 
-/// Block filter (for block handlers).
-#[derive(Debug, Deserialize)]
-pub struct BlockFilter {
-    pub kind: String,
+/// Which blocks a block handler runs on, its `filter`. Without one it runs
+/// on every block from the start block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawBlockFilter")]
+pub enum BlockFilter {
+    /// `kind: call`: blocks with a call to the data source's contract.
+    Call,
+    /// `kind: once`: the start block only, for initialization.
+    Once,
+    /// `kind: polling`: every `every` blocks, counting from the start block.
+    Polling { every: u64 },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawBlockFilter {
+    kind: String,
+    #[serde(default)]
+    every: Option<u64>,
+}
+
+impl TryFrom<RawBlockFilter> for BlockFilter {
+    type Error = String;
+
+    fn try_from(raw: RawBlockFilter) -> std::result::Result<Self, String> {
+        match (raw.kind.as_str(), raw.every) {
+            ("polling", Some(0)) => Err("`every` of a polling filter must be at least 1".to_string()),
+            ("polling", Some(every)) => Ok(BlockFilter::Polling { every }),
+            ("polling", None) => Err("a polling filter needs `every`".to_string()),
+            ("call" | "once", Some(_)) => Err(format!("`every` only applies to polling filters, not `{}`", raw.kind)),
+            ("call", None) => Ok(BlockFilter::Call),
+            ("once", None) => Ok(BlockFilter::Once),
+            (kind, _) => Err(format!("unknown block filter kind `{}`, expected call, once or polling", kind)),
+        }
+    }
+}
+
+impl std::fmt::Display for BlockFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockFilter::Call => f.write_str("call"),
+            BlockFilter::Once => f.write_str("once"),
+            BlockFilter::Polling { every } => write!(f, "polling every {}", every),
+        }
+    }
 }
 
 /// Data source template for dynamic data sources.
@@ -119,6 +431,81 @@ pub struct DataSourceTemplate {
     pub network: String,
     pub source: TemplateSource,
     pub mapping: Mapping,
+    /// Typed fields of the context instances are created with, a yogurt
+    /// extension that `yogurt deploy` strips from the manifest.
+    #[serde(default, deserialize_with = "deserialize_context")]
+    pub context: Vec<ContextField>,
+}
+
+/// A field of a template's context, such as `token0: Address`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextField {
+    pub name: String,
+    pub ty: ContextType,
+}
+
+/// The type of a context field, named like the schema's scalars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextType {
+    String,
+    Int,
+    Int8,
+    BigInt,
+    BigDecimal,
+    Bytes,
+    Boolean,
+    /// Stored as `Bytes`.
+    Address,
+}
+
+impl std::str::FromStr for ContextType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        Ok(match s {
+            "String" => ContextType::String,
+            "Int" => ContextType::Int,
+            "Int8" => ContextType::Int8,
+            "BigInt" => ContextType::BigInt,
+            "BigDecimal" => ContextType::BigDecimal,
+            "Bytes" => ContextType::Bytes,
+            "Boolean" => ContextType::Boolean,
+            "Address" => ContextType::Address,
+            other => {
+                return Err(format!(
+                    "unknown context type `{}`, expected String, Int, Int8, BigInt, BigDecimal, Bytes, Boolean or Address",
+                    other
+                ))
+            }
+        })
+    }
+}
+
+/// Read `context` as a map of field names to types, in declaration order.
+fn deserialize_context<'de, D>(deserializer: D) -> std::result::Result<Vec<ContextField>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let fields = serde_yaml::Mapping::deserialize(deserializer)?;
+    fields
+        .into_iter()
+        .map(|(name, ty)| {
+            let name = match name.as_str() {
+                Some(name) if is_identifier(name) => name,
+                Some(name) => return Err(D::Error::custom(format!("context field `{}` is not an identifier", name))),
+                None => return Err(D::Error::custom("context field names must be strings")),
+            };
+            let ty = ty
+                .as_str()
+                .ok_or_else(|| D::Error::custom(format!("context field `{}` needs a type name", name)))?;
+            Ok(ContextField {
+                name: name.to_string(),
+                ty: ty.parse().map_err(D::Error::custom)?,
+            })
+        })
+        .collect()
 }
 
 /// Template source (no address, as it's provided at runtime).
@@ -126,3 +513,340 @@ pub struct DataSourceTemplate {
 pub struct TemplateSource {
     pub abi: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_handler(topics: &str) -> EventHandler {
+        serde_yaml::from_str(&format!(
+            "event: Transfer(indexed address,indexed address,uint256)\nhandler: handleTransfer\n{}",
+            topics
+        ))
+        .unwrap()
+    }
+
+    /// A manifest with one data source whose block handlers are
+    /// `block_handlers`, a YAML flow sequence.
+    fn manifest_with_block_handlers(spec_version: &str, block_handlers: &str) -> Result<Manifest> {
+        Manifest::parse(&format!(
+            r#"
+specVersion: {}
+schema:
+  file: ./schema.graphql
+dataSources:
+  - kind: ethereum/contract
+    name: Pool
+    network: mainnet
+    source:
+      address: "0x0000000000000000000000000000000000000001"
+      abi: Pool
+    mapping:
+      kind: ethereum/events
+      apiVersion: 0.0.7
+      entities: []
+      abis: []
+      blockHandlers: {}
+      file: ./src/lib.rs
+"#,
+            spec_version, block_handlers
+        ))
+    }
+
+    fn block_filter(yaml: &str) -> std::result::Result<BlockFilter, String> {
+        serde_yaml::from_str::<BlockFilter>(yaml).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_block_filter_parse() {
+        assert_eq!(block_filter("kind: call"), Ok(BlockFilter::Call));
+        assert_eq!(block_filter("kind: once"), Ok(BlockFilter::Once));
+        assert_eq!(block_filter("{kind: polling, every: 10}"), Ok(BlockFilter::Polling { every: 10 }));
+    }
+
+    #[test]
+    fn test_block_filter_rejects_invalid_filters() {
+        for (yaml, message) in [
+            ("kind: hourly", "unknown block filter kind `hourly`, expected call, once or polling"),
+            ("kind: polling", "a polling filter needs `every`"),
+            ("{kind: polling, every: 0}", "`every` of a polling filter must be at least 1"),
+            ("{kind: once, every: 10}", "`every` only applies to polling filters, not `once`"),
+            ("{kind: call, every: 10}", "`every` only applies to polling filters, not `call`"),
+        ] {
+            let err = block_filter(yaml).unwrap_err();
+            assert!(err.contains(message), "{}: {}", yaml, err);
+        }
+        assert!(block_filter("{kind: polling, every: 10, offset: 1}").is_err());
+    }
+
+    #[test]
+    fn test_block_handlers_one_per_filter_kind() {
+        let manifest = manifest_with_block_handlers(
+            "0.0.8",
+            "[{handler: handleBlock}, {handler: handleOnce, filter: {kind: once}}, \
+             {handler: handleCall, filter: {kind: call}}]",
+        )
+        .unwrap();
+        assert_eq!(manifest.data_sources[0].mapping.block_handlers.len(), 3);
+
+        for (first, second) in [
+            ("{handler: handleA}", "{handler: handleB}"),
+            ("{handler: handleA, filter: {kind: call}}", "{handler: handleB, filter: {kind: call}}"),
+            ("{handler: handleA, filter: {kind: once}}", "{handler: handleB, filter: {kind: once}}"),
+            (
+                "{handler: handleA, filter: {kind: polling, every: 10}}",
+                "{handler: handleB, filter: {kind: polling, every: 20}}",
+            ),
+        ] {
+            let err = manifest_with_block_handlers("0.0.8", &format!("[{}, {}]", first, second)).unwrap_err();
+            assert!(
+                err.to_string().contains("block handlers `handleA` and `handleB` are both"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_handlers_reject_polling_with_every_block_handler() {
+        let polling = "{handler: handlePoll, filter: {kind: polling, every: 10}}";
+
+        let err = manifest_with_block_handlers("0.0.8", &format!("[{}, {{handler: handleBlock}}]", polling))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid manifest: data source `Pool`: polling block handler `handlePoll` \
+             cannot be combined with unfiltered block handler `handleBlock`"
+        );
+
+        let call = "{handler: handleCall, filter: {kind: call}}";
+        let err = manifest_with_block_handlers("0.0.8", &format!("[{}, {}]", call, polling)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid manifest: data source `Pool`: polling block handler `handlePoll` \
+             cannot be combined with call-filtered block handler `handleCall`"
+        );
+
+        let once = "{handler: handleOnce, filter: {kind: once}}";
+        assert!(manifest_with_block_handlers("0.0.8", &format!("[{}, {}]", once, polling)).is_ok());
+    }
+
+    #[test]
+    fn test_block_handlers_reject_once_and_polling_before_spec_version() {
+        for (filter, kind) in [("{kind: once}", "once"), ("{kind: polling, every: 10}", "polling")] {
+            let handlers = format!("[{{handler: handleBlock, filter: {}}}]", filter);
+            let err = manifest_with_block_handlers("0.0.7", &handlers).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Invalid manifest: data source `Pool`: block handler `handleBlock` uses the `{}` filter, \
+                     which needs specVersion 0.0.8 or later, not 0.0.7",
+                    kind
+                )
+            );
+            assert!(manifest_with_block_handlers("0.0.8", &handlers).is_ok());
+        }
+
+        let err = manifest_with_block_handlers("0.0.8", "[{handler: handleBlock, filter: {kind: hourly}}]")
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown block filter kind `hourly`"), "{}", err);
+    }
+
+    /// The context of a `Pair` template declaring `context`, a YAML flow
+    /// mapping, or `None` to leave it out.
+    fn template_context(context: Option<&str>) -> std::result::Result<Vec<ContextField>, String> {
+        let context = context.map(|c| format!("context: {}\n", c)).unwrap_or_default();
+        let template: DataSourceTemplate = serde_yaml::from_str(&format!(
+            r#"
+kind: ethereum/contract
+name: Pair
+network: mainnet
+source:
+  abi: Pair
+{}mapping:
+  kind: ethereum/events
+  apiVersion: 0.0.7
+  entities: []
+  abis: []
+  file: ./src/lib.rs
+"#,
+            context
+        ))
+        .map_err(|e| e.to_string())?;
+        Ok(template.context)
+    }
+
+    #[test]
+    fn test_template_context_parse() {
+        assert_eq!(template_context(None), Ok(vec![]));
+        assert_eq!(
+            template_context(Some("{token0: Address, token1: Address, fee: Int}")),
+            Ok(vec![
+                ContextField { name: "token0".to_string(), ty: ContextType::Address },
+                ContextField { name: "token1".to_string(), ty: ContextType::Address },
+                ContextField { name: "fee".to_string(), ty: ContextType::Int },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_context_type_parse() {
+        for (name, ty) in [
+            ("String", ContextType::String),
+            ("Int", ContextType::Int),
+            ("Int8", ContextType::Int8),
+            ("BigInt", ContextType::BigInt),
+            ("BigDecimal", ContextType::BigDecimal),
+            ("Bytes", ContextType::Bytes),
+            ("Boolean", ContextType::Boolean),
+            ("Address", ContextType::Address),
+        ] {
+            assert_eq!(name.parse(), Ok(ty));
+            let fields = template_context(Some(&format!("{{x: {}}}", name))).unwrap();
+            assert_eq!(fields[0].ty, ty);
+        }
+    }
+
+    #[test]
+    fn test_template_context_rejects_invalid_fields() {
+        for (context, message) in [
+            (
+                "{x: Float}",
+                "unknown context type `Float`, expected String, Int, Int8, BigInt, BigDecimal, Bytes, Boolean or Address",
+            ),
+            ("{token-0: Address}", "context field `token-0` is not an identifier"),
+            ("{0token: Address}", "context field `0token` is not an identifier"),
+            ("{x: }", "context field `x` needs a type name"),
+            ("{x: [Address]}", "context field `x` needs a type name"),
+            ("{1: Address}", "context field names must be strings"),
+        ] {
+            let err = template_context(Some(context)).unwrap_err();
+            assert!(err.contains(message), "{}: {}", context, err);
+        }
+        assert_eq!(
+            "address".parse::<ContextType>(),
+            Err("unknown context type `address`, expected String, Int, Int8, BigInt, BigDecimal, Bytes, Boolean or Address"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_topic_value() {
+        assert_eq!(parse_topic_value("0x00ff"), Ok(vec![0x00, 0xff]));
+        assert_eq!(parse_topic_value("0xAbCd"), Ok(vec![0xab, 0xcd]));
+        let full = format!("0x{}", "ab".repeat(32));
+        assert_eq!(parse_topic_value(&full), Ok(vec![0xab; 32]));
+    }
+
+    #[test]
+    fn test_parse_topic_value_rejects_malformed_values() {
+        assert_eq!(
+            parse_topic_value("00ff"),
+            Err("`00ff` is not a 0x-prefixed hex value".to_string())
+        );
+        for odd in ["0x0ff", "0x", "0xzz"] {
+            assert_eq!(
+                parse_topic_value(odd),
+                Err(format!("`{}` is not an even number of hex digits", odd))
+            );
+        }
+        let long = format!("0x{}", "ab".repeat(33));
+        assert_eq!(
+            parse_topic_value(&long),
+            Err(format!("`{}` is 33 bytes, longer than a 32-byte topic", long))
+        );
+    }
+
+    #[test]
+    fn test_topic_filters() {
+        let handler = event_handler("topic2: ['0x01', '0x00ff']\n");
+        assert!(handler.has_topic_filters());
+        assert_eq!(
+            handler.topic_filters().unwrap(),
+            [None, Some(vec![vec![0x01], vec![0x00, 0xff]]), None]
+        );
+
+        let handler = event_handler("");
+        assert!(!handler.has_topic_filters());
+        assert_eq!(handler.topic_filters().unwrap(), [None, None, None]);
+    }
+
+    #[test]
+    fn test_topic_filters_reject_empty_list() {
+        let err = event_handler("topic1: []\n").topic_filters().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid manifest: event handler `handleTransfer`, topic1: no values; remove it to match any value"
+        );
+    }
+
+    #[test]
+    fn test_topic_filters_name_the_invalid_topic() {
+        let err = event_handler("topic3: ['0x01', '0x123']\n").topic_filters().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid manifest: event handler `handleTransfer`, topic3: `0x123` is not an even number of hex digits"
+        );
+    }
+
+    #[test]
+    fn test_declared_call_parse() {
+        let call = DeclaredCall::parse(
+            "ERC20.balanceOf",
+            " ERC20 [event.address].balanceOf(event.params.to, event.params.id ) ",
+        )
+        .unwrap();
+        assert_eq!(
+            call,
+            DeclaredCall {
+                label: "ERC20.balanceOf".to_string(),
+                contract: "ERC20".to_string(),
+                address: CallArg::Address,
+                function: "balanceOf".to_string(),
+                args: vec![CallArg::Param("to".to_string()), CallArg::Param("id".to_string())],
+            }
+        );
+
+        let call = DeclaredCall::parse("reserves", "Pair[event.params.pair].getReserves()").unwrap();
+        assert_eq!(call.address, CallArg::Param("pair".to_string()));
+        assert!(call.args.is_empty());
+    }
+
+    #[test]
+    fn test_declared_call_parse_rejects_malformed_expressions() {
+        for expr in [
+            "ERC20.balanceOf(event.params.to)",
+            "ERC20[event.address]balanceOf(event.params.to)",
+            "ERC20[event.address].balanceOf(event.params.to",
+            "[event.address].balanceOf()",
+            "1ERC20[event.address].balanceOf()",
+            "ERC20[event.address].balance-of()",
+        ] {
+            assert_eq!(
+                DeclaredCall::parse("call", expr),
+                Err(format!(
+                    "expected `Contract[event.address].function(event.params.x, ...)`, found `{}`",
+                    expr
+                )),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_declared_call_parse_rejects_non_event_values() {
+        assert_eq!(
+            DeclaredCall::parse("call", "ERC20[0x1234].balanceOf()"),
+            Err("`0x1234` is not `event.address` or `event.params.<name>`".to_string())
+        );
+        assert_eq!(
+            DeclaredCall::parse("call", "ERC20[event.address].balanceOf(event.params.)"),
+            Err("`event.params.` is not `event.address` or `event.params.<name>`".to_string())
+        );
+        assert_eq!(
+            DeclaredCall::parse("call", "ERC20[event.address].balanceOf(event.params.to,)"),
+            Err("`` is not `event.address` or `event.params.<name>`".to_string())
+        );
+    }
+}
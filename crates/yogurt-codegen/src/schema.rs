@@ -1,11 +1,47 @@
 //! GraphQL schema parsing and Rust code generation.
 
 use crate::error::{CodegenError, Result};
+use crate::manifest::is_identifier;
 
 /// A parsed GraphQL schema.
 #[derive(Debug)]
 pub struct SchemaParser {
     pub entities: Vec<Entity>,
+    pub enums: Vec<EnumType>,
+    pub interfaces: Vec<Interface>,
+    pub aggregations: Vec<Aggregation>,
+}
+
+/// A GraphQL `@aggregation` type, computed by graph-node from a timeseries.
+#[derive(Debug)]
+pub struct Aggregation {
+    pub name: String,
+    pub intervals: Vec<String>,
+    pub source: String,
+    pub fields: Vec<Field>,
+}
+
+/// An `@aggregate(fn:, arg:)` directive on an aggregation field.
+#[derive(Debug)]
+pub struct Aggregate {
+    pub function: String,
+    pub arg: Option<String>,
+    pub cumulative: bool,
+}
+
+/// A GraphQL interface, implemented by one or more entities.
+#[derive(Debug)]
+pub struct Interface {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub id_type: IdType,
+}
+
+/// A GraphQL enum type.
+#[derive(Debug)]
+pub struct EnumType {
+    pub name: String,
+    pub values: Vec<String>,
 }
 
 /// A GraphQL entity type.
@@ -14,6 +50,9 @@ pub struct Entity {
     pub name: String,
     pub fields: Vec<Field>,
     pub is_immutable: bool,
+    pub is_timeseries: bool,
+    pub implements: Vec<String>,
+    pub id_type: IdType,
 }
 
 /// A field in a GraphQL entity.
@@ -24,6 +63,7 @@ pub struct Field {
     pub is_required: bool,
     pub is_derived: bool,
     pub derived_from: Option<String>,
+    pub aggregate: Option<Aggregate>,
 }
 
 /// GraphQL field types.
@@ -37,8 +77,52 @@ pub enum FieldType {
     BigDecimal,
     Bytes,
     Boolean,
+    Timestamp,
     Array(Box<FieldType>),
-    Reference(String),
+    /// A schema enum, stored as its value name.
+    Enum(String),
+    /// A reference to any entity implementing an interface, stored as its ID.
+    Interface(String, IdType),
+    /// A reference to another entity, stored as its ID.
+    Reference(String, IdType),
+}
+
+/// The type of an entity's `id` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdType {
+    String,
+    Bytes,
+    Int8,
+}
+
+impl IdType {
+    /// The ID type of an `id` field, if graph-node accepts it as one.
+    fn from_field_type(field_type: &FieldType) -> Option<Self> {
+        match field_type {
+            FieldType::Id | FieldType::String => Some(IdType::String),
+            FieldType::Bytes => Some(IdType::Bytes),
+            FieldType::Int8 => Some(IdType::Int8),
+            _ => None,
+        }
+    }
+
+    /// The Rust type of the ID.
+    fn rust_type(self) -> &'static str {
+        match self {
+            IdType::String => "String",
+            IdType::Bytes => "Bytes",
+            IdType::Int8 => "i64",
+        }
+    }
+
+    /// The `Value` variant the ID is stored as.
+    fn value_variant(self) -> &'static str {
+        match self {
+            IdType::String => "String",
+            IdType::Bytes => "Bytes",
+            IdType::Int8 => "Int8",
+        }
+    }
 }
 
 impl SchemaParser {
@@ -49,10 +133,59 @@ impl SchemaParser {
         let doc = parse_schema::<String>(content)
             .map_err(|e| CodegenError::GraphQL(e.to_string()))?;
 
+        // Enums first, so fields can refer to enums declared further down
+        let enums: Vec<EnumType> = doc
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::TypeDefinition(TypeDefinition::Enum(e)) => Some(EnumType {
+                    name: e.name.clone(),
+                    values: e.values.iter().map(|v| v.name.clone()).collect(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let interface_names: Vec<String> = doc
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::TypeDefinition(TypeDefinition::Interface(i)) => Some(i.name.clone()),
+                _ => None,
+            })
+            .collect();
+        let known = KnownTypes {
+            enums: &enums,
+            interfaces: &interface_names,
+        };
+
         let mut entities = Vec::new();
+        let mut interfaces = Vec::new();
+        let mut aggregations = Vec::new();
+
+        for def in &doc.definitions {
+            if let Definition::TypeDefinition(TypeDefinition::Interface(iface)) = def {
+                let fields = parse_fields(&iface.fields, &known)?;
+                interfaces.push(Interface {
+                    name: iface.name.clone(),
+                    id_type: id_type("interface", &iface.name, &fields)?,
+                    fields,
+                });
+            }
+        }
 
         for def in doc.definitions {
             if let Definition::TypeDefinition(TypeDefinition::Object(obj)) = def {
+                if let Some(directive) = obj.directives.iter().find(|d| d.name == "aggregation") {
+                    aggregations.push(Aggregation {
+                        name: obj.name.clone(),
+                        intervals: directive_strings(directive, "intervals"),
+                        source: directive_string(directive, "source").unwrap_or_default(),
+                        fields: parse_fields(&obj.fields, &known)?,
+                    });
+                    continue;
+                }
+
                 // Check for @entity directive
                 let is_entity = obj.directives.iter().any(|d| d.name == "entity");
                 if !is_entity {
@@ -67,50 +200,61 @@ impl SchemaParser {
                         })
                 });
 
-                let mut fields = Vec::new();
-
-                for field in &obj.fields {
-                    // Check for @derivedFrom directive
-                    let derived_from = field
-                        .directives
-                        .iter()
-                        .find(|d| d.name == "derivedFrom")
-                        .and_then(|d| {
-                            d.arguments.iter().find(|(k, _)| k == "field").map(|(_, v)| {
-                                if let graphql_parser::schema::Value::String(s) = v {
-                                    s.clone()
-                                } else {
-                                    String::new()
-                                }
-                            })
-                        });
-
-                    let is_derived = derived_from.is_some();
-
-                    let (field_type, is_required) = parse_field_type(&field.field_type)?;
-
-                    fields.push(Field {
-                        name: field.name.clone(),
-                        field_type,
-                        is_required,
-                        is_derived,
-                        derived_from,
-                    });
-                }
+                // Check for @entity(timeseries: true)
+                let is_timeseries = obj.directives.iter().any(|d| {
+                    d.name == "entity"
+                        && d.arguments.iter().any(|(k, v)| {
+                            k == "timeseries" && matches!(v, graphql_parser::schema::Value::Boolean(true))
+                        })
+                });
+
+                let fields = parse_fields(&obj.fields, &known)?;
 
                 entities.push(Entity {
                     name: obj.name.clone(),
+                    id_type: id_type("entity", &obj.name, &fields)?,
                     fields,
-                    is_immutable,
+                    // Data points can never be updated
+                    is_immutable: is_immutable || is_timeseries,
+                    is_timeseries,
+                    implements: obj.implements_interfaces.clone(),
                 });
             }
         }
 
-        Ok(SchemaParser { entities })
+        for entity in entities.iter().filter(|e| e.is_timeseries) {
+            validate_timeseries_fields("timeseries entity", &entity.name, &entity.fields)?;
+        }
+        validate_interface_ids(&entities, &interfaces)?;
+
+        // References are stored with the ID type of the entity they point to
+        let id_types: Vec<(String, IdType)> = entities
+            .iter()
+            .map(|e| (e.name.clone(), e.id_type))
+            .chain(interfaces.iter().map(|i| (i.name.clone(), i.id_type)))
+            .collect();
+        let all_fields = entities
+            .iter_mut()
+            .flat_map(|e| e.fields.iter_mut())
+            .chain(interfaces.iter_mut().flat_map(|i| i.fields.iter_mut()))
+            .chain(aggregations.iter_mut().flat_map(|a| a.fields.iter_mut()));
+        for field in all_fields {
+            resolve_reference_id(&mut field.field_type, &id_types);
+        }
+        for aggregation in &aggregations {
+            validate_aggregation(aggregation, &entities)?;
+        }
+
+        Ok(SchemaParser {
+            entities,
+            enums,
+            interfaces,
+            aggregations,
+        })
     }
 
     /// Generate Rust code for all entities.
-    pub fn generate_rust(&self) -> String {
+    pub fn generate_rust(&self) -> Result<String> {
         let mut code = String::from(
             "//! Auto-generated by yogurt codegen — do not edit\n\n\
              #[allow(unused_imports)]\n\
@@ -119,32 +263,293 @@ impl SchemaParser {
              use alloc::string::ToString;\n\
              #[allow(unused_imports)]\n\
              use alloc::vec::Vec;\n\
+             #[allow(unused_imports)]\n\
+             use alloc::boxed::Box;\n\
              use yogurt_runtime::prelude::*;\n\
              use yogurt_runtime::store;\n\
              use yogurt_runtime::types::{EntityData, Value};\n\n",
         );
 
+        for enum_type in &self.enums {
+            code.push_str(&generate_enum(enum_type)?);
+            code.push('\n');
+        }
+
+        for interface in &self.interfaces {
+            code.push_str(&generate_interface(interface, &self.entities));
+            code.push('\n');
+        }
+
         for entity in &self.entities {
             code.push_str(&generate_entity_struct(entity));
             code.push('\n');
         }
 
-        code
+        for aggregation in &self.aggregations {
+            code.push_str(&generate_aggregation_struct(aggregation));
+            code.push('\n');
+        }
+
+        Ok(code)
+    }
+}
+
+/// Non-entity type names that fields can refer to.
+struct KnownTypes<'a> {
+    enums: &'a [EnumType],
+    interfaces: &'a [String],
+}
+
+fn parse_fields(
+    fields: &[graphql_parser::schema::Field<String>],
+    known: &KnownTypes,
+) -> Result<Vec<Field>> {
+    let mut result = Vec::new();
+
+    for field in fields {
+        // Check for @derivedFrom directive
+        let derived_from = field
+            .directives
+            .iter()
+            .find(|d| d.name == "derivedFrom")
+            .and_then(|d| {
+                d.arguments.iter().find(|(k, _)| k == "field").map(|(_, v)| {
+                    if let graphql_parser::schema::Value::String(s) = v {
+                        s.clone()
+                    } else {
+                        String::new()
+                    }
+                })
+            });
+
+        let is_derived = derived_from.is_some();
+
+        // Check for @aggregate directive (aggregation types only)
+        let aggregate = field
+            .directives
+            .iter()
+            .find(|d| d.name == "aggregate")
+            .map(|d| Aggregate {
+                function: directive_string(d, "fn").unwrap_or_default(),
+                arg: directive_string(d, "arg"),
+                cumulative: d.arguments.iter().any(|(k, v)| {
+                    k == "cumulative" && matches!(v, graphql_parser::schema::Value::Boolean(true))
+                }),
+            });
+
+        let (field_type, is_required) = parse_field_type(&field.field_type, known)?;
+
+        result.push(Field {
+            name: field.name.clone(),
+            field_type,
+            is_required,
+            is_derived,
+            derived_from,
+            aggregate,
+        });
     }
+
+    Ok(result)
+}
+
+/// A string argument of a directive.
+fn directive_string(directive: &graphql_parser::schema::Directive<String>, name: &str) -> Option<String> {
+    directive.arguments.iter().find(|(k, _)| k == name).and_then(|(_, v)| match v {
+        graphql_parser::schema::Value::String(s) => Some(s.clone()),
+        _ => None,
+    })
+}
+
+/// A list-of-strings argument of a directive.
+fn directive_strings(directive: &graphql_parser::schema::Directive<String>, name: &str) -> Vec<String> {
+    directive
+        .arguments
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| match v {
+            graphql_parser::schema::Value::List(items) => items
+                .iter()
+                .filter_map(|item| match item {
+                    graphql_parser::schema::Value::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .unwrap_or_default()
+}
+
+/// The ID type declared by a type's `id` field, `String` if it has none.
+fn id_type(kind: &str, name: &str, fields: &[Field]) -> Result<IdType> {
+    let Some(field) = fields.iter().find(|f| f.name == "id") else {
+        return Ok(IdType::String);
+    };
+    IdType::from_field_type(&field.field_type).ok_or_else(|| {
+        CodegenError::InvalidSchema(format!(
+            "{} `{}` has an `id` of unsupported type (expected ID, String, Bytes or Int8)",
+            kind, name
+        ))
+    })
+}
+
+/// graph-node requires implementors to share the interface's ID type.
+fn validate_interface_ids(entities: &[Entity], interfaces: &[Interface]) -> Result<()> {
+    for entity in entities {
+        for interface in interfaces.iter().filter(|i| entity.implements.contains(&i.name)) {
+            if entity.id_type != interface.id_type {
+                return Err(CodegenError::InvalidSchema(format!(
+                    "entity `{}` has a {} `id` but its interface `{}` has a {} `id`",
+                    entity.name,
+                    entity.id_type.rust_type(),
+                    interface.name,
+                    interface.id_type.rust_type()
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the ID type of a reference to that of the entity or interface it points to.
+fn resolve_reference_id(field_type: &mut FieldType, id_types: &[(String, IdType)]) {
+    match field_type {
+        FieldType::Reference(target, id_type) | FieldType::Interface(target, id_type) => {
+            if let Some((_, resolved)) = id_types.iter().find(|(name, _)| name == target) {
+                *id_type = *resolved;
+            }
+        }
+        FieldType::Array(inner) => resolve_reference_id(inner, id_types),
+        _ => {}
+    }
+}
+
+/// Timeseries entities and aggregations both need `id: Int8!` and `timestamp: Timestamp!`.
+fn validate_timeseries_fields(kind: &str, name: &str, fields: &[Field]) -> Result<()> {
+    let has = |field_name: &str, check: fn(&FieldType) -> bool| {
+        fields
+            .iter()
+            .any(|f| f.name == field_name && f.is_required && check(&f.field_type))
+    };
+
+    if !has("id", |t| matches!(t, FieldType::Int8)) {
+        return Err(CodegenError::InvalidSchema(format!(
+            "{} `{}` must have an `id: Int8!` field",
+            kind, name
+        )));
+    }
+    if !has("timestamp", |t| matches!(t, FieldType::Timestamp)) {
+        return Err(CodegenError::InvalidSchema(format!(
+            "{} `{}` must have a `timestamp: Timestamp!` field",
+            kind, name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Aggregation functions graph-node supports in `@aggregate(fn:)`.
+const AGGREGATE_FUNCTIONS: &[&str] = &["sum", "count", "min", "max", "first", "last"];
+
+/// Intervals graph-node supports in `@aggregation(intervals:)`.
+const AGGREGATION_INTERVALS: &[&str] = &["hour", "day"];
+
+fn validate_aggregation(aggregation: &Aggregation, entities: &[Entity]) -> Result<()> {
+    let name = &aggregation.name;
+    let invalid = |msg: String| Err(CodegenError::InvalidSchema(msg));
+
+    validate_timeseries_fields("aggregation", name, &aggregation.fields)?;
+
+    if aggregation.intervals.is_empty() {
+        return invalid(format!("aggregation `{}` must list at least one interval", name));
+    }
+    if let Some(interval) = aggregation
+        .intervals
+        .iter()
+        .find(|i| !AGGREGATION_INTERVALS.contains(&i.as_str()))
+    {
+        return invalid(format!(
+            "aggregation `{}` has unsupported interval `{}` (expected one of: {})",
+            name,
+            interval,
+            AGGREGATION_INTERVALS.join(", ")
+        ));
+    }
+
+    let Some(source) = entities.iter().find(|e| e.name == aggregation.source) else {
+        return invalid(format!(
+            "aggregation `{}` has unknown source `{}`",
+            name, aggregation.source
+        ));
+    };
+    if !source.is_timeseries {
+        return invalid(format!(
+            "aggregation `{}` source `{}` must be declared with @entity(timeseries: true)",
+            name, source.name
+        ));
+    }
+
+    for field in &aggregation.fields {
+        if field.name == "id" || field.name == "timestamp" {
+            continue;
+        }
+
+        match &field.aggregate {
+            Some(aggregate) => {
+                if !AGGREGATE_FUNCTIONS.contains(&aggregate.function.as_str()) {
+                    return invalid(format!(
+                        "aggregation `{}` field `{}` has unsupported fn `{}` (expected one of: {})",
+                        name,
+                        field.name,
+                        aggregate.function,
+                        AGGREGATE_FUNCTIONS.join(", ")
+                    ));
+                }
+                if aggregate.function != "count" && aggregate.arg.is_none() {
+                    return invalid(format!(
+                        "aggregation `{}` field `{}` needs an `arg` for fn `{}`",
+                        name, field.name, aggregate.function
+                    ));
+                }
+                // Other args are expressions over the source's fields
+                if let Some(arg) = &aggregate.arg
+                    && is_identifier(arg)
+                    && !source.fields.iter().any(|f| &f.name == arg)
+                {
+                    return invalid(format!(
+                        "aggregation `{}` field `{}` aggregates `{}`, which does not exist on source `{}`",
+                        name, field.name, arg, source.name
+                    ));
+                }
+            }
+            // Fields without @aggregate are dimensions, copied from the source
+            None => {
+                if !source.fields.iter().any(|f| f.name == field.name) {
+                    return invalid(format!(
+                        "aggregation `{}` dimension `{}` does not exist on source `{}`",
+                        name, field.name, source.name
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
 }
 
 fn parse_field_type(
     ty: &graphql_parser::schema::Type<String>,
+    known: &KnownTypes,
 ) -> Result<(FieldType, bool)> {
     use graphql_parser::schema::Type;
 
     match ty {
         Type::NonNullType(inner) => {
-            let (ft, _) = parse_field_type(inner)?;
+            let (ft, _) = parse_field_type(inner, known)?;
             Ok((ft, true))
         }
         Type::ListType(inner) => {
-            let (ft, _) = parse_field_type(inner)?;
+            let (ft, _) = parse_field_type(inner, known)?;
             Ok((FieldType::Array(Box::new(ft)), false))
         }
         Type::NamedType(name) => {
@@ -157,14 +562,152 @@ fn parse_field_type(
                 "BigDecimal" => FieldType::BigDecimal,
                 "Bytes" => FieldType::Bytes,
                 "Boolean" => FieldType::Boolean,
-                other => FieldType::Reference(other.to_string()),
+                "Timestamp" => FieldType::Timestamp,
+                other if known.enums.iter().any(|e| e.name == other) => FieldType::Enum(other.to_string()),
+                // ID types are resolved once every entity has been parsed
+                other if known.interfaces.iter().any(|i| i == other) => {
+                    FieldType::Interface(other.to_string(), IdType::String)
+                }
+                other => FieldType::Reference(other.to_string(), IdType::String),
             };
             Ok((ft, false))
         }
     }
 }
 
+/// Generate a Rust enum for a GraphQL enum, with conversions to and from
+/// the value names graph-node stores.
+fn generate_enum(enum_type: &EnumType) -> Result<String> {
+    let name = &enum_type.name;
+    let variants: Vec<(String, &str)> = enum_type
+        .values
+        .iter()
+        .map(|value| (enum_variant_name(value), value.as_str()))
+        .collect();
+    for (i, (variant, value)) in variants.iter().enumerate() {
+        if let Some((_, other)) = variants[..i].iter().find(|(v, _)| v == variant) {
+            return Err(CodegenError::InvalidSchema(format!(
+                "enum `{}` values `{}` and `{}` both become the Rust variant `{}`",
+                name, other, value, variant
+            )));
+        }
+    }
+
+    // Getters read an unset or unknown value as the first one
+    let mut code = format!(
+        "#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]\npub enum {} {{\n",
+        name
+    );
+    for (i, (variant, _)) in variants.iter().enumerate() {
+        if i == 0 {
+            code.push_str("    #[default]\n");
+        }
+        code.push_str(&format!("    {},\n", variant));
+    }
+    code.push_str("}\n\n");
+
+    code.push_str(&format!("impl {} {{\n", name));
+    code.push_str("    /// The value name as written in the schema.\n");
+    code.push_str("    pub fn as_str(&self) -> &'static str {\n        match self {\n");
+    for (variant, value) in &variants {
+        code.push_str(&format!("            Self::{} => \"{}\",\n", variant, value));
+    }
+    code.push_str("        }\n    }\n\n");
+
+    code.push_str("    /// Parse a value name as written in the schema.\n");
+    code.push_str("    #[allow(clippy::should_implement_trait)]\n");
+    code.push_str("    pub fn from_str(s: &str) -> Option<Self> {\n        match s {\n");
+    for (variant, value) in &variants {
+        code.push_str(&format!("            \"{}\" => Some(Self::{}),\n", value, variant));
+    }
+    code.push_str("            _ => None,\n        }\n    }\n}\n");
+
+    Ok(code)
+}
+
+/// Convert a GraphQL enum value (usually `SCREAMING_CASE`) to a Rust variant name.
+fn enum_variant_name(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for segment in value.split('_').filter(|s| !s.is_empty()) {
+        let shouting = !segment.chars().any(|c| c.is_lowercase());
+        let mut chars = segment.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+        }
+        if shouting {
+            result.extend(chars.map(|c| c.to_ascii_lowercase()));
+        } else {
+            result.extend(chars);
+        }
+    }
+
+    if result == "Self" {
+        result.push('_');
+    }
+    result
+}
+
+/// Generate a trait for a GraphQL interface, implemented by every entity
+/// that declares it, plus a loader that resolves an ID to whichever
+/// implementor is stored under it.
+fn generate_interface(interface: &Interface, entities: &[Entity]) -> String {
+    let name = &interface.name;
+    let implementors: Vec<&Entity> = entities
+        .iter()
+        .filter(|e| e.implements.iter().any(|i| i == name))
+        .collect();
+    let fields: Vec<&Field> = interface.fields.iter().filter(|f| !f.is_derived).collect();
+
+    let mut code = format!(
+        "/// Entities implementing the `{name}` interface.\npub trait {name} {{\n    /// The concrete entity type name.\n    fn entity_type(&self) -> &'static str;\n\n",
+        name = name
+    );
+    for field in &fields {
+        let (return_type, _) = getter_parts(field);
+        code.push_str(&format!("    fn {}(&self) -> {};\n", to_snake_case(&field.name), return_type));
+    }
+    code.push_str("}\n\n");
+
+    // Resolve an ID by trying each implementor in turn
+    code.push_str(&format!(
+        "impl dyn {name} {{\n    /// Load whichever `{name}` implementor is stored under `id`.\n    pub fn load(id: impl EntityKey<{id_type}>) -> Option<Box<dyn {name}>> {{\n",
+        name = name,
+        id_type = interface.id_type.rust_type()
+    ));
+    for entity in implementors.iter().filter(|e| !e.is_timeseries) {
+        code.push_str(&format!(
+            "        if let Some(entity) = {}::load(&id) {{\n            return Some(Box::new(entity));\n        }}\n",
+            entity.name
+        ));
+    }
+    code.push_str("        None\n    }\n}\n");
+
+    for entity in &implementors {
+        code.push_str(&format!(
+            "\nimpl {} for {} {{\n    fn entity_type(&self) -> &'static str {{\n        Self::ENTITY_TYPE\n    }}\n",
+            name, entity.name
+        ));
+        for field in &fields {
+            let (return_type, body) = getter_parts(field);
+            code.push_str(&format!(
+                "\n    fn {}(&self) -> {} {{\n        {}\n    }}\n",
+                to_snake_case(&field.name),
+                return_type,
+                body
+            ));
+        }
+        code.push_str("}\n");
+    }
+
+    code
+}
+
 fn generate_entity_struct(entity: &Entity) -> String {
+    if entity.is_timeseries {
+        return generate_timeseries_struct(entity);
+    }
+
     let name = &entity.name;
 
     // Add doc comment for immutable entities
@@ -184,12 +727,14 @@ fn generate_entity_struct(entity: &Entity) -> String {
     // Constructor
     code.push_str(&format!(
         "impl {name} {{\n\
-            pub fn new(id: impl Into<String>) -> Self {{\n\
+            pub fn new(id: impl Into<{id_type}>) -> Self {{\n\
                 let mut data = EntityData::new();\n\
-                data.set(\"id\", Value::String(id.into()));\n\
+                data.set(\"id\", Value::{variant}(id.into()));\n\
                 Self {{ data }}\n\
             }}\n\n",
-        name = name
+        name = name,
+        id_type = entity.id_type.rust_type(),
+        variant = entity.id_type.value_variant()
     ));
 
     // Getters
@@ -202,6 +747,11 @@ fn generate_entity_struct(entity: &Entity) -> String {
         code.push_str(&getter);
     }
 
+    // Reverse lookups for derived fields
+    for field in &entity.fields {
+        code.push_str(&generate_loaded_getter(field));
+    }
+
     // Setters (generated for all entities - immutability is enforced by graph-node on update)
     for field in &entity.fields {
         if field.is_derived || field.name == "id" {
@@ -215,48 +765,155 @@ fn generate_entity_struct(entity: &Entity) -> String {
     code.push_str("}\n\n");
 
     // Entity trait implementation
+    code.push_str(&generate_entity_impl(entity, "Self::new(id)"));
+
+    // TestableEntity trait implementation (native builds only)
     code.push_str(&format!(
+        "#[cfg(not(target_arch = \"wasm32\"))]\n\
+         impl yogurt_runtime::testing::TestableEntity for {name} {{\n\
+             fn as_data(&self) -> &EntityData {{\n\
+                 &self.data\n\
+             }}\n\n\
+             fn from_data(data: EntityData) -> Self {{\n\
+                 Self {{ data }}\n\
+             }}\n\
+         }}\n\n",
+        name = name
+    ));
+
+    // Generate builder pattern
+    code.push_str(&generate_entity_builder(entity));
+
+    code
+}
+
+/// Generate the `Entity` impl, storing the entity under the key of its ID.
+///
+/// `create` is the body of `Entity::create`, with the new ID in `id`.
+fn generate_entity_impl(entity: &Entity, create: &str) -> String {
+    format!(
         "impl Entity for {name} {{\n\
+            type Id = {id_type};\n\n\
             const ENTITY_TYPE: &'static str = \"{name}\";\n\n\
-            fn id(&self) -> &str {{\n\
-                self.data.get_string(\"id\")\n\
+            fn id(&self) -> &Self::Id {{\n\
+                self.data.get_id()\n\
             }}\n\n\
             fn save(&self) {{\n\
-                store::set(Self::ENTITY_TYPE, self.id(), &self.data);\n\
+                store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);\n\
             }}\n\n\
-            fn load(id: &str) -> Option<Self> {{\n\
-                store::get(Self::ENTITY_TYPE, id).map(|data| Self {{ data }})\n\
+            fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {{\n\
+                store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self {{ data }})\n\
             }}\n\n\
-            fn remove(id: &str) {{\n\
-                store::remove(Self::ENTITY_TYPE, id);\n\
+            fn remove(id: impl EntityKey<Self::Id>) {{\n\
+                store::remove(Self::ENTITY_TYPE, &id.to_key());\n\
             }}\n\n\
-            fn create(id: impl Into<String>) -> Self {{\n\
-                Self::new(id)\n\
+            fn create(id: impl Into<Self::Id>) -> Self {{\n\
+                {create}\n\
             }}\n\
         }}\n\n",
+        name = entity.name,
+        id_type = entity.id_type.rust_type(),
+        create = create
+    )
+}
+
+/// Generate a timeseries data point.
+///
+/// graph-node assigns the `id` and `timestamp` of every data point itself,
+/// so the constructor takes no ID and neither field gets a setter. Saving
+/// goes through `Entity`, keyed by the `Int8` ID like any other entity.
+fn generate_timeseries_struct(entity: &Entity) -> String {
+    let name = &entity.name;
+
+    let mut code = format!(
+        "/// Timeseries data point — graph-node assigns `id` and `timestamp` on save.\n\
+         pub struct {name} {{\n    data: EntityData,\n}}\n\n\
+         impl {name} {{\n\
+         \x20   /// Create a data point with the next auto-assigned ID.\n\
+         \x20   pub fn new() -> Self {{\n\
+         \x20       let mut data = EntityData::new();\n\
+         \x20       data.set(\"id\", Value::Int8(store::next_timeseries_id(Self::ENTITY_TYPE)));\n\
+         \x20       Self {{ data }}\n\
+         \x20   }}\n\n",
         name = name
+    );
+
+    for field in &entity.fields {
+        if field.is_derived {
+            continue;
+        }
+        code.push_str(&generate_getter(field));
+    }
+
+    for field in &entity.fields {
+        if field.is_derived || field.name == "id" || field.name == "timestamp" {
+            continue;
+        }
+        code.push_str(&generate_setter(field));
+    }
+
+    code.push_str("}\n\n");
+
+    code.push_str(&generate_entity_impl(
+        entity,
+        "let mut data = EntityData::new();\n\
+         \x20       data.set(\"id\", Value::Int8(id.into()));\n\
+         \x20       Self { data }",
     ));
 
-    // TestableEntity trait implementation (native builds only)
     code.push_str(&format!(
-        "#[cfg(not(target_arch = \"wasm32\"))]\n\
-         impl yogurt_runtime::testing::TestableEntity for {name} {{\n\
-             fn as_data(&self) -> &EntityData {{\n\
-                 &self.data\n\
-             }}\n\n\
-             fn from_data(data: EntityData) -> Self {{\n\
-                 Self {{ data }}\n\
-             }}\n\
+        "impl Default for {name} {{\n\
+         \x20   fn default() -> Self {{\n\
+         \x20       Self::new()\n\
+         \x20   }}\n\
          }}\n\n",
         name = name
     ));
 
-    // Generate builder pattern
     code.push_str(&generate_entity_builder(entity));
 
     code
 }
 
+/// Generate a read-only type for an aggregation.
+///
+/// Aggregations are computed by graph-node from their source timeseries, so
+/// mappings never write them; `from_data` exists for tests.
+fn generate_aggregation_struct(aggregation: &Aggregation) -> String {
+    let name = &aggregation.name;
+    let intervals = aggregation
+        .intervals
+        .iter()
+        .map(|i| format!("\"{}\"", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut code = format!(
+        "/// Aggregation of `{source}` — computed by graph-node, read-only.\n\
+         pub struct {name} {{\n    data: EntityData,\n}}\n\n\
+         impl {name} {{\n\
+         \x20   /// The entity type name as it appears in the GraphQL schema.\n\
+         \x20   pub const ENTITY_TYPE: &'static str = \"{name}\";\n\n\
+         \x20   /// The intervals graph-node aggregates over.\n\
+         \x20   pub const INTERVALS: &'static [&'static str] = &[{intervals}];\n\n\
+         \x20   /// Wrap aggregated entity data.\n\
+         \x20   pub fn from_data(data: EntityData) -> Self {{\n\
+         \x20       Self {{ data }}\n\
+         \x20   }}\n\n",
+        name = name,
+        source = aggregation.source,
+        intervals = intervals
+    );
+
+    for field in &aggregation.fields {
+        code.push_str(&generate_getter(field));
+    }
+
+    code.push_str("}\n");
+
+    code
+}
+
 /// Generate a fluent builder for an entity.
 ///
 /// Creates a `{Entity}Builder` struct with chainable setter methods
@@ -265,6 +922,18 @@ fn generate_entity_builder(entity: &Entity) -> String {
     let name = &entity.name;
     let builder_name = format!("{}Builder", name);
 
+    // Timeseries data points get their ID from graph-node
+    let (id_param, id_arg, example_id) = if entity.is_timeseries {
+        (String::new(), "", "")
+    } else {
+        let example_id = match entity.id_type {
+            IdType::String => "\"my-id\"",
+            IdType::Bytes => "address",
+            IdType::Int8 => "1",
+        };
+        (format!("id: impl Into<{}>", entity.id_type.rust_type()), "id", example_id)
+    };
+
     let mut code = String::new();
 
     // Builder struct
@@ -274,7 +943,7 @@ fn generate_entity_builder(entity: &Entity) -> String {
          /// # Example\n\
          ///\n\
          /// ```ignore\n\
-         /// let entity = {name}::builder(\"my-id\")\n\
+         /// let entity = {name}::builder({example_id})\n\
          ///     .field_name(value)\n\
          ///     .build();\n\
          /// ```\n\
@@ -282,7 +951,8 @@ fn generate_entity_builder(entity: &Entity) -> String {
              inner: {name},\n\
          }}\n\n",
         name = name,
-        builder_name = builder_name
+        builder_name = builder_name,
+        example_id = example_id
     ));
 
     // Add builder() method to entity
@@ -293,18 +963,21 @@ fn generate_entity_builder(entity: &Entity) -> String {
              /// # Example\n\
              ///\n\
              /// ```ignore\n\
-             /// let entity = {name}::builder(\"my-id\")\n\
+             /// let entity = {name}::builder({example_id})\n\
              ///     .field_name(value)\n\
              ///     .build();\n\
              /// ```\n\
-             pub fn builder(id: impl Into<String>) -> {builder_name} {{\n\
+             pub fn builder({id_param}) -> {builder_name} {{\n\
                  {builder_name} {{\n\
-                     inner: {name}::new(id),\n\
+                     inner: {name}::new({id_arg}),\n\
                  }}\n\
              }}\n\
          }}\n\n",
         name = name,
-        builder_name = builder_name
+        builder_name = builder_name,
+        example_id = example_id,
+        id_param = id_param,
+        id_arg = id_arg
     ));
 
     // Builder impl with chainable setters
@@ -315,6 +988,9 @@ fn generate_entity_builder(entity: &Entity) -> String {
         if field.is_derived || field.name == "id" {
             continue;
         }
+        if entity.is_timeseries && field.name == "timestamp" {
+            continue;
+        }
 
         let setter = generate_builder_setter(field);
         code.push_str(&setter);
@@ -340,10 +1016,111 @@ fn generate_entity_builder(entity: &Entity) -> String {
 }
 
 fn generate_getter(field: &Field) -> String {
+    let method_name = to_snake_case(&field.name);
+    let (return_type, getter_body) = getter_parts(field);
+
+    let mut code = format!(
+        "    pub fn {}(&self) -> {} {{\n        {}\n    }}\n\n",
+        method_name, return_type, getter_body
+    );
+
+    // Interface references also get a loader for the concrete entity
+    match &field.field_type {
+        FieldType::Interface(interface, _) => {
+            let body = if field.is_required {
+                format!("<dyn {}>::load(self.{}())", interface, method_name)
+            } else {
+                format!("self.{}().and_then(<dyn {}>::load)", method_name, interface)
+            };
+            code.push_str(&format!(
+                "    pub fn load_{}(&self) -> Option<Box<dyn {}>> {{\n        {}\n    }}\n\n",
+                method_name, interface, body
+            ));
+        }
+        FieldType::Array(inner) => {
+            if let FieldType::Interface(interface, _) = inner.as_ref() {
+                code.push_str(&format!(
+                    "    pub fn load_{0}(&self) -> Vec<Box<dyn {1}>> {{\n        self.{0}().iter().filter_map(<dyn {1}>::load).collect()\n    }}\n\n",
+                    method_name, interface
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    code
+}
+
+/// Generate `<field>_loaded()` for a `@derivedFrom` field, resolving the
+/// reverse relation with `store.loadRelated`.
+fn generate_loaded_getter(field: &Field) -> String {
+    let Some(derived_from) = &field.derived_from else {
+        return String::new();
+    };
+    let target = match &field.field_type {
+        FieldType::Reference(target, _) => target,
+        FieldType::Array(inner) => match inner.as_ref() {
+            FieldType::Reference(target, _) => target,
+            _ => return String::new(),
+        },
+        _ => return String::new(),
+    };
+
+    format!(
+        "    /// Load the `{target}` entities whose `{derived_from}` refers to this entity.\n\
+         \x20   pub fn {method}_loaded(&self) -> Vec<{target}> {{\n\
+         \x20       #[cfg(not(target_arch = \"wasm32\"))]\n\
+         \x20       yogurt_runtime::testing::register_derived_field(Self::ENTITY_TYPE, \"{name}\", \"{target}\", \"{derived_from}\");\n\
+         \x20       store::load_related(Self::ENTITY_TYPE, &Entity::id(self).to_key(), \"{name}\")\n\
+         \x20           .into_iter()\n\
+         \x20           .map(|data| {target} {{ data }})\n\
+         \x20           .collect()\n\
+         \x20   }}\n\n",
+        target = target,
+        derived_from = derived_from,
+        method = to_snake_case(&field.name),
+        name = field.name
+    )
+}
+
+/// References to entities with `Bytes` or `Int8` IDs are accessed like a
+/// field of that type; string references keep their `String` accessors.
+fn id_scalar_field(field: &Field) -> Option<Field> {
+    let scalar = |id_type: IdType| match id_type {
+        IdType::String => None,
+        IdType::Bytes => Some(FieldType::Bytes),
+        IdType::Int8 => Some(FieldType::Int8),
+    };
+    let field_type = match &field.field_type {
+        FieldType::Reference(_, id_type) | FieldType::Interface(_, id_type) => scalar(*id_type)?,
+        FieldType::Array(inner) => match inner.as_ref() {
+            FieldType::Reference(_, id_type) | FieldType::Interface(_, id_type) => {
+                FieldType::Array(Box::new(scalar(*id_type)?))
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(Field {
+        name: field.name.clone(),
+        field_type,
+        is_required: field.is_required,
+        is_derived: field.is_derived,
+        derived_from: None,
+        aggregate: None,
+    })
+}
+
+/// The return type and body of a field's getter.
+fn getter_parts(field: &Field) -> (String, String) {
+    if let Some(id_field) = id_scalar_field(field) {
+        return getter_parts(&id_field);
+    }
+
     let name = &field.name;
-    let method_name = to_snake_case(name);
 
-    let (return_type, getter_body) = match &field.field_type {
+    match &field.field_type {
         FieldType::Id | FieldType::String => {
             if field.is_required {
                 ("&str".to_string(), format!("self.data.get_string(\"{}\")", name))
@@ -411,6 +1188,19 @@ fn generate_getter(field: &Field) -> String {
                 )
             }
         }
+        FieldType::Timestamp => {
+            if field.is_required {
+                (
+                    "Timestamp".to_string(),
+                    format!("self.data.get_timestamp_opt(\"{}\").unwrap_or_default()", name),
+                )
+            } else {
+                (
+                    "Option<Timestamp>".to_string(),
+                    format!("self.data.get_timestamp_opt(\"{}\")", name),
+                )
+            }
+        }
         FieldType::Array(inner) => {
             match inner.as_ref() {
                 FieldType::String | FieldType::Id => {
@@ -429,12 +1219,22 @@ fn generate_getter(field: &Field) -> String {
                     ("Vec<i32>".to_string(), format!("self.data.get_int_array(\"{}\")", name))
                 }
                 FieldType::Int8 => {
-                    ("Vec<i64>".to_string(), format!("self.data.get_int_array(\"{}\").into_iter().map(|i| i as i64).collect()", name))
+                    ("Vec<i64>".to_string(), format!("self.data.get_int8_array(\"{}\")", name))
                 }
                 FieldType::Boolean => {
                     ("Vec<bool>".to_string(), format!("self.data.get_bool_array(\"{}\")", name))
                 }
-                FieldType::Reference(_) => {
+                FieldType::Timestamp => {
+                    ("Vec<Timestamp>".to_string(), format!("self.data.get_timestamp_array(\"{}\")", name))
+                }
+                FieldType::Enum(enum_name) => (
+                    format!("Vec<{}>", enum_name),
+                    format!(
+                        "self.data.get_string_array(\"{}\").iter().filter_map(|s| {}::from_str(s)).collect()",
+                        name, enum_name
+                    ),
+                ),
+                FieldType::Reference(..) | FieldType::Interface(..) => {
                     // References stored as ID strings
                     ("Vec<String>".to_string(), format!("self.data.get_string_array(\"{}\")", name))
                 }
@@ -444,7 +1244,23 @@ fn generate_getter(field: &Field) -> String {
                 }
             }
         }
-        FieldType::Reference(_) => {
+        FieldType::Enum(enum_name) => {
+            if field.is_required {
+                (
+                    enum_name.clone(),
+                    format!(
+                        "{0}::from_str(self.data.get_string(\"{1}\")).unwrap_or_default()",
+                        enum_name, name
+                    ),
+                )
+            } else {
+                (
+                    format!("Option<{}>", enum_name),
+                    format!("self.data.get_string_opt(\"{}\").and_then({}::from_str)", name, enum_name),
+                )
+            }
+        }
+        FieldType::Reference(..) | FieldType::Interface(..) => {
             // References are stored as ID strings
             if field.is_required {
                 ("String".to_string(), format!("self.data.get_string(\"{}\").to_string()", name))
@@ -452,15 +1268,14 @@ fn generate_getter(field: &Field) -> String {
                 ("Option<String>".to_string(), format!("self.data.get_string_opt(\"{}\").map(|s| s.to_string())", name))
             }
         }
-    };
-
-    format!(
-        "    pub fn {}(&self) -> {} {{\n        {}\n    }}\n\n",
-        method_name, return_type, getter_body
-    )
+    }
 }
 
 fn generate_setter(field: &Field) -> String {
+    if let Some(id_field) = id_scalar_field(field) {
+        return generate_setter(&id_field);
+    }
+
     let name = &field.name;
     let snake_name = to_snake_case(name);
     let method_name = format!("set_{}", snake_name);
@@ -476,6 +1291,8 @@ fn generate_setter(field: &Field) -> String {
         FieldType::BigDecimal => ("impl Into<BigDecimal>".to_string(), "Value::BigDecimal(val.into())"),
         FieldType::Bytes => ("impl Into<Bytes>".to_string(), "Value::Bytes(val.into())"),
         FieldType::Boolean => ("bool".to_string(), "Value::Bool(val)"),
+        FieldType::Timestamp => ("impl Into<Timestamp>".to_string(), "Value::Timestamp(val.into())"),
+        FieldType::Enum(enum_name) => (enum_name.clone(), "Value::String(val.as_str().into())"),
         FieldType::Array(inner) => {
             let (param_type, map_expr) = match inner.as_ref() {
                 FieldType::String | FieldType::Id => (
@@ -506,7 +1323,17 @@ fn generate_setter(field: &Field) -> String {
                     "impl IntoIterator<Item = bool>",
                     "val.into_iter().map(Value::Bool).collect()",
                 ),
-                FieldType::Reference(_) => (
+                FieldType::Timestamp => (
+                    "impl IntoIterator<Item = impl Into<Timestamp>>",
+                    "val.into_iter().map(|v| Value::Timestamp(v.into())).collect()",
+                ),
+                FieldType::Enum(enum_name) => {
+                    return format!(
+                        "    pub fn {}(&mut self, val: impl IntoIterator<Item = {}>) {{\n        self.data.set(\"{}\", Value::Array(val.into_iter().map(|v| Value::String(v.as_str().into())).collect()));\n    }}\n\n",
+                        method_name, enum_name, name
+                    );
+                }
+                FieldType::Reference(..) | FieldType::Interface(..) => (
                     "impl IntoIterator<Item = impl Into<String>>",
                     "val.into_iter().map(|v| Value::String(v.into())).collect()",
                 ),
@@ -520,7 +1347,7 @@ fn generate_setter(field: &Field) -> String {
                 method_name, param_type, name, map_expr
             );
         }
-        FieldType::Reference(_) => {
+        FieldType::Reference(..) | FieldType::Interface(..) => {
             ("impl Into<String>".to_string(), "Value::String(val.into())")
         }
     };
@@ -543,6 +1370,10 @@ fn generate_setter(field: &Field) -> String {
 
 /// Generate a chainable builder setter method.
 fn generate_builder_setter(field: &Field) -> String {
+    if let Some(id_field) = id_scalar_field(field) {
+        return generate_builder_setter(&id_field);
+    }
+
     let name = &field.name;
     let snake_name = to_snake_case(name);
 
@@ -555,9 +1386,11 @@ fn generate_builder_setter(field: &Field) -> String {
         FieldType::BigDecimal => "impl Into<BigDecimal>".to_string(),
         FieldType::Bytes => "impl Into<Bytes>".to_string(),
         FieldType::Boolean => "bool".to_string(),
+        FieldType::Timestamp => "impl Into<Timestamp>".to_string(),
+        FieldType::Enum(enum_name) => enum_name.clone(),
         FieldType::Array(inner) => {
             match inner.as_ref() {
-                FieldType::String | FieldType::Id | FieldType::Reference(_) => {
+                FieldType::String | FieldType::Id | FieldType::Reference(..) | FieldType::Interface(..) => {
                     "impl IntoIterator<Item = impl Into<String>>".to_string()
                 }
                 FieldType::Bytes => "impl IntoIterator<Item = impl Into<Bytes>>".to_string(),
@@ -566,10 +1399,12 @@ fn generate_builder_setter(field: &Field) -> String {
                 FieldType::Int => "impl IntoIterator<Item = i32>".to_string(),
                 FieldType::Int8 => "impl IntoIterator<Item = i64>".to_string(),
                 FieldType::Boolean => "impl IntoIterator<Item = bool>".to_string(),
+                FieldType::Timestamp => "impl IntoIterator<Item = impl Into<Timestamp>>".to_string(),
+                FieldType::Enum(enum_name) => format!("impl IntoIterator<Item = {}>", enum_name),
                 FieldType::Array(_) => return String::new(), // Skip nested arrays
             }
         }
-        FieldType::Reference(_) => "impl Into<String>".to_string(),
+        FieldType::Reference(..) | FieldType::Interface(..) => "impl Into<String>".to_string(),
     };
 
     format!(
@@ -610,3 +1445,205 @@ fn to_snake_case(s: &str) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL_SCHEMA: &str = r#"
+        enum PoolKind {
+          STABLE
+          VOLATILE
+        }
+
+        type Pool @entity {
+          id: ID!
+          kind: PoolKind!
+          previousKind: PoolKind
+          history: [PoolKind!]!
+        }
+    "#;
+
+    #[test]
+    fn test_enum_variant_name() {
+        assert_eq!(enum_variant_name("STABLE"), "Stable");
+        assert_eq!(enum_variant_name("FOO_BAR"), "FooBar");
+        assert_eq!(enum_variant_name("FooBar"), "FooBar");
+        assert_eq!(enum_variant_name("_private"), "Private");
+        assert_eq!(enum_variant_name("Self"), "Self_");
+    }
+
+    #[test]
+    fn test_enum_getters_do_not_panic() {
+        let code = SchemaParser::parse(POOL_SCHEMA).unwrap().generate_rust().unwrap();
+
+        assert!(code.contains("#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]"));
+        assert!(code.contains("    #[default]\n    Stable,\n    Volatile,\n"));
+        assert!(code.contains("PoolKind::from_str(self.data.get_string(\"kind\")).unwrap_or_default()"));
+        assert!(code.contains("self.data.get_string_opt(\"previousKind\").and_then(PoolKind::from_str)"));
+        assert!(!code.contains(".expect("));
+    }
+
+    #[test]
+    fn test_enum_values_with_same_variant_are_rejected() {
+        let enum_type = EnumType {
+            name: "Side".into(),
+            values: vec!["BUY".into(), "FOO_BAR".into(), "FooBar".into()],
+        };
+        let err = generate_enum(&enum_type).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid schema: enum `Side` values `FOO_BAR` and `FooBar` both become the Rust variant `FooBar`"
+        );
+
+        let schema = SchemaParser::parse(
+            "enum Side { BUY FOO_BAR FooBar }\ntype Order @entity { id: ID! side: Side! }",
+        )
+        .unwrap();
+        assert!(schema.generate_rust().is_err());
+    }
+
+    const PRICE_POINT: &str = r#"
+        type PricePoint @entity(timeseries: true) {
+          id: Int8!
+          timestamp: Timestamp!
+          token: Bytes!
+          price: BigDecimal!
+        }
+    "#;
+
+    /// The error parsing `schema` fails with.
+    fn schema_error(schema: &str) -> String {
+        SchemaParser::parse(schema).unwrap_err().to_string()
+    }
+
+    /// The error parsing [`PRICE_POINT`] and an aggregation over it fails with.
+    fn aggregation_error(aggregation: &str) -> String {
+        schema_error(&format!("{}\n{}", PRICE_POINT, aggregation))
+    }
+
+    #[test]
+    fn test_timeseries_requires_int8_id_and_timestamp() {
+        let err = schema_error(
+            "type PricePoint @entity(timeseries: true) { id: ID! timestamp: Timestamp! }",
+        );
+        assert_eq!(
+            err,
+            "Invalid schema: timeseries entity `PricePoint` must have an `id: Int8!` field"
+        );
+
+        let err = schema_error("type PricePoint @entity(timeseries: true) { id: Int8! price: BigDecimal! }");
+        assert_eq!(
+            err,
+            "Invalid schema: timeseries entity `PricePoint` must have a `timestamp: Timestamp!` field"
+        );
+
+        let err = schema_error("type PricePoint @entity(timeseries: true) { id: Int8! timestamp: Timestamp }");
+        assert!(err.contains("must have a `timestamp: Timestamp!` field"), "{}", err);
+
+        let err = aggregation_error(
+            r#"type PriceStats @aggregation(intervals: ["hour"], source: "PricePoint") { id: Int8! }"#,
+        );
+        assert_eq!(
+            err,
+            "Invalid schema: aggregation `PriceStats` must have a `timestamp: Timestamp!` field"
+        );
+    }
+
+    #[test]
+    fn test_aggregation_rejects_unknown_fn() {
+        let err = aggregation_error(
+            r#"type PriceStats @aggregation(intervals: ["hour"], source: "PricePoint") {
+                 id: Int8!
+                 timestamp: Timestamp!
+                 avgPrice: BigDecimal! @aggregate(fn: "avg", arg: "price")
+               }"#,
+        );
+        assert_eq!(
+            err,
+            "Invalid schema: aggregation `PriceStats` field `avgPrice` has unsupported fn `avg` \
+             (expected one of: sum, count, min, max, first, last)"
+        );
+    }
+
+    #[test]
+    fn test_aggregation_rejects_unsupported_interval() {
+        let err = aggregation_error(
+            r#"type PriceStats @aggregation(intervals: ["hour", "week"], source: "PricePoint") {
+                 id: Int8!
+                 timestamp: Timestamp!
+               }"#,
+        );
+        assert_eq!(
+            err,
+            "Invalid schema: aggregation `PriceStats` has unsupported interval `week` (expected one of: hour, day)"
+        );
+    }
+
+    #[test]
+    fn test_aggregation_rejects_missing_or_plain_source() {
+        let err = aggregation_error(
+            r#"type PriceStats @aggregation(intervals: ["hour"], source: "Price") {
+                 id: Int8!
+                 timestamp: Timestamp!
+               }"#,
+        );
+        assert_eq!(err, "Invalid schema: aggregation `PriceStats` has unknown source `Price`");
+
+        let err = schema_error(
+            r#"type Token @entity { id: ID! price: BigDecimal! }
+               type PriceStats @aggregation(intervals: ["hour"], source: "Token") {
+                 id: Int8!
+                 timestamp: Timestamp!
+               }"#,
+        );
+        assert_eq!(
+            err,
+            "Invalid schema: aggregation `PriceStats` source `Token` must be declared with @entity(timeseries: true)"
+        );
+    }
+
+    #[test]
+    fn test_aggregation_rejects_arg_missing_from_source() {
+        let err = aggregation_error(
+            r#"type PriceStats @aggregation(intervals: ["day"], source: "PricePoint") {
+                 id: Int8!
+                 timestamp: Timestamp!
+                 volume: BigDecimal! @aggregate(fn: "sum", arg: "amount")
+               }"#,
+        );
+        assert_eq!(
+            err,
+            "Invalid schema: aggregation `PriceStats` field `volume` aggregates `amount`, \
+             which does not exist on source `PricePoint`"
+        );
+
+        // Expressions over the source's fields are left to graph-node
+        let schema = format!(
+            "{}\n{}",
+            PRICE_POINT,
+            r#"type PriceStats @aggregation(intervals: ["day"], source: "PricePoint") {
+                 id: Int8!
+                 timestamp: Timestamp!
+                 value: BigDecimal! @aggregate(fn: "sum", arg: "price * price")
+               }"#
+        );
+        assert!(SchemaParser::parse(&schema).is_ok());
+    }
+
+    #[test]
+    fn test_timeseries_struct_saves_through_entity() {
+        let schema = SchemaParser::parse(PRICE_POINT).unwrap();
+        let code = generate_timeseries_struct(&schema.entities[0]);
+
+        assert!(code.contains("pub fn new() -> Self {"));
+        assert!(code.contains("store::next_timeseries_id(Self::ENTITY_TYPE)"));
+        assert!(code.contains("impl Entity for PricePoint {\ntype Id = i64;"));
+        assert!(code.contains("store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);"));
+        assert!(code.contains("data.set(\"id\", Value::Int8(id.into()));"));
+        assert!(code.contains("pub fn set_price("));
+        assert!(!code.contains("pub fn set_id("));
+        assert!(!code.contains("pub fn set_timestamp("));
+        assert!(!code.contains("to_string()"));
+    }
+}
@@ -5,7 +5,7 @@
 
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, FnArg, ItemFn, Pat};
+use syn::{parse_macro_input, FnArg, ItemFn, Pat, ReturnType};
 
 /// Transform a mapping handler function into a graph-node-compatible WASM export.
 ///
@@ -30,10 +30,26 @@ use syn::{parse_macro_input, FnArg, ItemFn, Pat};
 ///
 /// #[no_mangle]
 /// pub extern "C" fn handleTransfer(ptr: u32) {
-///     let event = TransferEvent::from_asc_ptr(ptr);
+///     let mark = yogurt_runtime::allocator::mark();
+///     let event = yogurt_runtime::handler_arg::<TransferEvent>(ptr);
 ///     handle_transfer(event);
+///     unsafe { yogurt_runtime::allocator::reset(mark) };
 /// }
 /// ```
+///
+/// `handler_arg` decodes an AssemblyScript object, or a Rust ABI message when
+/// yogurt-runtime is built with the `rust-abi` feature. Resetting the heap
+/// releases the argument and everything the handler allocated, so handlers
+/// must not keep heap data in statics between invocations.
+///
+/// Natively, while `YOGURT_WASM_MODULE` is set by `yogurt test --wasm`, the
+/// function runs `handleTransfer` in the compiled module instead of its body,
+/// so tests calling handlers directly exercise the WASM build.
+///
+/// A handler may also return `Result<(), HandlerError>`. The wrapper passes
+/// the result to `yogurt_runtime::handler::finish`, which fails the handler
+/// with a critical log on `Err`. Under `yogurt test --wasm` that failure
+/// comes back to the test as the same `Err`.
 #[proc_macro_attribute]
 pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
@@ -78,14 +94,31 @@ pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     };
 
     let param_type = &param.ty;
+    let fn_output = &input.sig.output;
 
     // Generate the wrapper function name (camelCase for WASM export)
     let wrapper_name = format_ident!("{}", export_name);
 
+    // Fallible handlers hand their result to the runtime, which decides
+    // between failing the handler and skipping it
+    let call = match fn_output {
+        ReturnType::Default => quote! { #fn_name(#param_name); },
+        ReturnType::Type(..) => quote! {
+            yogurt_runtime::handler::finish(#export_name, #fn_name(#param_name));
+        },
+    };
+
     let expanded = quote! {
         // Original function (internal, not exported)
         #(#fn_attrs)*
-        #fn_vis fn #fn_name(#param_name: #param_type) #fn_block
+        #fn_vis fn #fn_name(#param_name: #param_type) #fn_output {
+            // Under `yogurt test --wasm`, run the export in the compiled module
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(output) = yogurt_runtime::testing::run_in_wasm_host(#export_name, &#param_name) {
+                return output;
+            }
+            #fn_block
+        }
 
         // WASM export wrapper
         #[unsafe(no_mangle)]
@@ -94,8 +127,12 @@ pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
             if ptr == 0 {
                 return;
             }
-            let #param_name = <#param_type as yogurt_runtime::asc::FromAscPtr>::from_asc_ptr(ptr);
-            #fn_name(#param_name);
+            let mark = yogurt_runtime::allocator::mark();
+            let #param_name = yogurt_runtime::handler_arg::<#param_type>(ptr);
+            #call
+            // SAFETY: the argument was decoded into owned values and the
+            // handler has returned, so nothing refers to the released memory
+            unsafe { yogurt_runtime::allocator::reset(mark) };
         }
     };
 
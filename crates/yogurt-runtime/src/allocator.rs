@@ -11,7 +11,12 @@
 //! Pointer -  4: rtSize   (u32) — Payload byte length
 //! Pointer     : [payload bytes...]
 //! ```
+//!
+//! Nothing is freed individually. Instead, `#[handler]` wrappers take a
+//! [`mark`] on entry and [`reset`] the heap to it on exit, so an instance
+//! graph-node reuses across triggers doesn't keep growing its memory.
 
+use alloc::vec::Vec;
 use core::sync::atomic::{AtomicU32, Ordering};
 
 /// Size of the AssemblyScript managed object header in bytes
@@ -29,6 +34,7 @@ pub mod class_id {
     // TypedArray types (from IndexForAscTypeId)
     pub const INT64ARRAY: u32 = 5;
     pub const UINT8ARRAY: u32 = 6;  // For Bytes and BigInt
+    pub const BIG_DECIMAL: u32 = 12;            // AscBigDecimal
 
     // Graph-node specific type IDs (from IndexForAscTypeId)
     pub const ARRAY_STORE_VALUE: u32 = 16;                      // ArrayStoreValue
@@ -36,6 +42,7 @@ pub mod class_id {
     pub const STORE_VALUE: u32 = 31;                            // StoreValue enum
     pub const TYPED_MAP_ENTRY_STRING_STORE_VALUE: u32 = 34;     // TypedMapEntryStringStoreValue
     pub const TYPED_MAP_STRING_STORE_VALUE: u32 = 36;           // TypedMapStringStoreValue (Entity)
+    pub const ARRAY_TYPED_MAP_STRING_STORE_VALUE: u32 = 1004;   // ArrayTypedMapStringStoreValue (store.loadRelated)
 
     // Aliases for backwards compatibility
     pub const ARRAY_PTR: u32 = ARRAY_TYPED_MAP_ENTRY_STRING_STORE_VALUE;
@@ -46,6 +53,53 @@ pub mod class_id {
     pub const ARRAY_ETHEREUM_VALUE: u32 = 15;  // ArrayEthereumValue
     pub const SMART_CONTRACT_CALL: u32 = 22;   // SmartContractCall
     pub const ETHEREUM_VALUE: u32 = 30;        // EthereumValue enum
+    pub const ARRAY_EVENT_PARAM: u32 = 19;     // ArrayEventParam
+    pub const EVENT_PARAM: u32 = 23;           // EventParam
+    pub const ETHEREUM_TRANSACTION: u32 = 24;  // EthereumTransaction
+    pub const ETHEREUM_BLOCK: u32 = 25;        // EthereumBlock
+    pub const ETHEREUM_CALL: u32 = 26;         // EthereumCall
+    pub const ETHEREUM_EVENT: u32 = 33;        // EthereumEvent
+
+    // JSON types (from IndexForAscTypeId)
+    pub const ARRAY_JSON_VALUE: u32 = 17;                        // ArrayJsonValue
+    pub const ARRAY_STRING: u32 = 18;                            // ArrayString
+    pub const ARRAY_TYPED_MAP_ENTRY_STRING_JSON_VALUE: u32 = 20; // ArrayTypedMapEntryStringJsonValue
+    pub const JSON_VALUE: u32 = 32;                              // JsonValue enum
+    pub const TYPED_MAP_ENTRY_STRING_JSON_VALUE: u32 = 35;       // TypedMapEntryStringJsonValue
+    pub const TYPED_MAP_STRING_JSON_VALUE: u32 = 37;             // TypedMapStringJsonValue
+
+    /// The graph-node name of a class id, if yogurt allocates it.
+    pub fn name(id: u32) -> Option<&'static str> {
+        Some(match id {
+            STRING => "String",
+            ARRAY_BUFFER => "ArrayBuffer",
+            INT64ARRAY => "Int64Array",
+            UINT8ARRAY => "Uint8Array",
+            BIG_DECIMAL => "BigDecimal",
+            ARRAY_STORE_VALUE => "ArrayStoreValue",
+            ARRAY_TYPED_MAP_ENTRY_STRING_STORE_VALUE => "ArrayTypedMapEntryStringStoreValue",
+            STORE_VALUE => "StoreValue",
+            TYPED_MAP_ENTRY_STRING_STORE_VALUE => "TypedMapEntryStringStoreValue",
+            TYPED_MAP_STRING_STORE_VALUE => "TypedMapStringStoreValue",
+            ARRAY_TYPED_MAP_STRING_STORE_VALUE => "ArrayTypedMapStringStoreValue",
+            ARRAY_ETHEREUM_VALUE => "ArrayEthereumValue",
+            SMART_CONTRACT_CALL => "SmartContractCall",
+            ETHEREUM_VALUE => "EthereumValue",
+            ARRAY_EVENT_PARAM => "ArrayEventParam",
+            EVENT_PARAM => "EventParam",
+            ETHEREUM_TRANSACTION => "EthereumTransaction",
+            ETHEREUM_BLOCK => "EthereumBlock",
+            ETHEREUM_CALL => "EthereumCall",
+            ETHEREUM_EVENT => "EthereumEvent",
+            ARRAY_JSON_VALUE => "ArrayJsonValue",
+            ARRAY_STRING => "ArrayString",
+            ARRAY_TYPED_MAP_ENTRY_STRING_JSON_VALUE => "ArrayTypedMapEntryStringJsonValue",
+            JSON_VALUE => "JsonValue",
+            TYPED_MAP_ENTRY_STRING_JSON_VALUE => "TypedMapEntryStringJsonValue",
+            TYPED_MAP_STRING_JSON_VALUE => "TypedMapStringJsonValue",
+            _ => return None,
+        })
+    }
 }
 
 /// Current heap pointer for bump allocation
@@ -54,6 +108,20 @@ static HEAP_PTR: AtomicU32 = AtomicU32::new(0);
 /// Initial heap base (set on first allocation)
 static HEAP_BASE: AtomicU32 = AtomicU32::new(0);
 
+/// Heap position the previous handler invocation was reset to.
+static ARENA_START: AtomicU32 = AtomicU32::new(NO_ARENA);
+
+/// `ARENA_START` before the first reset.
+const NO_ARENA: u32 = u32::MAX;
+
+/// Highest heap position reached, used to spot released pointers.
+#[cfg(all(debug_assertions, any(target_arch = "wasm32", test)))]
+static HEAP_HIGH_WATER: AtomicU32 = AtomicU32::new(0);
+
+/// Byte written over released memory in debug builds.
+#[cfg(all(debug_assertions, target_arch = "wasm32"))]
+const POISON: u8 = 0xA5;
+
 /// Initialise the heap pointer from WASM memory size
 #[cfg(target_arch = "wasm32")]
 fn ensure_heap_initialised() {
@@ -74,6 +142,305 @@ fn ensure_heap_initialised() {
     // Native testing: heap simulation not needed
 }
 
+/// Reserve `size` bytes aligned to `align` and return their address.
+///
+/// Returns `None` if linear memory cannot grow to fit them.
+#[cfg(any(target_arch = "wasm32", test))]
+fn bump(size: u32, align: u32) -> Option<u32> {
+    ensure_heap_initialised();
+
+    let start = (HEAP_PTR.load(Ordering::Relaxed) + align - 1) & !(align - 1);
+    let end = start + size;
+
+    // Check if we need to grow memory
+    #[cfg(target_arch = "wasm32")]
+    {
+        let pages_needed = end.div_ceil(65536);
+        let current_pages = core::arch::wasm32::memory_size(0) as u32;
+        if pages_needed > current_pages {
+            let grow = pages_needed - current_pages;
+            if core::arch::wasm32::memory_grow(0, grow as usize) == usize::MAX {
+                return None;
+            }
+        }
+    }
+
+    HEAP_PTR.store(end, Ordering::Relaxed);
+    #[cfg(debug_assertions)]
+    HEAP_HIGH_WATER.fetch_max(end, Ordering::Relaxed);
+    #[cfg(feature = "alloc-profile")]
+    PEAK.fetch_max(end, Ordering::Relaxed);
+    Some(start)
+}
+
+// ============================================================================
+// Arena Reset
+// ============================================================================
+
+/// A heap position to [`reset`] to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeapMark(u32);
+
+/// Mark the start of a handler invocation.
+///
+/// graph-node allocates the handler's argument through `__new` before
+/// calling the export, so the mark is the position the previous invocation
+/// was reset to. Resetting then releases the argument as well.
+pub fn mark() -> HeapMark {
+    ensure_heap_initialised();
+    match ARENA_START.load(Ordering::Relaxed) {
+        NO_ARENA => HeapMark(HEAP_PTR.load(Ordering::Relaxed)),
+        start => HeapMark(start),
+    }
+}
+
+/// Release everything allocated since `mark`.
+///
+/// In debug builds the released memory is poisoned, and reading an
+/// AssemblyScript object from it panics with "used after arena reset".
+///
+/// # Safety
+/// No pointer or reference into memory allocated after `mark` may be used
+/// afterwards. This includes AssemblyScript objects graph-node allocated for
+/// the current trigger, and Rust values kept in statics.
+pub unsafe fn reset(mark: HeapMark) {
+    #[cfg(all(debug_assertions, target_arch = "wasm32"))]
+    {
+        let top = HEAP_PTR.load(Ordering::Relaxed);
+        let len = top.saturating_sub(mark.0) as usize;
+        unsafe { core::ptr::write_bytes(mark.0 as *mut u8, POISON, len) };
+    }
+    #[cfg(feature = "alloc-profile")]
+    {
+        let peak = PEAK.swap(mark.0, Ordering::Relaxed);
+        COUNTERS
+            .last_peak
+            .store(peak.saturating_sub(mark.0), Ordering::Relaxed);
+    }
+    HEAP_PTR.store(mark.0, Ordering::Relaxed);
+    ARENA_START.store(mark.0, Ordering::Relaxed);
+}
+
+/// Whether `ptr` points into memory released by [`reset`] and not reused.
+#[cfg(all(debug_assertions, any(target_arch = "wasm32", test)))]
+fn is_released(ptr: u32) -> bool {
+    ptr >= HEAP_PTR.load(Ordering::Relaxed) && ptr < HEAP_HIGH_WATER.load(Ordering::Relaxed)
+}
+
+/// Panic if the object at `ptr` lives in released memory (debug builds).
+#[cfg(target_arch = "wasm32")]
+#[inline(always)]
+fn check_live(ptr: u32) {
+    #[cfg(debug_assertions)]
+    {
+        let poisoned = u32::from_ne_bytes([POISON; 4]);
+        let rt_id = unsafe { core::ptr::read_unaligned(ptr.wrapping_sub(8) as *const u32) };
+        if is_released(ptr) || rt_id == poisoned {
+            panic!("AssemblyScript object {:#x} used after arena reset", ptr);
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = ptr;
+}
+
+// ============================================================================
+// Allocation Profiling
+// ============================================================================
+
+/// Class id the profile counts Rust heap (`WasmBumpAllocator`) allocations under.
+pub const RUST_HEAP: u32 = u32::MAX;
+
+/// Number of distinct class ids the profile counters can hold.
+pub const PROFILE_SLOTS: usize = 32;
+
+/// Length in `u32` words of the counters behind `yogurt_alloc_profile`.
+pub const PROFILE_WORDS: usize = 2 + 3 * PROFILE_SLOTS;
+
+/// Counters kept with the `alloc-profile` feature, laid out as
+/// [`PROFILE_WORDS`] little-endian words: the peak heap growth of the last
+/// invocation, the number of slots in use, then `class_id, count, bytes` for
+/// each slot. Counts and bytes are cumulative and wrap.
+#[cfg(feature = "alloc-profile")]
+#[repr(C)]
+struct Counters {
+    last_peak: AtomicU32,
+    len: AtomicU32,
+    slots: [[AtomicU32; 3]; PROFILE_SLOTS],
+}
+
+#[cfg(feature = "alloc-profile")]
+static COUNTERS: Counters = Counters {
+    last_peak: AtomicU32::new(0),
+    len: AtomicU32::new(0),
+    slots: [const { [const { AtomicU32::new(0) }; 3] }; PROFILE_SLOTS],
+};
+
+/// Highest heap position since the last reset.
+#[cfg(feature = "alloc-profile")]
+static PEAK: AtomicU32 = AtomicU32::new(0);
+
+/// Count an allocation of `bytes` bytes, header and padding included.
+#[cfg(all(feature = "alloc-profile", any(target_arch = "wasm32", test)))]
+fn record(class_id: u32, bytes: u32) {
+    let len = COUNTERS.len.load(Ordering::Relaxed) as usize;
+    let slot = match COUNTERS.slots[..len]
+        .iter()
+        .find(|slot| slot[0].load(Ordering::Relaxed) == class_id)
+    {
+        Some(slot) => slot,
+        None if len < PROFILE_SLOTS => {
+            let slot = &COUNTERS.slots[len];
+            slot[0].store(class_id, Ordering::Relaxed);
+            COUNTERS.len.store(len as u32 + 1, Ordering::Relaxed);
+            slot
+        }
+        // graph-node has fewer class ids than slots
+        None => return,
+    };
+    slot[1].fetch_add(1, Ordering::Relaxed);
+    slot[2].fetch_add(bytes, Ordering::Relaxed);
+}
+
+/// The address of the allocation counters, read by the WASM host emulator.
+#[unsafe(no_mangle)]
+#[cfg(all(target_arch = "wasm32", feature = "alloc-profile"))]
+pub extern "C" fn yogurt_alloc_profile() -> u32 {
+    &COUNTERS as *const Counters as u32
+}
+
+/// This instance's allocation counters, cumulative since it started.
+#[cfg(feature = "alloc-profile")]
+pub fn profile() -> AllocProfile {
+    let mut words = alloc::vec![
+        COUNTERS.last_peak.load(Ordering::Relaxed),
+        COUNTERS.len.load(Ordering::Relaxed),
+    ];
+    for slot in &COUNTERS.slots {
+        words.extend(slot.iter().map(|word| word.load(Ordering::Relaxed)));
+    }
+    AllocProfile::from_words(&words)
+}
+
+/// Allocations per class id and the peak heap growth of handler invocations.
+///
+/// The counters only ever grow, so the WASM host takes a reading before and
+/// after each handler and keeps the difference.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllocProfile {
+    /// Handler invocations merged into this profile.
+    pub invocations: u32,
+    /// Largest heap growth of a single invocation, in bytes.
+    pub peak_heap: u32,
+    /// Allocations per class id, most bytes first.
+    pub classes: Vec<ClassAllocs>,
+}
+
+/// Allocations of one class id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClassAllocs {
+    /// The AssemblyScript class id, or [`RUST_HEAP`].
+    pub class_id: u32,
+    pub count: u64,
+    /// Bytes allocated, including object headers and alignment padding.
+    pub bytes: u64,
+}
+
+impl AllocProfile {
+    /// Decode a reading of the counters laid out as described for
+    /// [`PROFILE_WORDS`].
+    pub fn from_words(words: &[u32]) -> Self {
+        let len = words.get(1).map_or(0, |&len| len as usize).min(PROFILE_SLOTS);
+        let classes = words
+            .get(2..)
+            .unwrap_or_default()
+            .chunks_exact(3)
+            .take(len)
+            .map(|slot| ClassAllocs {
+                class_id: slot[0],
+                count: slot[1] as u64,
+                bytes: slot[2] as u64,
+            })
+            .collect();
+        let mut profile = Self {
+            invocations: 0,
+            peak_heap: words.first().copied().unwrap_or(0),
+            classes,
+        };
+        profile.sort();
+        profile
+    }
+
+    /// The single invocation between the reading `earlier` and this one.
+    pub fn since(&self, earlier: &AllocProfile) -> AllocProfile {
+        let mut classes = Vec::new();
+        for class in &self.classes {
+            let (count, bytes) = earlier
+                .classes
+                .iter()
+                .find(|c| c.class_id == class.class_id)
+                .map_or((0, 0), |c| (c.count, c.bytes));
+            // The guest's counters are `u32` and wrap
+            let count = (class.count as u32).wrapping_sub(count as u32) as u64;
+            let bytes = (class.bytes as u32).wrapping_sub(bytes as u32) as u64;
+            if count > 0 {
+                classes.push(ClassAllocs {
+                    class_id: class.class_id,
+                    count,
+                    bytes,
+                });
+            }
+        }
+        let mut profile = Self {
+            invocations: 1,
+            peak_heap: self.peak_heap,
+            classes,
+        };
+        profile.sort();
+        profile
+    }
+
+    /// Add the invocations of `other` to this profile.
+    pub fn merge(&mut self, other: &AllocProfile) {
+        self.invocations += other.invocations;
+        self.peak_heap = self.peak_heap.max(other.peak_heap);
+        for class in &other.classes {
+            match self.classes.iter_mut().find(|c| c.class_id == class.class_id) {
+                Some(c) => {
+                    c.count += class.count;
+                    c.bytes += class.bytes;
+                }
+                None => self.classes.push(*class),
+            }
+        }
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.classes
+            .sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.class_id.cmp(&b.class_id)));
+    }
+}
+
+impl core::fmt::Display for AllocProfile {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{} invocations, peak heap {} bytes",
+            self.invocations, self.peak_heap
+        )?;
+        writeln!(f, "  {:<42} {:>10} {:>12}", "class", "allocs", "bytes")?;
+        for class in &self.classes {
+            let name = match class_id::name(class.class_id) {
+                _ if class.class_id == RUST_HEAP => alloc::string::String::from("Rust heap"),
+                Some(name) => alloc::format!("{} ({})", name, class.class_id),
+                None => alloc::format!("class {}", class.class_id),
+            };
+            writeln!(f, "  {:<42} {:>10} {:>12}", name, class.count, class.bytes)?;
+        }
+        Ok(())
+    }
+}
+
 /// Allocate memory with an AssemblyScript-compatible header.
 ///
 /// Returns a pointer to the payload (after the 20-byte header).
@@ -85,25 +452,15 @@ fn ensure_heap_initialised() {
 /// - rtSize: the provided size
 #[cfg(target_arch = "wasm32")]
 pub fn asc_alloc(size: u32, class_id: u32) -> u32 {
-    ensure_heap_initialised();
-
     let total_size = HEADER_SIZE + size;
     // Align to 8 bytes
     let aligned_size = (total_size + 7) & !7;
 
-    let base = HEAP_PTR.fetch_add(aligned_size, Ordering::Relaxed);
-
-    // Check if we need to grow memory
-    let end = base + aligned_size;
-    let pages_needed = (end + 65535) / 65536;
-    let current_pages = core::arch::wasm32::memory_size(0) as u32;
-
-    if pages_needed > current_pages {
-        let grow = pages_needed - current_pages;
-        if core::arch::wasm32::memory_grow(0, grow as usize) == usize::MAX {
-            core::arch::wasm32::unreachable();
-        }
-    }
+    let Some(base) = bump(aligned_size, 8) else {
+        core::arch::wasm32::unreachable();
+    };
+    #[cfg(feature = "alloc-profile")]
+    record(class_id, aligned_size);
 
     // Write the 20-byte header
     let header_ptr = base as *mut u32;
@@ -139,6 +496,7 @@ pub extern "C" fn __new(size: i32, class_id: i32) -> i32 {
 }
 
 /// Read the rtId (runtime type ID) from an object's header
+#[cfg(target_arch = "wasm32")]
 #[inline(never)]
 pub unsafe fn read_rt_id(ptr: u32) -> u32 {
     // Valid AS objects have 20-byte headers, so minimum valid ptr is HEADER_SIZE.
@@ -147,11 +505,13 @@ pub unsafe fn read_rt_id(ptr: u32) -> u32 {
     if ptr < HEADER_SIZE {
         return 0;
     }
+    check_live(ptr);
     let header_ptr = ptr.wrapping_sub(8) as *const u32;
     unsafe { core::ptr::read_unaligned(header_ptr) }
 }
 
 /// Read the rtSize (payload byte length) from an object's header
+#[cfg(target_arch = "wasm32")]
 #[inline(never)]
 pub unsafe fn read_rt_size(ptr: u32) -> u32 {
     // Valid AS objects have 20-byte headers, so minimum valid ptr is HEADER_SIZE.
@@ -160,18 +520,43 @@ pub unsafe fn read_rt_size(ptr: u32) -> u32 {
     if ptr < HEADER_SIZE {
         return 0;
     }
+    check_live(ptr);
     let header_ptr = ptr.wrapping_sub(4) as *const u32;
     unsafe { core::ptr::read_unaligned(header_ptr) }
 }
 
+/// Read the rtId from an object header in the installed `AscImage`.
+///
+/// # Safety
+/// See [`crate::asc::read_u32_at`].
+#[cfg(not(target_arch = "wasm32"))]
+pub unsafe fn read_rt_id(ptr: u32) -> u32 {
+    if ptr < HEADER_SIZE {
+        return 0;
+    }
+    unsafe { crate::asc::read_u32_at(ptr - 8, 0) }
+}
+
+/// Read the rtSize from an object header in the installed `AscImage`.
+///
+/// # Safety
+/// See [`crate::asc::read_u32_at`].
+#[cfg(not(target_arch = "wasm32"))]
+pub unsafe fn read_rt_size(ptr: u32) -> u32 {
+    if ptr < HEADER_SIZE {
+        return 0;
+    }
+    unsafe { crate::asc::read_u32_at(ptr - 4, 0) }
+}
+
 // ============================================================================
 // Global Allocator for no_std builds
 // ============================================================================
 
 /// A simple bump allocator for use as the global allocator in no_std WASM.
 ///
-/// This allocator never frees memory, which is acceptable for subgraph handlers
-/// since each handler invocation typically runs in a fresh WASM instance.
+/// `dealloc` is a no-op; memory is reclaimed when the handler wrapper
+/// [`reset`]s the heap at the end of the invocation.
 #[cfg(all(target_arch = "wasm32", not(feature = "std")))]
 pub struct WasmBumpAllocator;
 
@@ -180,34 +565,16 @@ unsafe impl core::alloc::GlobalAlloc for WasmBumpAllocator {
     unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
         // Use our existing bump allocator (without the AS header)
         // We allocate raw memory here, not AS objects
-        ensure_heap_initialised();
-
-        let size = layout.size() as u32;
-        let align = layout.align() as u32;
-
-        // Get current heap pointer and align it
-        let mut ptr = HEAP_PTR.load(Ordering::Relaxed);
-        let aligned_ptr = (ptr + align - 1) & !(align - 1);
-
-        let new_ptr = aligned_ptr + size;
-
-        // Check if we need to grow memory
-        let pages_needed = (new_ptr + 65535) / 65536;
-        let current_pages = core::arch::wasm32::memory_size(0) as u32;
-
-        if pages_needed > current_pages {
-            let grow = pages_needed - current_pages;
-            if core::arch::wasm32::memory_grow(0, grow as usize) == usize::MAX {
-                return core::ptr::null_mut();
-            }
+        #[cfg(feature = "alloc-profile")]
+        record(RUST_HEAP, layout.size() as u32);
+        match bump(layout.size() as u32, layout.align() as u32) {
+            Some(ptr) => ptr as *mut u8,
+            None => core::ptr::null_mut(),
         }
-
-        HEAP_PTR.store(new_ptr, Ordering::Relaxed);
-        aligned_ptr as *mut u8
     }
 
     unsafe fn dealloc(&self, _ptr: *mut u8, _layout: core::alloc::Layout) {
-        // Bump allocator never frees — this is fine for short-lived handlers
+        // Bump allocator never frees — the arena is reset after each handler
     }
 }
 
@@ -215,3 +582,91 @@ unsafe impl core::alloc::GlobalAlloc for WasmBumpAllocator {
 #[cfg(all(target_arch = "wasm32", not(feature = "std")))]
 #[global_allocator]
 pub static ALLOCATOR: WasmBumpAllocator = WasmBumpAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Native builds only move the heap pointer, which is all the arena
+    // bookkeeping looks at. This is the only test that allocates.
+    #[test]
+    fn test_arena_reset_keeps_heap_flat() {
+        let mut flat = None;
+        for i in 0..100_000 {
+            // graph-node allocates the trigger before calling the export
+            let event = bump(HEADER_SIZE + 180, 8).unwrap();
+
+            let mark = mark();
+            for (size, align) in [(HEADER_SIZE + 42, 8), (7, 1), (64, 16), (3, 4)] {
+                bump(size, align).unwrap();
+            }
+            unsafe { reset(mark) };
+
+            // The first trigger predates the first mark and stays allocated
+            if i == 0 {
+                continue;
+            }
+            assert_eq!(mark.0, event);
+            #[cfg(debug_assertions)]
+            assert!(is_released(event));
+
+            let top = HEAP_PTR.load(Ordering::Relaxed);
+            #[cfg(debug_assertions)]
+            let top = (top, HEAP_HIGH_WATER.load(Ordering::Relaxed));
+            assert_eq!(*flat.get_or_insert(top), top, "invocation {}", i);
+        }
+
+        // The last invocation grew the heap to the high-water mark
+        #[cfg(all(debug_assertions, feature = "alloc-profile"))]
+        assert_eq!(
+            profile().peak_heap,
+            HEAP_HIGH_WATER.load(Ordering::Relaxed) - HEAP_PTR.load(Ordering::Relaxed)
+        );
+
+        #[cfg(debug_assertions)]
+        assert!(!is_released(bump(HEADER_SIZE, 8).unwrap()));
+    }
+
+    #[cfg(feature = "alloc-profile")]
+    #[test]
+    fn test_alloc_profile() {
+        let class = |class_id, count, bytes| ClassAllocs {
+            class_id,
+            count,
+            bytes,
+        };
+
+        let before = profile();
+        record(class_id::TYPED_MAP, 40);
+        record(class_id::STRING, 24);
+        record(class_id::TYPED_MAP, 40);
+        record(RUST_HEAP, 100);
+        let invocation = profile().since(&before);
+        assert_eq!(
+            invocation.classes,
+            vec![
+                class(RUST_HEAP, 1, 100),
+                class(class_id::TYPED_MAP, 2, 80),
+                class(class_id::STRING, 1, 24),
+            ]
+        );
+
+        let mut total = AllocProfile::default();
+        total.merge(&invocation);
+        total.merge(&invocation);
+        assert_eq!(total.invocations, 2);
+        assert_eq!(total.classes[1], class(class_id::TYPED_MAP, 4, 160));
+
+        let report = total.to_string();
+        assert!(report.starts_with("2 invocations"));
+        assert!(report.contains("TypedMapStringStoreValue (36)"));
+        assert!(report.contains("Rust heap"));
+
+        // Counters wrap in the guest
+        let earlier = AllocProfile::from_words(&[0, 1, 6, u32::MAX, u32::MAX - 5]);
+        let later = AllocProfile::from_words(&[64, 1, 6, 2, 10]);
+        let invocation = later.since(&earlier);
+        assert_eq!(invocation.classes, vec![class(class_id::UINT8ARRAY, 3, 16)]);
+        assert_eq!(invocation.peak_heap, 64);
+    }
+}
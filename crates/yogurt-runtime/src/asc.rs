@@ -257,7 +257,6 @@ pub fn str_to_asc(_s: &str) -> AscPtr<AscString> {
 /// Convert an AssemblyScript string from WASM memory to a Rust String.
 ///
 /// Reads the UTF-16LE data and converts to UTF-8.
-#[cfg(target_arch = "wasm32")]
 pub fn asc_to_string(ptr: AscPtr<AscString>) -> String {
     if ptr.is_null() {
         return String::new();
@@ -268,22 +267,16 @@ pub fn asc_to_string(ptr: AscPtr<AscString>) -> String {
         let rt_size = read_rt_size(raw);
         let len = rt_size as usize / 2;
 
-        let mut units = Vec::with_capacity(len);
-        let base = raw as *const u16;
-
-        for i in 0..len {
-            units.push(u16::from_le(core::ptr::read_unaligned(base.add(i))));
-        }
+        let bytes = read_bytes_at(raw, len * 2);
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect();
 
         String::from_utf16_lossy(&units)
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn asc_to_string(_ptr: AscPtr<AscString>) -> String {
-    panic!("asc_to_string not available on native target");
-}
-
 /// Convert a byte slice to an AssemblyScript ArrayBuffer in WASM memory.
 #[cfg(target_arch = "wasm32")]
 pub fn bytes_to_asc(data: &[u8]) -> AscPtr<AscBytes> {
@@ -308,7 +301,6 @@ pub fn bytes_to_asc(_data: &[u8]) -> AscPtr<AscBytes> {
 /// This function handles both raw ArrayBuffer pointers and TypedArray (Uint8Array)
 /// wrappers. Graph-node passes TypedArray pointers for Bytes values in events,
 /// so we need to detect the type and read accordingly.
-#[cfg(target_arch = "wasm32")]
 pub fn asc_to_bytes(ptr: AscPtr<AscBytes>) -> Vec<u8> {
     if ptr.is_null() {
         return Vec::new();
@@ -322,18 +314,12 @@ pub fn asc_to_bytes(ptr: AscPtr<AscBytes>) -> Vec<u8> {
         // AND the structure looks like a valid TypedArray header.
         // TypedArray layout: buffer (u32), data_start (u32), byte_length (u32)
         if rt_size == 12 {
-            let header = raw as *const AscTypedArrayHeader;
-            let data_start = (*header).data_start;
-            let byte_length = (*header).byte_length as usize;
+            let data_start = read_u32_at(raw, 4);
+            let byte_length = read_u32_at(raw, 8) as usize;
 
             // Validate: data_start should be non-zero, byte_length reasonable
             if data_start != 0 && byte_length > 0 && byte_length < 1_000_000 {
-                let mut bytes = Vec::with_capacity(byte_length);
-                let src = data_start as *const u8;
-                for i in 0..byte_length {
-                    bytes.push(core::ptr::read(src.add(i)));
-                }
-                return bytes;
+                return read_bytes_at(data_start, byte_length);
             }
         }
 
@@ -343,95 +329,51 @@ pub fn asc_to_bytes(ptr: AscPtr<AscBytes>) -> Vec<u8> {
             return Vec::new();
         }
 
-        let mut bytes = Vec::with_capacity(len);
-        let src = raw as *const u8;
-
-        for i in 0..len {
-            bytes.push(core::ptr::read(src.add(i)));
-        }
-
-        bytes
+        read_bytes_at(raw, len)
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn asc_to_bytes(_ptr: AscPtr<AscBytes>) -> Vec<u8> {
-    panic!("asc_to_bytes not available on native target");
-}
-
 // ============================================================================
 // FromAscPtr Implementations for Basic Types
 // ============================================================================
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for alloc::string::String {
     fn from_asc_ptr(ptr: u32) -> Self {
         asc_to_string(AscPtr::new(ptr))
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for alloc::string::String {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        alloc::string::String::new()
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for alloc::vec::Vec<u8> {
     fn from_asc_ptr(ptr: u32) -> Self {
         asc_to_bytes(AscPtr::new(ptr))
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for alloc::vec::Vec<u8> {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        alloc::vec::Vec::new()
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for bool {
     fn from_asc_ptr(ptr: u32) -> Self {
         ptr != 0
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for bool {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        false
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for i32 {
     fn from_asc_ptr(ptr: u32) -> Self {
         ptr as i32
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for i32 {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        0
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for u32 {
     fn from_asc_ptr(ptr: u32) -> Self {
         ptr
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for u32 {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        0
-    }
-}
+// ============================================================================
+// Raw memory reads
+// ============================================================================
+//
+// On WASM these dereference linear memory directly. On native targets they
+// read from the image installed by `asc_image::AscImage::enter`, so the
+// deserialization code above can be tested against graph-node layouts.
 
 /// Read a u32 from AS memory at the given offset from a base pointer.
 #[cfg(target_arch = "wasm32")]
@@ -462,3 +404,55 @@ pub unsafe fn read_i32_at(base: u32, offset: usize) -> i32 {
         core::ptr::read_unaligned(ptr)
     }
 }
+
+/// Copy `len` bytes of AS memory starting at `ptr`.
+#[cfg(target_arch = "wasm32")]
+#[inline]
+pub unsafe fn read_bytes_at(ptr: u32, len: usize) -> Vec<u8> {
+    unsafe { core::slice::from_raw_parts(ptr as *const u8, len).to_vec() }
+}
+
+/// Read a u32 from the installed `AscImage`.
+///
+/// # Safety
+/// Always memory-safe; it is `unsafe` only to share the WASM signature.
+/// Panics if no image is installed or the read is out of bounds.
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub unsafe fn read_u32_at(base: u32, offset: usize) -> u32 {
+    let bytes = crate::asc_image::read_installed(base + offset as u32, 4);
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Read a u64 from the installed `AscImage`.
+///
+/// # Safety
+/// See [`read_u32_at`].
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub unsafe fn read_u64_at(base: u32, offset: usize) -> u64 {
+    let bytes = crate::asc_image::read_installed(base + offset as u32, 8);
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes);
+    u64::from_le_bytes(word)
+}
+
+/// Read an i32 from the installed `AscImage`.
+///
+/// # Safety
+/// See [`read_u32_at`].
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub unsafe fn read_i32_at(base: u32, offset: usize) -> i32 {
+    unsafe { read_u32_at(base, offset) as i32 }
+}
+
+/// Copy `len` bytes of the installed `AscImage` starting at `ptr`.
+///
+/// # Safety
+/// See [`read_u32_at`].
+#[cfg(not(target_arch = "wasm32"))]
+#[inline]
+pub unsafe fn read_bytes_at(ptr: u32, len: usize) -> Vec<u8> {
+    crate::asc_image::read_installed(ptr, len)
+}
@@ -0,0 +1,1127 @@
+//! AssemblyScript heap images for native deserialization tests.
+//!
+//! graph-node hands handlers pointers to objects it wrote with `ToAscObj`:
+//! a 20-byte header whose `rtId`/`rtSize` sit just before the payload,
+//! `Uint8Array` wrappers around every byte buffer, and 16-byte `AscEnum`s for
+//! Ethereum values. Getting any of these wrong only shows up as garbage (or a
+//! trap) inside graph-node, which is how event deserialization broke before.
+//!
+//! [`AscHeap`] knows those layouts. It writes and reads objects through three
+//! primitives, so the same code backs the WASM host emulator (guest memory)
+//! and [`AscImage`], a plain byte vector. Installing an image with
+//! [`AscImage::enter`] points the runtime's native memory reads at it, so
+//! `FromAscPtr` impls, `asc_to_string` and `asc_to_bytes` run on the host
+//! against byte-exact graph-node objects.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use yogurt_runtime::asc::FromAscPtr;
+//! use yogurt_runtime::asc_image::{AscHeap, AscImage};
+//!
+//! #[test]
+//! fn transfer_event_deserializes() {
+//!     let event = EventBuilder::<TransferParams>::new().params(params).build();
+//!     let mut image = AscImage::new();
+//!     let ptr = image.write_event(&event).unwrap();
+//!     let decoded = image.enter(|| Event::<TransferParams>::from_asc_ptr(ptr));
+//!     assert_eq!(decoded.params.value, event.params.value);
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::allocator::{HEADER_SIZE, class_id};
+use crate::ethereum::{
+    Block, Call, Event, EventParam, Log, ToEventParams, Token, Transaction, TransactionReceipt,
+};
+use crate::types::{Address, BigDecimal, BigInt, Bytes, EntityData, Timestamp, Value};
+
+/// Type id used for objects graph-node allocates with chain-specific ids
+/// (receipts, logs) that yogurt has no constant for. Nothing on the guest
+/// side inspects them.
+const UNTYPED: u32 = 0;
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// A malformed object or out-of-range access in an AssemblyScript heap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AscError(String);
+
+impl AscError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+
+    fn out_of_bounds(ptr: u32, len: u32) -> Self {
+        Self(format!(
+            "out of bounds memory access: {} bytes at {:#x}",
+            len, ptr
+        ))
+    }
+}
+
+impl fmt::Display for AscError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for AscError {}
+
+// ============================================================================
+// AscHeap
+// ============================================================================
+
+/// Reads and writes AssemblyScript objects in graph-node's layouts.
+///
+/// Implementors provide allocation and raw access; everything else is built
+/// on top. Allocation must place a 20-byte header before the payload with
+/// `rtId` at `-8` and `rtSize` at `-4`, as AssemblyScript's `__new` does.
+pub trait AscHeap: Sized {
+    type Error: From<AscError>;
+
+    /// Allocate `size` payload bytes with the given runtime type id.
+    fn alloc(&mut self, size: u32, class_id: u32) -> Result<u32, Self::Error>;
+
+    fn read_raw(&self, ptr: u32, len: u32) -> Result<Vec<u8>, Self::Error>;
+
+    fn write_raw(&mut self, ptr: u32, data: &[u8]) -> Result<(), Self::Error>;
+
+    fn read_u32(&self, ptr: u32) -> Result<u32, Self::Error> {
+        let bytes = self.read_raw(ptr, 4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn rt_id(&self, ptr: u32) -> Result<u32, Self::Error> {
+        self.read_u32(header_field(ptr, 8)?)
+    }
+
+    fn rt_size(&self, ptr: u32) -> Result<u32, Self::Error> {
+        self.read_u32(header_field(ptr, 4)?)
+    }
+
+    // --- Strings and bytes ---------------------------------------------------
+
+    fn write_string(&mut self, s: &str) -> Result<u32, Self::Error> {
+        let bytes: Vec<u8> = s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let ptr = self.alloc(bytes.len() as u32, class_id::STRING)?;
+        self.write_raw(ptr, &bytes)?;
+        Ok(ptr)
+    }
+
+    fn read_string(&self, ptr: u32) -> Result<String, Self::Error> {
+        let bytes = self.read_raw(ptr, self.rt_size(ptr)?)?;
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    fn write_array_buffer(&mut self, data: &[u8]) -> Result<u32, Self::Error> {
+        let ptr = self.alloc(data.len() as u32, class_id::ARRAY_BUFFER)?;
+        self.write_raw(ptr, data)?;
+        Ok(ptr)
+    }
+
+    /// Write a `Uint8Array`, the representation of `Bytes`, `Address` and `BigInt`.
+    fn write_uint8_array(&mut self, data: &[u8]) -> Result<u32, Self::Error> {
+        let buffer = self.write_array_buffer(data)?;
+        self.write_words(&[buffer, buffer, data.len() as u32], class_id::UINT8ARRAY)
+    }
+
+    /// Read a byte object, accepting both a `Uint8Array` and a bare
+    /// `ArrayBuffer` (which `asc::bytes_to_asc` produces).
+    fn read_bytes(&self, ptr: u32) -> Result<Vec<u8>, Self::Error> {
+        if self.rt_id(ptr)? == class_id::UINT8ARRAY {
+            let data_start = self.read_u32(ptr + 4)?;
+            let byte_length = self.read_u32(ptr + 8)?;
+            self.read_raw(data_start, byte_length)
+        } else {
+            self.read_raw(ptr, self.rt_size(ptr)?)
+        }
+    }
+
+    // --- Structs, arrays and enums -------------------------------------------
+
+    /// Write a struct made of 32-bit fields (pointers, mostly).
+    fn write_words(&mut self, words: &[u32], class_id: u32) -> Result<u32, Self::Error> {
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let ptr = self.alloc(bytes.len() as u32, class_id)?;
+        self.write_raw(ptr, &bytes)?;
+        Ok(ptr)
+    }
+
+    /// Write an `Array<T>` of pointers: `{buffer, dataStart, byteLength, length}`.
+    fn write_array(&mut self, items: &[u32], class_id: u32) -> Result<u32, Self::Error> {
+        let bytes: Vec<u8> = items.iter().flat_map(|p| p.to_le_bytes()).collect();
+        let buffer = self.write_array_buffer(&bytes)?;
+        self.write_words(
+            &[buffer, buffer, bytes.len() as u32, items.len() as u32],
+            class_id,
+        )
+    }
+
+    fn read_array(&self, ptr: u32) -> Result<Vec<u32>, Self::Error> {
+        let data_start = self.read_u32(ptr + 4)?;
+        let length = self.read_u32(ptr + 12)?;
+        (0..length)
+            .map(|i| self.read_u32(data_start + i * 4))
+            .collect()
+    }
+
+    /// Write an `AscEnum`: `{kind: i32, _padding: u32, payload: u64}`.
+    fn write_enum(&mut self, kind: i32, payload: u64, class_id: u32) -> Result<u32, Self::Error> {
+        let mut bytes = Vec::with_capacity(16);
+        bytes.extend_from_slice(&kind.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&payload.to_le_bytes());
+        let ptr = self.alloc(16, class_id)?;
+        self.write_raw(ptr, &bytes)?;
+        Ok(ptr)
+    }
+
+    fn read_enum(&self, ptr: u32) -> Result<(i32, u64), Self::Error> {
+        let bytes = self.read_raw(ptr, 16)?;
+        let kind = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let mut payload = [0u8; 8];
+        payload.copy_from_slice(&bytes[8..16]);
+        Ok((kind, u64::from_le_bytes(payload)))
+    }
+
+    // --- Numbers --------------------------------------------------------------
+
+    fn write_big_int(&mut self, value: &BigInt) -> Result<u32, Self::Error> {
+        self.write_uint8_array(&value.to_signed_bytes())
+    }
+
+    fn read_big_int(&self, ptr: u32) -> Result<BigInt, Self::Error> {
+        Ok(BigInt::from_signed_bytes(&self.read_bytes(ptr)?))
+    }
+
+    /// Write an `AscBigDecimal`: `{digits: BigInt, exp: BigInt}`.
+    fn write_big_decimal(&mut self, value: &BigDecimal) -> Result<u32, Self::Error> {
+        let digits = self.write_big_int(&BigInt::from_inner(value.digits().clone()))?;
+        let exp = self.write_big_int(&BigInt::from_i64(-value.scale()))?;
+        self.write_words(&[digits, exp], class_id::BIG_DECIMAL)
+    }
+
+    fn read_big_decimal(&self, ptr: u32) -> Result<BigDecimal, Self::Error> {
+        // `store::serialize_value` writes a "0" string for decimals it cannot serialise
+        if self.rt_id(ptr)? == class_id::STRING {
+            return Ok(BigDecimal::from_string(&self.read_string(ptr)?));
+        }
+        let digits = self.read_big_int(self.read_u32(ptr)?)?;
+        let exp = self.read_big_int(self.read_u32(ptr + 4)?)?;
+        Ok(decimal_from_parts(&digits, &exp)?)
+    }
+
+    // --- Ethereum values --------------------------------------------------------
+
+    fn write_token(&mut self, token: &Token) -> Result<u32, Self::Error> {
+        // EthereumValue kinds (from graph-ts):
+        // ADDRESS = 0, FIXED_BYTES = 1, BYTES = 2, INT = 3, UINT = 4,
+        // BOOL = 5, STRING = 6, FIXED_ARRAY = 7, ARRAY = 8, TUPLE = 9
+        let (kind, payload) = match token {
+            Token::Address(addr) => (0, self.write_uint8_array(addr.as_bytes())?),
+            Token::FixedBytes(bytes) => (1, self.write_uint8_array(bytes)?),
+            Token::Bytes(bytes) => (2, self.write_uint8_array(bytes.as_slice())?),
+            Token::Int(n) => (3, self.write_big_int(n)?),
+            Token::Uint(n) => (4, self.write_big_int(n)?),
+            Token::Bool(b) => (5, *b as u32),
+            Token::String(s) => (6, self.write_string(s)?),
+            Token::FixedArray(items) => (7, self.write_token_array(items)?),
+            Token::Array(items) => (8, self.write_token_array(items)?),
+            Token::Tuple(items) => (9, self.write_token_array(items)?),
+        };
+        self.write_enum(kind, payload as u64, class_id::ETHEREUM_VALUE)
+    }
+
+    fn write_token_array(&mut self, tokens: &[Token]) -> Result<u32, Self::Error> {
+        let ptrs = tokens
+            .iter()
+            .map(|t| self.write_token(t))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.write_array(&ptrs, class_id::ARRAY_ETHEREUM_VALUE)
+    }
+
+    fn read_token(&self, ptr: u32) -> Result<Token, Self::Error> {
+        let (kind, payload) = self.read_enum(ptr)?;
+        let ptr = payload as u32;
+        Ok(match kind {
+            0 => Token::Address(Address::from(self.read_bytes(ptr)?.as_slice())),
+            1 => Token::FixedBytes(self.read_bytes(ptr)?),
+            2 => Token::Bytes(Bytes::from_vec(self.read_bytes(ptr)?)),
+            3 => Token::Int(self.read_big_int(ptr)?),
+            4 => Token::Uint(self.read_big_int(ptr)?),
+            5 => Token::Bool(payload != 0),
+            6 => Token::String(self.read_string(ptr)?),
+            7 => Token::FixedArray(self.read_token_array(ptr)?),
+            8 => Token::Array(self.read_token_array(ptr)?),
+            9 => Token::Tuple(self.read_token_array(ptr)?),
+            other => {
+                return Err(AscError::new(format!("invalid EthereumValue kind {}", other)).into());
+            }
+        })
+    }
+
+    fn read_token_array(&self, ptr: u32) -> Result<Vec<Token>, Self::Error> {
+        self.read_array(ptr)?
+            .into_iter()
+            .map(|p| self.read_token(p))
+            .collect()
+    }
+
+    // --- Store values -----------------------------------------------------------
+
+    fn write_value(&mut self, value: &Value) -> Result<u32, Self::Error> {
+        // StoreValue kinds: see `asc::StoreValueKind`
+        let (kind, payload): (i32, u64) = match value {
+            Value::String(s) => (0, self.write_string(s)? as u64),
+            Value::Int(i) => (1, *i as u64),
+            Value::BigDecimal(d) => (2, self.write_big_decimal(d)? as u64),
+            Value::Bool(b) => (3, *b as u64),
+            Value::Array(items) => (4, self.write_value_array(items)? as u64),
+            Value::Null => (5, 0),
+            Value::Bytes(b) => (6, self.write_uint8_array(b.as_slice())? as u64),
+            Value::BigInt(n) => (7, self.write_big_int(n)? as u64),
+            Value::Int8(i) => (8, *i as u64),
+            Value::Timestamp(ts) => (9, ts.as_micros() as u64),
+        };
+        self.write_enum(kind, payload, class_id::STORE_VALUE)
+    }
+
+    fn write_value_array(&mut self, values: &[Value]) -> Result<u32, Self::Error> {
+        let ptrs = values
+            .iter()
+            .map(|v| self.write_value(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.write_array(&ptrs, class_id::ARRAY_STORE_VALUE)
+    }
+
+    fn read_value(&self, ptr: u32) -> Result<Value, Self::Error> {
+        let (kind, payload) = self.read_enum(ptr)?;
+        let ptr = payload as u32;
+        Ok(match kind {
+            0 => Value::String(self.read_string(ptr)?),
+            1 => Value::Int(payload as i32),
+            2 => Value::BigDecimal(self.read_big_decimal(ptr)?),
+            3 => Value::Bool(payload != 0),
+            4 => Value::Array(
+                self.read_array(ptr)?
+                    .into_iter()
+                    .map(|p| self.read_value(p))
+                    .collect::<Result<_, _>>()?,
+            ),
+            5 => Value::Null,
+            6 => Value::Bytes(Bytes::from_vec(self.read_bytes(ptr)?)),
+            7 => Value::BigInt(self.read_big_int(ptr)?),
+            8 => Value::Int8(payload as i64),
+            9 => Value::Timestamp(Timestamp::from_micros(payload as i64)),
+            other => {
+                return Err(AscError::new(format!("unsupported StoreValue kind {}", other)).into());
+            }
+        })
+    }
+
+    /// Write an entity as `TypedMap<string, StoreValue>`.
+    fn write_entity(&mut self, data: &EntityData) -> Result<u32, Self::Error> {
+        let mut entries = Vec::new();
+        for (key, value) in data.iter() {
+            let key = self.write_string(key)?;
+            let value = self.write_value(value)?;
+            entries.push(self.write_words(&[key, value], class_id::TYPED_MAP_ENTRY)?);
+        }
+        let array =
+            self.write_array(&entries, class_id::ARRAY_TYPED_MAP_ENTRY_STRING_STORE_VALUE)?;
+        self.write_words(&[array], class_id::TYPED_MAP)
+    }
+
+    fn read_entity(&self, ptr: u32) -> Result<EntityData, Self::Error> {
+        let mut data = EntityData::new();
+        for entry in self.read_array(self.read_u32(ptr)?)? {
+            let key = self.read_string(self.read_u32(entry)?)?;
+            let value = self.read_value(self.read_u32(entry + 4)?)?;
+            data.set(key, value);
+        }
+        Ok(data)
+    }
+
+    /// Write an `Array<Entity>`, as returned by `store.loadRelated`.
+    fn write_entity_array(&mut self, entities: &[EntityData]) -> Result<u32, Self::Error> {
+        let ptrs = entities
+            .iter()
+            .map(|e| self.write_entity(e))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.write_array(&ptrs, class_id::ARRAY_TYPED_MAP_STRING_STORE_VALUE)
+    }
+
+    // --- JSON -------------------------------------------------------------------
+
+    fn write_json(&mut self, value: &serde_json::Value) -> Result<u32, Self::Error> {
+        use serde_json::Value as Json;
+
+        // JSONValueKind: NULL = 0, BOOL = 1, NUMBER = 2, STRING = 3, ARRAY = 4, OBJECT = 5
+        let (kind, payload): (i32, u32) =
+            match value {
+                Json::Null => (0, 0),
+                Json::Bool(b) => (1, *b as u32),
+                // Numbers travel as their source text; `json.toI64` and friends parse them
+                Json::Number(n) => (2, self.write_string(&n.to_string())?),
+                Json::String(s) => (3, self.write_string(s)?),
+                Json::Array(items) => {
+                    let ptrs = items
+                        .iter()
+                        .map(|v| self.write_json(v))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    (4, self.write_array(&ptrs, class_id::ARRAY_JSON_VALUE)?)
+                }
+                Json::Object(map) => {
+                    let mut entries = Vec::with_capacity(map.len());
+                    for (key, value) in map {
+                        let key = self.write_string(key)?;
+                        let value = self.write_json(value)?;
+                        entries.push(self.write_words(
+                            &[key, value],
+                            class_id::TYPED_MAP_ENTRY_STRING_JSON_VALUE,
+                        )?);
+                    }
+                    let array = self
+                        .write_array(&entries, class_id::ARRAY_TYPED_MAP_ENTRY_STRING_JSON_VALUE)?;
+                    (
+                        5,
+                        self.write_words(&[array], class_id::TYPED_MAP_STRING_JSON_VALUE)?,
+                    )
+                }
+            };
+        self.write_enum(kind, payload as u64, class_id::JSON_VALUE)
+    }
+
+    // --- Ethereum structs ---------------------------------------------------------
+
+    fn write_block(&mut self, block: &Block) -> Result<u32, Self::Error> {
+        let fields = [
+            self.write_uint8_array(block.hash.as_slice())?,
+            self.write_uint8_array(block.parent_hash.as_slice())?,
+            self.write_uint8_array(block.uncles_hash.as_slice())?,
+            self.write_uint8_array(block.author.as_bytes())?,
+            self.write_uint8_array(block.state_root.as_slice())?,
+            self.write_uint8_array(block.transactions_root.as_slice())?,
+            self.write_uint8_array(block.receipts_root.as_slice())?,
+            self.write_big_int(&block.number)?,
+            self.write_big_int(&block.gas_used)?,
+            self.write_big_int(&block.gas_limit)?,
+            self.write_big_int(&block.timestamp)?,
+            self.write_big_int(&block.difficulty)?,
+            self.write_big_int(&block.total_difficulty)?,
+            self.write_optional_big_int(block.size.as_ref())?,
+            self.write_optional_big_int(block.base_fee_per_gas.as_ref())?,
+        ];
+        self.write_words(&fields, class_id::ETHEREUM_BLOCK)
+    }
+
+    fn write_transaction(&mut self, tx: &Transaction) -> Result<u32, Self::Error> {
+        let fields = [
+            self.write_uint8_array(tx.hash.as_slice())?,
+            self.write_big_int(&tx.index)?,
+            self.write_uint8_array(tx.from.as_bytes())?,
+            self.write_optional_address(tx.to.as_ref())?,
+            self.write_big_int(&tx.value)?,
+            self.write_big_int(&tx.gas_limit)?,
+            self.write_big_int(&tx.gas_price)?,
+            self.write_uint8_array(tx.input.as_slice())?,
+            self.write_big_int(&tx.nonce)?,
+        ];
+        // graph-node's transaction ends at `nonce`; only grow it when a
+        // test sets the EIP-1559 fields
+        if tx.max_fee_per_gas.is_none()
+            && tx.max_priority_fee_per_gas.is_none()
+            && tx.transaction_type.is_none()
+        {
+            return self.write_words(&fields, class_id::ETHEREUM_TRANSACTION);
+        }
+        let eip1559 = [
+            self.write_optional_big_int(tx.max_fee_per_gas.as_ref())?,
+            self.write_optional_big_int(tx.max_priority_fee_per_gas.as_ref())?,
+            self.write_optional_big_int(tx.transaction_type.as_ref())?,
+        ];
+        self.write_words(&[&fields[..], &eip1559[..]].concat(), class_id::ETHEREUM_TRANSACTION)
+    }
+
+    /// Write a receipt in graph-node's layout, which has a `logs` array
+    /// between `contract_address` and `status`.
+    fn write_receipt(&mut self, receipt: &TransactionReceipt) -> Result<u32, Self::Error> {
+        let logs = receipt
+            .logs
+            .iter()
+            .map(|log| self.write_log(log))
+            .collect::<Result<Vec<_>, _>>()?;
+        let fields = [
+            self.write_uint8_array(receipt.transaction_hash.as_slice())?,
+            self.write_big_int(&receipt.transaction_index)?,
+            self.write_uint8_array(receipt.block_hash.as_slice())?,
+            self.write_big_int(&receipt.block_number)?,
+            self.write_big_int(&receipt.cumulative_gas_used)?,
+            self.write_big_int(&receipt.gas_used)?,
+            self.write_optional_address(receipt.contract_address.as_ref())?,
+            self.write_array(&logs, UNTYPED)?,
+            self.write_big_int(&receipt.status)?,
+            self.write_uint8_array(receipt.root.as_slice())?,
+            self.write_uint8_array(receipt.logs_bloom.as_slice())?,
+        ];
+        self.write_words(&fields, UNTYPED)
+    }
+
+    /// Write a log in graph-node's layout; `removed` is a `Wrapped<bool>`.
+    fn write_log(&mut self, log: &Log) -> Result<u32, Self::Error> {
+        let topics = log
+            .topics
+            .iter()
+            .map(|topic| self.write_uint8_array(topic.as_slice()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let fields = [
+            self.write_uint8_array(log.address.as_bytes())?,
+            self.write_array(&topics, UNTYPED)?,
+            self.write_uint8_array(log.data.as_slice())?,
+            self.write_uint8_array(log.block_hash.as_slice())?,
+            self.write_big_int(&log.block_number)?,
+            self.write_uint8_array(log.transaction_hash.as_slice())?,
+            self.write_big_int(&log.transaction_index)?,
+            self.write_big_int(&log.log_index)?,
+            self.write_big_int(&log.transaction_log_index)?,
+            match &log.log_type {
+                Some(log_type) => self.write_string(log_type)?,
+                None => 0,
+            },
+            match log.removed {
+                Some(removed) => {
+                    let ptr = self.alloc(1, UNTYPED)?;
+                    self.write_raw(ptr, &[removed as u8])?;
+                    ptr
+                }
+                None => 0,
+            },
+        ];
+        self.write_words(&fields, UNTYPED)
+    }
+
+    fn write_event_params(&mut self, params: &[EventParam]) -> Result<u32, Self::Error> {
+        let mut ptrs = Vec::with_capacity(params.len());
+        for param in params {
+            let name = self.write_string(&param.name)?;
+            let value = self.write_token(&param.value)?;
+            ptrs.push(self.write_words(&[name, value], class_id::EVENT_PARAM)?);
+        }
+        self.write_array(&ptrs, class_id::ARRAY_EVENT_PARAM)
+    }
+
+    fn write_event<P: ToEventParams>(&mut self, event: &Event<P>) -> Result<u32, Self::Error> {
+        let fields = [
+            self.write_uint8_array(event.address.as_bytes())?,
+            self.write_big_int(&event.log_index)?,
+            self.write_big_int(&event.transaction_log_index)?,
+            match &event.log_type {
+                Some(log_type) => self.write_string(log_type)?,
+                None => 0,
+            },
+            self.write_block(&event.block)?,
+            self.write_transaction(&event.transaction)?,
+            self.write_event_params(&event.params.to_event_params())?,
+            match &event.receipt {
+                Some(receipt) => self.write_receipt(receipt)?,
+                None => 0,
+            },
+        ];
+        self.write_words(&fields, class_id::ETHEREUM_EVENT)
+    }
+
+    fn write_call<I: ToEventParams, O: ToEventParams>(
+        &mut self,
+        call: &Call<I, O>,
+    ) -> Result<u32, Self::Error> {
+        let fields = [
+            self.write_uint8_array(call.to.as_bytes())?,
+            self.write_uint8_array(call.from.as_bytes())?,
+            self.write_block(&call.block)?,
+            self.write_transaction(&call.transaction)?,
+            self.write_event_params(&call.inputs.to_event_params())?,
+            self.write_event_params(&call.outputs.to_event_params())?,
+        ];
+        self.write_words(&fields, class_id::ETHEREUM_CALL)
+    }
+
+    fn write_optional_big_int(&mut self, value: Option<&BigInt>) -> Result<u32, Self::Error> {
+        value.map_or(Ok(0), |v| self.write_big_int(v))
+    }
+
+    fn write_optional_address(&mut self, value: Option<&Address>) -> Result<u32, Self::Error> {
+        value.map_or(Ok(0), |v| self.write_uint8_array(v.as_bytes()))
+    }
+}
+
+/// Address of a header field `back` bytes before `ptr`, rejecting null and
+/// pointers that cannot carry a header.
+fn header_field(ptr: u32, back: u32) -> Result<u32, AscError> {
+    if ptr < HEADER_SIZE {
+        return Err(AscError::new(format!("invalid object pointer {:#x}", ptr)));
+    }
+    Ok(ptr - back)
+}
+
+/// Build `digits × 10^exp` from an `AscBigDecimal`'s parts.
+pub(crate) fn decimal_from_parts(digits: &BigInt, exp: &BigInt) -> Result<BigDecimal, AscError> {
+    let exp: i64 = exp
+        .to_string()
+        .parse()
+        .map_err(|_| AscError::new("BigDecimal exponent out of range"))?;
+    let digits = digits.to_string();
+    let (sign, magnitude) = match digits.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", digits.as_str()),
+    };
+
+    let text = if exp >= 0 {
+        format!("{}{}{}", sign, magnitude, "0".repeat(exp as usize))
+    } else {
+        let scale = exp.unsigned_abs() as usize;
+        if magnitude.len() > scale {
+            let split = magnitude.len() - scale;
+            format!("{}{}.{}", sign, &magnitude[..split], &magnitude[split..])
+        } else {
+            format!(
+                "{}0.{}{}",
+                sign,
+                "0".repeat(scale - magnitude.len()),
+                magnitude
+            )
+        }
+    };
+    Ok(BigDecimal::from_string(&text))
+}
+
+// ============================================================================
+// AscImage
+// ============================================================================
+
+/// AssemblyScript objects are 16-byte aligned, like AS's TLSF allocator places them.
+const OBJECT_ALIGN: u32 = 16;
+
+/// An AssemblyScript heap held in host memory.
+///
+/// Objects are laid out as AssemblyScript's allocator would: each payload is
+/// 16-byte aligned and preceded by its header. Address 0 is never handed
+/// out, so null pointers stay null.
+#[derive(Clone, Debug, Default)]
+pub struct AscImage {
+    memory: Vec<u8>,
+}
+
+impl AscImage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The raw heap contents, for byte-level assertions.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.memory
+    }
+
+    /// Run `f` with this image installed as the native target's memory.
+    ///
+    /// Inside `f`, `asc::read_u32_at`, `asc::asc_to_bytes`, `FromAscPtr`
+    /// and friends read from the image instead of panicking. The previous
+    /// image (if any) is restored afterwards, even if `f` panics.
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Vec<u8>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                INSTALLED.with(|image| *image.borrow_mut() = self.0.take());
+            }
+        }
+
+        let previous = INSTALLED.with(|image| image.borrow_mut().replace(self.memory.clone()));
+        let _restore = Restore(previous);
+        f()
+    }
+}
+
+impl AscHeap for AscImage {
+    type Error = AscError;
+
+    fn alloc(&mut self, size: u32, class_id: u32) -> Result<u32, AscError> {
+        let end = self.memory.len() as u32 + HEADER_SIZE;
+        let ptr = end.next_multiple_of(OBJECT_ALIGN);
+        self.memory.resize((ptr + size) as usize, 0);
+        self.write_raw(ptr - 8, &class_id.to_le_bytes())?;
+        self.write_raw(ptr - 4, &size.to_le_bytes())?;
+        Ok(ptr)
+    }
+
+    fn read_raw(&self, ptr: u32, len: u32) -> Result<Vec<u8>, AscError> {
+        let start = ptr as usize;
+        self.memory
+            .get(start..start + len as usize)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| AscError::out_of_bounds(ptr, len))
+    }
+
+    fn write_raw(&mut self, ptr: u32, data: &[u8]) -> Result<(), AscError> {
+        let start = ptr as usize;
+        self.memory
+            .get_mut(start..start + data.len())
+            .ok_or_else(|| AscError::out_of_bounds(ptr, data.len() as u32))?
+            .copy_from_slice(data);
+        Ok(())
+    }
+}
+
+thread_local! {
+    static INSTALLED: RefCell<Option<Vec<u8>>> = const { RefCell::new(None) };
+}
+
+/// Read `len` bytes at `ptr` from the installed image.
+///
+/// Backs the runtime's memory reads on native targets. Panics if no image is
+/// installed or the range is out of bounds, which is what an invalid pointer
+/// would do in WASM.
+pub(crate) fn read_installed(ptr: u32, len: usize) -> Vec<u8> {
+    INSTALLED.with(|image| {
+        let image = image.borrow();
+        let memory = image
+            .as_ref()
+            .expect("no AscImage installed; wrap AssemblyScript reads in AscImage::enter");
+        let start = ptr as usize;
+        match memory.get(start..start + len) {
+            Some(bytes) => bytes.to_vec(),
+            None => panic!("{}", AscError::out_of_bounds(ptr, len as u32)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::{read_rt_id, read_rt_size};
+    use crate::asc::{AscPtr, FromAscPtr, asc_to_bytes, asc_to_string, read_u32_at, read_u64_at};
+
+    /// A params struct shaped like `yogurt codegen` output.
+    #[derive(Clone, Debug, PartialEq)]
+    struct TransferParams {
+        from: Address,
+        to: Address,
+        value: BigInt,
+        memo: String,
+    }
+
+    impl ToEventParams for TransferParams {
+        fn to_event_params(&self) -> Vec<EventParam> {
+            vec![
+                EventParam {
+                    name: "from".into(),
+                    value: Token::Address(self.from.clone()),
+                },
+                EventParam {
+                    name: "to".into(),
+                    value: Token::Address(self.to.clone()),
+                },
+                EventParam {
+                    name: "value".into(),
+                    value: Token::Uint(self.value.clone()),
+                },
+                EventParam {
+                    name: "memo".into(),
+                    value: Token::String(self.memo.clone()),
+                },
+            ]
+        }
+    }
+
+    impl FromAscPtr for TransferParams {
+        fn from_asc_ptr(ptr: u32) -> Self {
+            unsafe {
+                // Array<EventParam>.dataStart, then EventParam.value, then the AscEnum payload
+                let buffer_ptr = read_u32_at(ptr, 4);
+                let payload = |i: usize| {
+                    let param_ptr = read_u32_at(buffer_ptr, i * 4);
+                    let value_ptr = read_u32_at(param_ptr, 4);
+                    read_u64_at(value_ptr, 8) as u32
+                };
+                Self {
+                    from: Address::from_asc_ptr(payload(0)),
+                    to: Address::from_asc_ptr(payload(1)),
+                    value: BigInt::from_asc_ptr(payload(2)),
+                    memo: String::from_asc_ptr(payload(3)),
+                }
+            }
+        }
+    }
+
+    fn address(byte: u8) -> Address {
+        Address::from([byte; 20])
+    }
+
+    fn transfer_params() -> TransferParams {
+        TransferParams {
+            from: address(0xaa),
+            to: address(0xbb),
+            value: BigInt::from_string("1000000000000000000000").unwrap(),
+            memo: "gm ☕".into(),
+        }
+    }
+
+    fn block() -> Block {
+        Block {
+            hash: Bytes::from_vec(vec![0x11; 32]),
+            author: address(0xcc),
+            number: BigInt::from_u64(19_000_000),
+            timestamp: BigInt::from_u64(1_700_000_000),
+            difficulty: BigInt::from_i64(-1),
+            base_fee_per_gas: Some(BigInt::from_u64(30_000_000_000)),
+            ..Block::default()
+        }
+    }
+
+    fn transaction() -> Transaction {
+        Transaction {
+            hash: Bytes::from_vec(vec![0x22; 32]),
+            index: BigInt::from_u64(7),
+            from: address(0xaa),
+            to: None,
+            value: BigInt::from_u64(255),
+            input: Bytes::from_vec(vec![0xa9, 0x05, 0x9c, 0xbb]),
+            ..Transaction::default()
+        }
+    }
+
+    fn assert_block_eq(actual: &Block, expected: &Block) {
+        assert_eq!(actual.hash, expected.hash);
+        assert_eq!(actual.author, expected.author);
+        assert_eq!(actual.number, expected.number);
+        assert_eq!(actual.timestamp, expected.timestamp);
+        assert_eq!(actual.difficulty, expected.difficulty);
+        assert_eq!(actual.size, expected.size);
+        assert_eq!(actual.base_fee_per_gas, expected.base_fee_per_gas);
+    }
+
+    fn assert_transaction_eq(actual: &Transaction, expected: &Transaction) {
+        assert_eq!(actual.hash, expected.hash);
+        assert_eq!(actual.index, expected.index);
+        assert_eq!(actual.from, expected.from);
+        assert_eq!(actual.to, expected.to);
+        assert_eq!(actual.value, expected.value);
+        assert_eq!(actual.input, expected.input);
+        assert_eq!(actual.max_fee_per_gas, expected.max_fee_per_gas);
+        assert_eq!(actual.max_priority_fee_per_gas, expected.max_priority_fee_per_gas);
+        assert_eq!(actual.transaction_type, expected.transaction_type);
+    }
+
+    #[test]
+    fn test_alloc_matches_assemblyscript_layout() {
+        let mut image = AscImage::new();
+        let a = image.write_string("abc").unwrap();
+        let b = image.write_array_buffer(&[1, 2, 3, 4, 5]).unwrap();
+
+        assert_eq!(a % 16, 0);
+        assert_eq!(b % 16, 0);
+        assert!(b >= a + 6 + HEADER_SIZE);
+        image.enter(|| unsafe {
+            assert_eq!(read_rt_id(a), class_id::STRING);
+            assert_eq!(read_rt_size(a), 6);
+            assert_eq!(read_rt_id(b), class_id::ARRAY_BUFFER);
+            assert_eq!(read_rt_size(b), 5);
+        });
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let mut image = AscImage::new();
+        let ptr = image
+            .write_string("Transfer(address,address,uint256) ✓")
+            .unwrap();
+
+        image.enter(|| {
+            assert_eq!(
+                asc_to_string(AscPtr::new(ptr)),
+                "Transfer(address,address,uint256) ✓"
+            );
+            assert_eq!(asc_to_string(AscPtr::null()), "");
+        });
+    }
+
+    #[test]
+    fn test_bytes_round_trip_through_typed_array_and_array_buffer() {
+        let mut image = AscImage::new();
+        let typed = image.write_uint8_array(&[0xde, 0xad, 0xbe, 0xef]).unwrap();
+        let raw = image.write_array_buffer(&[1, 2, 3]).unwrap();
+
+        image.enter(|| {
+            assert_eq!(
+                asc_to_bytes(AscPtr::new(typed)),
+                vec![0xde, 0xad, 0xbe, 0xef]
+            );
+            assert_eq!(asc_to_bytes(AscPtr::new(raw)), vec![1, 2, 3]);
+            assert!(asc_to_bytes(AscPtr::null()).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_big_int_round_trip() {
+        let mut image = AscImage::new();
+        let values = [
+            "0",
+            "1",
+            "-1",
+            "255",
+            "-129",
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        ];
+        let ptrs: Vec<u32> = values
+            .iter()
+            .map(|v| {
+                image
+                    .write_big_int(&BigInt::from_string(v).unwrap())
+                    .unwrap()
+            })
+            .collect();
+
+        image.enter(|| {
+            for (value, ptr) in values.iter().zip(ptrs) {
+                assert_eq!(BigInt::from_asc_ptr(ptr).to_string(), *value);
+            }
+            assert_eq!(BigInt::from_asc_ptr(0), BigInt::zero());
+        });
+    }
+
+    #[test]
+    fn test_block_and_transaction_round_trip() {
+        let mut image = AscImage::new();
+        let block_ptr = image.write_block(&block()).unwrap();
+        let tx_ptr = image.write_transaction(&transaction()).unwrap();
+
+        let (decoded_block, decoded_tx) = image.enter(|| {
+            (
+                Block::from_asc_ptr(block_ptr),
+                Transaction::from_asc_ptr(tx_ptr),
+            )
+        });
+
+        assert_block_eq(&decoded_block, &block());
+        assert_transaction_eq(&decoded_tx, &transaction());
+    }
+
+    #[test]
+    fn test_eip1559_transaction_round_trip() {
+        let tx = Transaction {
+            max_fee_per_gas: Some(BigInt::from_u64(40_000_000_000)),
+            max_priority_fee_per_gas: Some(BigInt::from_u64(2_000_000_000)),
+            transaction_type: Some(BigInt::from_u64(2)),
+            ..transaction()
+        };
+        let mut image = AscImage::new();
+        let legacy_ptr = image.write_transaction(&transaction()).unwrap();
+        let tx_ptr = image.write_transaction(&tx).unwrap();
+
+        // graph-node's 36-byte transaction has no EIP-1559 fields to read
+        let (legacy, decoded) = image.enter(|| {
+            assert_eq!(unsafe { read_rt_size(legacy_ptr) }, 36);
+            (
+                Transaction::from_asc_ptr(legacy_ptr),
+                Transaction::from_asc_ptr(tx_ptr),
+            )
+        });
+
+        assert_transaction_eq(&legacy, &transaction());
+        assert_transaction_eq(&decoded, &tx);
+    }
+
+    #[test]
+    fn test_receipt_round_trip() {
+        let log = Log {
+            address: address(0x01),
+            topics: vec![Bytes::from_vec(vec![0xdd; 32])],
+            data: Bytes::from_vec(vec![0, 1, 2, 3]),
+            log_index: BigInt::from_u64(3),
+            ..Log::default()
+        };
+        let receipt = TransactionReceipt {
+            transaction_hash: Bytes::from_vec(vec![0x22; 32]),
+            gas_used: BigInt::from_u64(52_000),
+            contract_address: Some(address(0xcc)),
+            logs: vec![log.clone(), Log { address: address(0x02), ..log }],
+            status: BigInt::from_u64(1),
+            logs_bloom: Bytes::from_vec(vec![0xff; 256]),
+            ..TransactionReceipt::default()
+        };
+        let mut image = AscImage::new();
+        let ptr = image.write_receipt(&receipt).unwrap();
+
+        let decoded = image.enter(|| TransactionReceipt::from_asc_ptr(ptr));
+
+        assert_eq!(decoded.transaction_hash, receipt.transaction_hash);
+        assert_eq!(decoded.gas_used, receipt.gas_used);
+        assert_eq!(decoded.contract_address, receipt.contract_address);
+        assert_eq!(decoded.logs.len(), 2);
+        assert_eq!(decoded.logs[0].topics, receipt.logs[0].topics);
+        assert_eq!(decoded.logs[0].data, receipt.logs[0].data);
+        assert_eq!(decoded.logs[1].address, address(0x02));
+        assert_eq!(decoded.status, receipt.status);
+        assert_eq!(decoded.logs_bloom, receipt.logs_bloom);
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let event = Event {
+            address: address(0x01),
+            log_index: BigInt::from_u64(3),
+            transaction_log_index: BigInt::from_u64(1),
+            log_type: Some("mined".into()),
+            block: block(),
+            transaction: transaction(),
+            params: transfer_params(),
+            receipt: None,
+        };
+        let mut image = AscImage::new();
+        let ptr = image.write_event(&event).unwrap();
+
+        let decoded = image.enter(|| Event::<TransferParams>::from_asc_ptr(ptr));
+
+        assert_eq!(decoded.address, event.address);
+        assert_eq!(decoded.log_index, event.log_index);
+        assert_eq!(decoded.transaction_log_index, event.transaction_log_index);
+        assert_eq!(decoded.log_type.as_deref(), Some("mined"));
+        assert_block_eq(&decoded.block, &event.block);
+        assert_transaction_eq(&decoded.transaction, &event.transaction);
+        assert_eq!(decoded.params, event.params);
+        assert!(decoded.receipt.is_none());
+    }
+
+    #[test]
+    fn test_call_round_trip() {
+        let call = Call {
+            to: address(0x02),
+            from: address(0x03),
+            block: block(),
+            transaction: transaction(),
+            inputs: transfer_params(),
+            outputs: TransferParams {
+                memo: String::new(),
+                ..transfer_params()
+            },
+        };
+        let mut image = AscImage::new();
+        let ptr = image.write_call(&call).unwrap();
+
+        let decoded = image.enter(|| Call::<TransferParams, TransferParams>::from_asc_ptr(ptr));
+
+        assert_eq!(decoded.to, call.to);
+        assert_eq!(decoded.from, call.from);
+        assert_block_eq(&decoded.block, &call.block);
+        assert_transaction_eq(&decoded.transaction, &call.transaction);
+        assert_eq!(decoded.inputs, call.inputs);
+        assert_eq!(decoded.outputs, call.outputs);
+    }
+
+    #[test]
+    fn test_log_round_trip() {
+        let log = Log {
+            address: address(0x01),
+            topics: vec![
+                Bytes::from_vec(vec![0xdd; 32]),
+                Bytes::from_vec(vec![0xaa; 32]),
+            ],
+            data: Bytes::from_vec(vec![0, 1, 2, 3]),
+            block_hash: Bytes::from_vec(vec![0x11; 32]),
+            block_number: BigInt::from_u64(19_000_000),
+            transaction_hash: Bytes::from_vec(vec![0x22; 32]),
+            transaction_index: BigInt::from_u64(7),
+            log_index: BigInt::from_u64(3),
+            transaction_log_index: BigInt::from_u64(1),
+            log_type: None,
+            removed: Some(false),
+        };
+        let mut image = AscImage::new();
+        let ptr = image.write_log(&log).unwrap();
+
+        let decoded = image.enter(|| Log::from_asc_ptr(ptr));
+
+        assert_eq!(decoded.address, log.address);
+        assert_eq!(decoded.topics, log.topics);
+        assert_eq!(decoded.data, log.data);
+        assert_eq!(decoded.block_hash, log.block_hash);
+        assert_eq!(decoded.block_number, log.block_number);
+        assert_eq!(decoded.transaction_hash, log.transaction_hash);
+        assert_eq!(decoded.transaction_index, log.transaction_index);
+        assert_eq!(decoded.log_index, log.log_index);
+        assert_eq!(decoded.transaction_log_index, log.transaction_log_index);
+        assert_eq!(decoded.log_type, None);
+        assert_eq!(decoded.removed, Some(false));
+    }
+
+    #[test]
+    fn test_nested_token_round_trip() {
+        // A `(uint128,int24)[]` next to a `tuple(address,bytes32,string)`
+        let tokens = vec![
+            Token::Array(vec![
+                Token::Tuple(vec![
+                    Token::Uint(BigInt::from_u64(5)),
+                    Token::Int(BigInt::from_i64(-887272)),
+                ]),
+                Token::Tuple(vec![
+                    Token::Uint(BigInt::zero()),
+                    Token::Int(BigInt::from_i64(60)),
+                ]),
+            ]),
+            Token::Tuple(vec![
+                Token::Address(address(0xaa)),
+                Token::FixedBytes(vec![0x33; 32]),
+                Token::String("pool".into()),
+            ]),
+            Token::FixedArray(vec![Token::Bool(true), Token::Bool(false)]),
+        ];
+        let mut image = AscImage::new();
+        let ptrs: Vec<u32> = tokens
+            .iter()
+            .map(|t| image.write_token(t).unwrap())
+            .collect();
+
+        let decoded: Vec<Token> =
+            image.enter(|| ptrs.iter().map(|&p| Token::from_asc_ptr(p)).collect());
+
+        assert_eq!(decoded, tokens);
+    }
+
+    #[test]
+    fn test_enter_restores_previous_image() {
+        let mut outer = AscImage::new();
+        let outer_ptr = outer.write_string("outer").unwrap();
+        let inner = AscImage::new();
+
+        outer.enter(|| {
+            inner.enter(|| ());
+            assert_eq!(asc_to_string(AscPtr::new(outer_ptr)), "outer");
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "no AscImage installed")]
+    fn test_reads_without_image_panic() {
+        asc_to_string(AscPtr::new(32));
+    }
+
+    #[test]
+    fn test_decimal_from_parts() {
+        let parts = |digits: i64, exp: i64| {
+            decimal_from_parts(&BigInt::from_i64(digits), &BigInt::from_i64(exp))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(parts(125, -2), "1.25");
+        assert_eq!(parts(-5, 3), "-5000");
+        assert_eq!(parts(5, -3), "0.005");
+    }
+}
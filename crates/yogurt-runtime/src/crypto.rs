@@ -1,4 +1,14 @@
 //! Cryptographic utilities.
+//!
+//! graph-node only exposes keccak256 as a host function, so every other hash
+//! is implemented in pure Rust in the submodules below. They need no host
+//! calls or allocation beyond the result, and behave identically in native
+//! tests and in WASM.
+
+pub mod blake2;
+pub mod keccak;
+pub mod ripemd160;
+pub mod sha2;
 
 #[cfg(target_arch = "wasm32")]
 use crate::asc::{asc_to_bytes, bytes_to_asc, AscPtr};
@@ -6,7 +16,8 @@ use crate::types::Bytes;
 
 /// Compute the Keccak-256 hash of the input data.
 ///
-/// Returns a 32-byte hash.
+/// Returns a 32-byte hash. In WASM this calls graph-node's `crypto.keccak256`;
+/// [`keccak::keccak256`] is the same hash without the host call.
 ///
 /// # Example
 ///
@@ -25,32 +36,179 @@ pub fn keccak256(data: &[u8]) -> Bytes {
 
 #[cfg(not(target_arch = "wasm32"))]
 pub fn keccak256(data: &[u8]) -> Bytes {
-    use sha3::{Digest, Keccak256};
-
-    let mut hasher = Keccak256::new();
-    hasher.update(data);
-    let result = hasher.finalize();
-    Bytes::from_vec(result.to_vec())
+    Bytes::from(keccak::keccak256(data))
 }
 
 /// Compute the SHA-256 hash of the input data.
 ///
 /// Returns a 32-byte hash.
+pub fn sha256(data: &[u8]) -> Bytes {
+    Bytes::from(sha2::sha256(data))
+}
+
+/// Compute the SHA3-256 hash of the input data.
 ///
-/// Note: This function is only available in native builds (for testing).
-/// Graph-node does not provide a SHA-256 host function - only keccak256.
-/// In WASM builds, this will panic. Use `keccak256` instead for production code.
-#[cfg(target_arch = "wasm32")]
-pub fn sha256(_data: &[u8]) -> Bytes {
-    panic!("sha256 is not available in WASM - graph-node only supports keccak256")
+/// Returns a 32-byte hash. This is the standardised FIPS 202 hash, which
+/// differs from Ethereum's [`keccak256`] only in padding.
+pub fn sha3_256(data: &[u8]) -> Bytes {
+    Bytes::from(keccak::sha3_256(data))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn sha256(data: &[u8]) -> Bytes {
-    use sha2::{Digest, Sha256};
+/// Compute the RIPEMD-160 hash of the input data.
+///
+/// Returns a 20-byte hash.
+pub fn ripemd160(data: &[u8]) -> Bytes {
+    Bytes::from_vec(ripemd160::ripemd160(data).to_vec())
+}
+
+/// Compute the BLAKE2b-512 hash of the input data.
+///
+/// Returns a 64-byte hash. Use [`blake2::blake2b`] for shorter digests,
+/// e.g. BLAKE2b-256.
+pub fn blake2b(data: &[u8]) -> Bytes {
+    Bytes::from_vec(blake2::blake2b(data, 64))
+}
+
+/// Compute the BLAKE2s-256 hash of the input data.
+///
+/// Returns a 32-byte hash.
+pub fn blake2s(data: &[u8]) -> Bytes {
+    Bytes::from_vec(blake2::blake2s(data, 32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn hex(bytes: &[u8]) -> alloc::string::String {
+        Bytes::from(bytes).to_hex()
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            sha256(b"").to_hex(),
+            "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256(b"abc").to_hex(),
+            "0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        // Every padding boundary, against the reference implementation
+        use ::sha2::Digest;
+        for len in 0..300 {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(
+                sha2::sha256(&data)[..],
+                ::sha2::Sha256::digest(&data)[..],
+                "length {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_keccak256_and_sha3_256() {
+        assert_eq!(
+            keccak256(b"").to_hex(),
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            sha3_256(b"").to_hex(),
+            "0xa7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+
+        use ::sha3::Digest;
+        for len in 0..300 {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(
+                keccak::keccak256(&data)[..],
+                ::sha3::Keccak256::digest(&data)[..],
+                "length {}",
+                len
+            );
+            assert_eq!(
+                keccak::sha3_256(&data)[..],
+                ::sha3::Sha3_256::digest(&data)[..],
+                "length {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_ripemd160() {
+        let cases: [(&[u8], &str); 4] = [
+            (b"", "0x9c1185a5c5e9fc54612808977ee8f548b2258d31"),
+            (b"abc", "0x8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+            (
+                b"message digest",
+                "0x5d0689ef49d2fae572b881b123a85ffa21595f36",
+            ),
+            (
+                b"abcdefghijklmnopqrstuvwxyz",
+                "0xf71c27109c692c1b56bbdceb5b9d2865b3708dbc",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(ripemd160(input).to_hex(), expected);
+        }
+
+        let counting: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        assert_eq!(
+            hex(&ripemd160::ripemd160(&counting)),
+            "0x91293d6ee016d6e273deee1c55fb5b3891fc55e7"
+        );
+
+        let million_a = alloc::vec![b'a'; 1_000_000];
+        assert_eq!(
+            hex(&ripemd160::ripemd160(&million_a)),
+            "0x52783243c1697bdbe16d37f97f68f08325dc1528"
+        );
+    }
+
+    #[test]
+    fn test_blake2() {
+        assert_eq!(
+            blake2b(b"").to_hex(),
+            "0x786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+             d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+        );
+        assert_eq!(
+            blake2b(b"abc").to_hex(),
+            "0xba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(
+            hex(&blake2::blake2b(b"", 32)),
+            "0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+
+        // Multi-block inputs, including an exactly full final block
+        let counting: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        assert_eq!(
+            blake2b(&counting).to_hex(),
+            "0xd9cf5983dc6b34c0fa1f0226926855ad3eccd2bcdcd8f8053b9a80664d33b5af\
+             cc32fd21c70ea14f4ef50ca97c3203c4d1803159f0e01bb6cb1d1c83db52b63c"
+        );
+        assert_eq!(
+            hex(&blake2::blake2b(&counting[..256], 32)),
+            "0x39a7eb9fedc19aabc83425c6755dd90e6f9d0c804964a1f4aaeea3b9fb599835"
+        );
+        assert_eq!(
+            blake2s(&counting[..200]).to_hex(),
+            "0x6d244e1a06ce4ef578dd0f63aff0936706735119ca9c8d22d86c801414ab9741"
+        );
 
-    let mut hasher = Sha256::new();
-    hasher.update(data);
-    let result = hasher.finalize();
-    Bytes::from_vec(result.to_vec())
+        assert_eq!(
+            blake2s(b"").to_hex(),
+            "0x69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"
+        );
+        assert_eq!(
+            blake2s(b"abc").to_hex(),
+            "0x508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+        );
+    }
 }
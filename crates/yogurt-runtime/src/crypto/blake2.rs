@@ -0,0 +1,176 @@
+//! BLAKE2b and BLAKE2s (RFC 7693), unkeyed.
+//!
+//! Both support any digest length up to their maximum: 64 bytes for BLAKE2b
+//! and 32 for BLAKE2s. Substrate chains, for one, key storage with
+//! BLAKE2b-256.
+
+use alloc::vec::Vec;
+
+/// Message word schedule, shared by both variants.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+const IV_B: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const IV_S: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Compute the BLAKE2b digest of `data`, `out_len` bytes long.
+///
+/// # Panics
+///
+/// If `out_len` is 0 or greater than 64.
+pub fn blake2b(data: &[u8], out_len: usize) -> Vec<u8> {
+    assert!(
+        (1..=64).contains(&out_len),
+        "BLAKE2b digest length must be 1..=64"
+    );
+
+    let mut h = IV_B;
+    h[0] ^= 0x01010000 ^ out_len as u64;
+
+    // The last block is always compressed as final, even if it is full
+    let block_count = data.len().div_ceil(128).max(1);
+    for i in 0..block_count {
+        let chunk = &data[i * 128..data.len().min((i + 1) * 128)];
+        let mut block = [0u8; 128];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let mut m = [0u64; 16];
+        for (word, bytes) in m.iter_mut().zip(block.chunks_exact(8)) {
+            *word = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        let counter = (i * 128 + chunk.len()) as u128;
+        compress_b(&mut h, &m, counter, i + 1 == block_count);
+    }
+
+    h.iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take(out_len)
+        .collect()
+}
+
+/// Compute the BLAKE2s digest of `data`, `out_len` bytes long.
+///
+/// # Panics
+///
+/// If `out_len` is 0 or greater than 32.
+pub fn blake2s(data: &[u8], out_len: usize) -> Vec<u8> {
+    assert!(
+        (1..=32).contains(&out_len),
+        "BLAKE2s digest length must be 1..=32"
+    );
+
+    let mut h = IV_S;
+    h[0] ^= 0x01010000 ^ out_len as u32;
+
+    let block_count = data.len().div_ceil(64).max(1);
+    for i in 0..block_count {
+        let chunk = &data[i * 64..data.len().min((i + 1) * 64)];
+        let mut block = [0u8; 64];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let mut m = [0u32; 16];
+        for (word, bytes) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        let counter = (i * 64 + chunk.len()) as u64;
+        compress_s(&mut h, &m, counter, i + 1 == block_count);
+    }
+
+    h.iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take(out_len)
+        .collect()
+}
+
+fn compress_b(h: &mut [u64; 8], m: &[u64; 16], counter: u128, last: bool) {
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV_B);
+    v[12] ^= counter as u64;
+    v[13] ^= (counter >> 64) as u64;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for round in 0..12 {
+        let s = &SIGMA[round % 10];
+        let mut g = |a: usize, b: usize, c: usize, d: usize, x: u64, y: u64| {
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+            v[d] = (v[d] ^ v[a]).rotate_right(32);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(24);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+            v[d] = (v[d] ^ v[a]).rotate_right(16);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(63);
+        };
+        g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+fn compress_s(h: &mut [u32; 8], m: &[u32; 16], counter: u64, last: bool) {
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV_S);
+    v[12] ^= counter as u32;
+    v[13] ^= (counter >> 32) as u32;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for s in &SIGMA {
+        let mut g = |a: usize, b: usize, c: usize, d: usize, x: u32, y: u32| {
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+            v[d] = (v[d] ^ v[a]).rotate_right(16);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(12);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+            v[d] = (v[d] ^ v[a]).rotate_right(8);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(7);
+        };
+        g(0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
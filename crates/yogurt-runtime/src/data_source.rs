@@ -31,9 +31,10 @@ pub fn create(name: &str, params: &[String]) {
     }
 }
 
+/// Records the data source in tests, see `testing::created_data_sources`.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn create(_name: &str, _params: &[String]) {
-    // No-op in tests - templates don't spawn real data sources
+pub fn create(name: &str, params: &[String]) {
+    crate::testing::record_created_data_source(name, params, None);
 }
 
 /// Create a new data source from a template with context.
@@ -62,9 +63,10 @@ pub fn create_with_context(name: &str, params: &[String], context: EntityData) {
     }
 }
 
+/// Records the data source in tests, see `testing::created_data_sources`.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn create_with_context(_name: &str, _params: &[String], _context: EntityData) {
-    // No-op in tests - templates don't spawn real data sources
+pub fn create_with_context(name: &str, params: &[String], context: EntityData) {
+    crate::testing::record_created_data_source(name, params, Some(context));
 }
 
 /// Get the address of the current data source.
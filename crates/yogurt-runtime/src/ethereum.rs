@@ -2,9 +2,12 @@
 
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
 
 use crate::asc::{asc_to_bytes, asc_to_string, AscPtr, FromAscPtr};
-use crate::types::{Address, BigInt, Bytes};
+use crate::types::{Address, BigInt, Bytes, Timestamp};
+
+pub mod abi;
 
 // ============================================================================
 // Memory Layout Constants
@@ -40,6 +43,10 @@ mod tx_offsets {
     pub const GAS_PRICE: usize = 24;
     pub const INPUT: usize = 28;
     pub const NONCE: usize = 32;
+    // EIP-1559 fields, present only when the host object is large enough
+    pub const MAX_FEE_PER_GAS: usize = 36;
+    pub const MAX_PRIORITY_FEE_PER_GAS: usize = 40;
+    pub const TYPE: usize = 44;
 }
 
 // Field offsets for EthereumEvent
@@ -63,9 +70,25 @@ mod receipt_offsets {
     pub const CUMULATIVE_GAS_USED: usize = 16;
     pub const GAS_USED: usize = 20;
     pub const CONTRACT_ADDRESS: usize = 24;
-    pub const STATUS: usize = 28;
-    pub const ROOT: usize = 32;
-    pub const LOGS_BLOOM: usize = 36;
+    pub const LOGS: usize = 28;
+    pub const STATUS: usize = 32;
+    pub const ROOT: usize = 36;
+    pub const LOGS_BLOOM: usize = 40;
+}
+
+// Field offsets for Log
+mod log_offsets {
+    pub const ADDRESS: usize = 0;
+    pub const TOPICS: usize = 4;
+    pub const DATA: usize = 8;
+    pub const BLOCK_HASH: usize = 12;
+    pub const BLOCK_NUMBER: usize = 16;
+    pub const TRANSACTION_HASH: usize = 20;
+    pub const TRANSACTION_INDEX: usize = 24;
+    pub const LOG_INDEX: usize = 28;
+    pub const TRANSACTION_LOG_INDEX: usize = 32;
+    pub const LOG_TYPE: usize = 36;
+    pub const REMOVED: usize = 40;
 }
 
 // Field offsets for EthereumCall
@@ -102,6 +125,13 @@ pub struct Block {
     pub base_fee_per_gas: Option<BigInt>,
 }
 
+/// The block's timestamp, converted from seconds.
+impl From<&Block> for Timestamp {
+    fn from(block: &Block) -> Self {
+        Timestamp::from_block_timestamp(&block.timestamp)
+    }
+}
+
 /// An Ethereum transaction.
 #[derive(Clone, Debug)]
 pub struct Transaction {
@@ -114,6 +144,12 @@ pub struct Transaction {
     pub gas_price: BigInt,
     pub input: Bytes,
     pub nonce: BigInt,
+    /// EIP-1559 fee cap, `None` for legacy transactions or when the host
+    /// does not provide it.
+    pub max_fee_per_gas: Option<BigInt>,
+    pub max_priority_fee_per_gas: Option<BigInt>,
+    /// EIP-2718 type: 0 legacy, 1 access list, 2 EIP-1559.
+    pub transaction_type: Option<BigInt>,
 }
 
 /// An Ethereum transaction receipt.
@@ -126,11 +162,152 @@ pub struct TransactionReceipt {
     pub cumulative_gas_used: BigInt,
     pub gas_used: BigInt,
     pub contract_address: Option<Address>,
+    /// Every log the transaction emitted, in order.
+    pub logs: Vec<Log>,
     pub status: BigInt,
     pub root: Bytes,
     pub logs_bloom: Bytes,
 }
 
+impl TransactionReceipt {
+    /// Decode the logs that are events of type `P`, skipping the others.
+    ///
+    /// ```ignore
+    /// let transfers: Vec<TransferParams> = receipt.decode_logs().collect();
+    /// ```
+    pub fn decode_logs<'a, P: FromLog + 'a>(&'a self) -> impl Iterator<Item = P> + 'a {
+        self.logs.iter().filter_map(Log::decode)
+    }
+}
+
+/// Params decodable from a raw [`Log`], implemented by codegen for every
+/// event params struct.
+pub trait FromLog: Sized {
+    /// Decode the log, or `None` if it is another event or does not decode.
+    fn from_log(log: &Log) -> Option<Self>;
+}
+
+/// Params encodable into a raw [`Log`], the inverse of [`FromLog`].
+///
+/// Implemented by codegen for every event params struct on the native target,
+/// where tests need the topics of a built event to apply topic filters.
+pub trait ToLog {
+    /// Encode the params as a log emitted by `address`.
+    fn to_log(&self, address: Address) -> Log;
+}
+
+/// The ABI-encoded inputs or outputs of a function call.
+///
+/// Implemented by codegen for the inputs and outputs of every call handler
+/// on the native target, where tests feed raw calls to `testing::Chain`.
+/// Inputs start with the 4-byte selector of their function, so decoding the
+/// input of a call to another function fails.
+pub trait CallData: Sized {
+    /// Decode call data, or `None` if it is not this function's.
+    fn from_call_data(data: &[u8]) -> Option<Self>;
+
+    /// Encode as call data.
+    fn to_call_data(&self) -> Vec<u8>;
+}
+
+/// The `topic1` to `topic3` filters of an event handler.
+///
+/// graph-node only triggers the handler on logs whose topic at each set
+/// position is one of the listed values. Codegen emits one as
+/// `<HANDLER>_TOPIC_FILTER` for every event handler that filters on topics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TopicFilter {
+    pub topic1: Option<&'static [[u8; 32]]>,
+    pub topic2: Option<&'static [[u8; 32]]>,
+    pub topic3: Option<&'static [[u8; 32]]>,
+}
+
+impl TopicFilter {
+    /// A filter that lets every log through.
+    pub const ANY: TopicFilter = TopicFilter { topic1: None, topic2: None, topic3: None };
+
+    /// Whether graph-node would deliver `log` to the filtered handler.
+    pub fn matches(&self, log: &Log) -> bool {
+        [self.topic1, self.topic2, self.topic3]
+            .iter()
+            .enumerate()
+            .all(|(i, values)| match values {
+                None => true,
+                Some(values) => log
+                    .topics
+                    .get(i + 1)
+                    .is_some_and(|topic| values.iter().any(|v| v.as_slice() == topic.as_slice())),
+            })
+    }
+}
+
+/// Which blocks graph-node runs a block handler on, its manifest `filter`.
+///
+/// Codegen emits one as `<HANDLER>_BLOCK_FILTER` for every block handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockFilter {
+    /// No filter: every block from the start block.
+    All,
+    /// `kind: call`: blocks with a call to the data source's contract.
+    Call,
+    /// `kind: once`: the start block only.
+    Once,
+    /// `kind: polling`: every `every` blocks, counting from the start block.
+    Polling { every: u64 },
+}
+
+impl BlockFilter {
+    /// Whether graph-node runs the handler on block `number` of a data source
+    /// starting at `start_block`. `has_call` is whether the block calls the
+    /// data source's contract.
+    pub fn matches(&self, number: u64, start_block: u64, has_call: bool) -> bool {
+        if number < start_block {
+            return false;
+        }
+        match self {
+            BlockFilter::All => true,
+            BlockFilter::Call => has_call,
+            BlockFilter::Once => number == start_block,
+            BlockFilter::Polling { every } => (number - start_block).checked_rem(*every) == Some(0),
+        }
+    }
+}
+
+/// A raw Ethereum log.
+///
+/// Unlike [`Event`], the parameters are not decoded: indexed ones are in
+/// `topics` and the rest ABI-encoded in `data`. Generated params structs
+/// decode a log of their event with `from_log`, which is the only way to
+/// read anonymous events.
+#[derive(Clone, Debug, Default)]
+pub struct Log {
+    pub address: Address,
+    /// Up to four 32-byte topics. The first is the event signature hash,
+    /// unless the event is anonymous.
+    pub topics: Vec<Bytes>,
+    pub data: Bytes,
+    pub block_hash: Bytes,
+    pub block_number: BigInt,
+    pub transaction_hash: Bytes,
+    pub transaction_index: BigInt,
+    pub log_index: BigInt,
+    pub transaction_log_index: BigInt,
+    pub log_type: Option<String>,
+    pub removed: Option<bool>,
+}
+
+impl Log {
+    /// The signature hash of a non-anonymous event.
+    pub fn topic0(&self) -> Option<&Bytes> {
+        self.topics.first()
+    }
+
+    /// Decode the log as event params `P`.
+    pub fn decode<P: FromLog>(&self) -> Option<P> {
+        P::from_log(self)
+    }
+}
+
 /// An Ethereum event (log) with typed parameters.
 #[derive(Clone, Debug)]
 pub struct Event<P> {
@@ -169,8 +346,76 @@ pub struct SmartContractCall {
     pub function_params: Vec<Token>,
 }
 
+/// Why a `try_*` contract call failed.
+///
+/// `E` is the generated enum of the contract's custom errors. graph-node's
+/// `ethereum.call` returns no revert data, so in a deployed subgraph every
+/// revert is `Unknown` with empty data; tests see the payload given to
+/// `testing::mock_call_reverts`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallError<E> {
+    /// `revert("reason")` or a failed `require`: `Error(string)`.
+    Revert(String),
+    /// A custom error declared in the contract's ABI.
+    Custom(E),
+    /// Revert data that is not a known error, possibly empty.
+    Unknown(Bytes),
+}
+
+/// Custom errors of a contract, implemented by codegen for the generated
+/// `<Contract>Error` enum.
+pub trait ContractError: Sized {
+    /// Decode revert data, selector included, or `None` if it is not one of
+    /// the contract's errors.
+    fn decode(data: &[u8]) -> Option<Self>;
+
+    /// Encode the error as revert data, such as for `mock_call_reverts`.
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// The selector of `Error(string)`.
+const REVERT_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+impl<E: ContractError> CallError<E> {
+    /// Classify the revert data of a failed call.
+    pub fn from_revert_data(data: &[u8]) -> Self {
+        if let Some(reason) = data
+            .strip_prefix(&REVERT_SELECTOR)
+            .and_then(|args| abi::decode(&[abi::ParamType::String], args))
+            .and_then(|mut tokens| match tokens.pop() {
+                Some(Token::String(reason)) => Some(reason),
+                _ => None,
+            })
+        {
+            return CallError::Revert(reason);
+        }
+        match E::decode(data) {
+            Some(error) => CallError::Custom(error),
+            None => CallError::Unknown(Bytes::from(data)),
+        }
+    }
+}
+
+/// ABI-encode `reason` as `Error(string)` revert data.
+pub fn encode_revert_reason(reason: &str) -> Vec<u8> {
+    let mut data = REVERT_SELECTOR.to_vec();
+    data.extend(abi::encode(&[Token::String(reason.into())]).unwrap_or_default());
+    data
+}
+
+impl<E: fmt::Debug> fmt::Display for CallError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallError::Revert(reason) => write!(f, "Call reverted: {}", reason),
+            CallError::Custom(error) => write!(f, "Call reverted with {:?}", error),
+            CallError::Unknown(data) if data.is_empty() => f.write_str("Call reverted"),
+            CallError::Unknown(data) => write!(f, "Call reverted with data {}", data.to_hex()),
+        }
+    }
+}
+
 /// ABI token types for encoding/decoding function calls.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Token {
     Address(Address),
     FixedBytes(Vec<u8>),
@@ -184,11 +429,32 @@ pub enum Token {
     Tuple(Vec<Token>),
 }
 
+/// A named event (or call) parameter, as graph-node passes it to handlers.
+#[derive(Clone, Debug)]
+pub struct EventParam {
+    pub name: String,
+    pub value: Token,
+}
+
+/// Conversion of typed event params or call inputs/outputs back into the
+/// `Array<EventParam>` form graph-node hands to handlers.
+///
+/// Implemented by codegen for every generated params struct. The WASM host
+/// emulator uses it to serialise test events into guest memory.
+pub trait ToEventParams {
+    fn to_event_params(&self) -> Vec<EventParam>;
+}
+
+impl ToEventParams for () {
+    fn to_event_params(&self) -> Vec<EventParam> {
+        Vec::new()
+    }
+}
+
 // ============================================================================
 // FromAscPtr Implementations
 // ============================================================================
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for Address {
     fn from_asc_ptr(ptr: u32) -> Self {
         if ptr == 0 {
@@ -199,14 +465,6 @@ impl FromAscPtr for Address {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for Address {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        Address::zero()
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for Bytes {
     fn from_asc_ptr(ptr: u32) -> Self {
         if ptr == 0 {
@@ -216,13 +474,6 @@ impl FromAscPtr for Bytes {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for Bytes {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        Bytes::new()
-    }
-}
-
 #[cfg(target_arch = "wasm32")]
 impl FromAscPtr for BigInt {
     fn from_asc_ptr(ptr: u32) -> Self {
@@ -232,12 +483,14 @@ impl FromAscPtr for BigInt {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl FromAscPtr for BigInt {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        BigInt::zero()
+    fn from_asc_ptr(ptr: u32) -> Self {
+        if ptr == 0 {
+            return BigInt::zero();
+        }
+        BigInt::from_signed_bytes(&asc_to_bytes(AscPtr::new(ptr)))
     }
 }
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for Block {
     fn from_asc_ptr(ptr: u32) -> Self {
         use crate::asc::read_u32_at;
@@ -274,13 +527,6 @@ impl FromAscPtr for Block {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for Block {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        Block::default()
-    }
-}
-
 impl Default for Block {
     fn default() -> Self {
         Block {
@@ -303,7 +549,6 @@ impl Default for Block {
     }
 }
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for Transaction {
     fn from_asc_ptr(ptr: u32) -> Self {
         use crate::asc::read_u32_at;
@@ -326,18 +571,14 @@ impl FromAscPtr for Transaction {
                 gas_price: BigInt::from_asc_ptr(read_u32_at(ptr, tx_offsets::GAS_PRICE)),
                 input: Bytes::from_asc_ptr(read_u32_at(ptr, tx_offsets::INPUT)),
                 nonce: BigInt::from_asc_ptr(read_u32_at(ptr, tx_offsets::NONCE)),
+                max_fee_per_gas: read_optional_big_int(ptr, tx_offsets::MAX_FEE_PER_GAS),
+                max_priority_fee_per_gas: read_optional_big_int(ptr, tx_offsets::MAX_PRIORITY_FEE_PER_GAS),
+                transaction_type: read_optional_big_int(ptr, tx_offsets::TYPE),
             }
         }
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for Transaction {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        Transaction::default()
-    }
-}
-
 impl Default for Transaction {
     fn default() -> Self {
         Transaction {
@@ -350,11 +591,31 @@ impl Default for Transaction {
             gas_price: BigInt::zero(),
             input: Bytes::new(),
             nonce: BigInt::zero(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            transaction_type: None,
         }
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+/// Read an optional `BigInt` field, treating fields past the end of the
+/// object as absent: graph-node's transaction predates EIP-1559.
+///
+/// # Safety
+/// `ptr` must point to a live AssemblyScript object.
+unsafe fn read_optional_big_int(ptr: u32, offset: usize) -> Option<BigInt> {
+    use crate::allocator::read_rt_size;
+    use crate::asc::read_u32_at;
+
+    unsafe {
+        if (read_rt_size(ptr) as usize) < offset + 4 {
+            return None;
+        }
+        let p = read_u32_at(ptr, offset);
+        if p == 0 { None } else { Some(BigInt::from_asc_ptr(p)) }
+    }
+}
+
 impl FromAscPtr for TransactionReceipt {
     fn from_asc_ptr(ptr: u32) -> Self {
         use crate::asc::read_u32_at;
@@ -375,6 +636,18 @@ impl FromAscPtr for TransactionReceipt {
                     let p = read_u32_at(ptr, receipt_offsets::CONTRACT_ADDRESS);
                     if p == 0 { None } else { Some(Address::from_asc_ptr(p)) }
                 },
+                logs: {
+                    // Array<Log>: buffer, buffer_data_start, buffer_data_length, length
+                    let array = read_u32_at(ptr, receipt_offsets::LOGS);
+                    if array == 0 {
+                        Vec::new()
+                    } else {
+                        let data_start = read_u32_at(array, 4);
+                        (0..read_u32_at(array, 12) as usize)
+                            .map(|i| Log::from_asc_ptr(read_u32_at(data_start, i * 4)))
+                            .collect()
+                    }
+                },
                 status: BigInt::from_asc_ptr(read_u32_at(ptr, receipt_offsets::STATUS)),
                 root: Bytes::from_asc_ptr(read_u32_at(ptr, receipt_offsets::ROOT)),
                 logs_bloom: Bytes::from_asc_ptr(read_u32_at(ptr, receipt_offsets::LOGS_BLOOM)),
@@ -383,13 +656,6 @@ impl FromAscPtr for TransactionReceipt {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for TransactionReceipt {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        TransactionReceipt::default()
-    }
-}
-
 impl Default for TransactionReceipt {
     fn default() -> Self {
         TransactionReceipt {
@@ -400,6 +666,7 @@ impl Default for TransactionReceipt {
             cumulative_gas_used: BigInt::zero(),
             gas_used: BigInt::zero(),
             contract_address: None,
+            logs: Vec::new(),
             status: BigInt::zero(),
             root: Bytes::new(),
             logs_bloom: Bytes::new(),
@@ -407,11 +674,54 @@ impl Default for TransactionReceipt {
     }
 }
 
+impl FromAscPtr for Log {
+    fn from_asc_ptr(ptr: u32) -> Self {
+        use crate::asc::{read_bytes_at, read_u32_at};
+
+        if ptr == 0 {
+            return Log::default();
+        }
+
+        unsafe {
+            Log {
+                address: Address::from_asc_ptr(read_u32_at(ptr, log_offsets::ADDRESS)),
+                topics: {
+                    // Array<Bytes>: buffer, buffer_data_start, buffer_data_length, length
+                    let array = read_u32_at(ptr, log_offsets::TOPICS);
+                    if array == 0 {
+                        Vec::new()
+                    } else {
+                        let data_start = read_u32_at(array, 4);
+                        (0..read_u32_at(array, 12) as usize)
+                            .map(|i| Bytes::from_asc_ptr(read_u32_at(data_start, i * 4)))
+                            .collect()
+                    }
+                },
+                data: Bytes::from_asc_ptr(read_u32_at(ptr, log_offsets::DATA)),
+                block_hash: Bytes::from_asc_ptr(read_u32_at(ptr, log_offsets::BLOCK_HASH)),
+                block_number: BigInt::from_asc_ptr(read_u32_at(ptr, log_offsets::BLOCK_NUMBER)),
+                transaction_hash: Bytes::from_asc_ptr(read_u32_at(ptr, log_offsets::TRANSACTION_HASH)),
+                transaction_index: BigInt::from_asc_ptr(read_u32_at(ptr, log_offsets::TRANSACTION_INDEX)),
+                log_index: BigInt::from_asc_ptr(read_u32_at(ptr, log_offsets::LOG_INDEX)),
+                transaction_log_index: BigInt::from_asc_ptr(read_u32_at(ptr, log_offsets::TRANSACTION_LOG_INDEX)),
+                log_type: {
+                    let p = read_u32_at(ptr, log_offsets::LOG_TYPE);
+                    if p == 0 { None } else { Some(asc_to_string(AscPtr::new(p))) }
+                },
+                removed: {
+                    // Wrapped<bool>: a single `inner` byte
+                    let p = read_u32_at(ptr, log_offsets::REMOVED);
+                    if p == 0 { None } else { Some(read_bytes_at(p, 1)[0] != 0) }
+                },
+            }
+        }
+    }
+}
+
 /// Deserialize an Event from an AssemblyScript pointer.
 ///
 /// The params type `P` must implement `FromAscPtr` — this is typically
 /// generated by `yogurt codegen` based on the ABI.
-#[cfg(target_arch = "wasm32")]
 impl<P: FromAscPtr> FromAscPtr for Event<P> {
     fn from_asc_ptr(ptr: u32) -> Self {
         use crate::asc::read_u32_at;
@@ -441,18 +751,10 @@ impl<P: FromAscPtr> FromAscPtr for Event<P> {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl<P: FromAscPtr + Default> FromAscPtr for Event<P> {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        panic!("Event deserialization not available on native target")
-    }
-}
-
 /// Deserialize a Call from an AssemblyScript pointer.
 ///
 /// The input type `I` and output type `O` must implement `FromAscPtr` —
 /// these are typically generated by `yogurt codegen` based on the ABI.
-#[cfg(target_arch = "wasm32")]
 impl<I: FromAscPtr, O: FromAscPtr> FromAscPtr for Call<I, O> {
     fn from_asc_ptr(ptr: u32) -> Self {
         use crate::asc::read_u32_at;
@@ -474,13 +776,6 @@ impl<I: FromAscPtr, O: FromAscPtr> FromAscPtr for Call<I, O> {
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl<I: FromAscPtr, O: FromAscPtr> FromAscPtr for Call<I, O> {
-    fn from_asc_ptr(_ptr: u32) -> Self {
-        panic!("Call deserialization not available on native target")
-    }
-}
-
 // ============================================================================
 // Contract Call Functions
 // ============================================================================
@@ -488,8 +783,17 @@ impl<I: FromAscPtr, O: FromAscPtr> FromAscPtr for Call<I, O> {
 /// Execute an Ethereum contract call.
 ///
 /// Returns `None` if the call reverts.
-#[cfg(target_arch = "wasm32")]
 pub fn call(call_data: SmartContractCall) -> Option<Vec<Token>> {
+    try_call(call_data).ok()
+}
+
+/// Execute an Ethereum contract call, returning the revert data if it
+/// reverts.
+///
+/// graph-node does not pass revert data to the guest, so in WASM it is
+/// always empty.
+#[cfg(target_arch = "wasm32")]
+pub fn try_call(call_data: SmartContractCall) -> Result<Vec<Token>, Bytes> {
     use crate::asc::{str_to_asc, bytes_to_asc, AscArrayHeader};
     use crate::allocator::{asc_alloc, class_id};
 
@@ -523,15 +827,15 @@ pub fn call(call_data: SmartContractCall) -> Option<Vec<Token>> {
     let result_ptr = unsafe { crate::host::ethereum_call(call_ptr as i32) };
 
     if result_ptr == 0 {
-        return None;
+        return Err(Bytes::new());
     }
 
     // Deserialize the result array
-    Some(deserialize_token_array(result_ptr as u32))
+    Ok(deserialize_token_array(result_ptr as u32))
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub fn call(call_data: SmartContractCall) -> Option<Vec<Token>> {
+pub fn try_call(call_data: SmartContractCall) -> Result<Vec<Token>, Bytes> {
     crate::testing::execute_mock_call(&call_data.contract_address, &call_data.function_signature)
 }
 
@@ -646,137 +950,106 @@ fn serialize_token(token: &Token) -> u32 {
     enum_ptr
 }
 
-/// Deserialize an array of Tokens from AS memory.
-#[cfg(target_arch = "wasm32")]
+/// Deserialize an `Array<ethereum.Value>` from AS memory.
 fn deserialize_token_array(ptr: u32) -> Vec<Token> {
-    use crate::asc::AscArrayHeader;
+    use crate::asc::read_u32_at;
 
     if ptr == 0 {
         return Vec::new();
     }
 
     unsafe {
-        let header = ptr as *const AscArrayHeader;
-        let buffer_ptr = (*header).buffer;
-        let length = (*header).length;
+        // Array layout: buffer, buffer_data_start, buffer_data_length, length
+        let data_start = read_u32_at(ptr, 4);
+        let length = read_u32_at(ptr, 12) as i32;
 
-        if buffer_ptr == 0 || length <= 0 {
+        if data_start == 0 || length <= 0 {
             return Vec::new();
         }
 
-        let mut tokens = Vec::with_capacity(length as usize);
-
-        for i in 0..length as usize {
-            let token_ptr_addr = (buffer_ptr as *const u32).add(i);
-            let token_ptr = core::ptr::read_unaligned(token_ptr_addr);
-            tokens.push(deserialize_token(token_ptr));
-        }
-
-        tokens
+        (0..length as usize)
+            .map(|i| deserialize_token(read_u32_at(data_start, i * 4)))
+            .collect()
     }
 }
 
 /// Deserialize a single Token from AS memory.
-#[cfg(target_arch = "wasm32")]
 fn deserialize_token(ptr: u32) -> Token {
-    use crate::asc::{asc_to_bytes, asc_to_string, AscEnumHeader, AscPtr};
+    use crate::asc::{read_i32_at, read_u64_at};
 
     if ptr == 0 {
         return Token::Bool(false); // Default fallback
     }
 
-    unsafe {
-        let header = ptr as *const AscEnumHeader;
-        let kind = (*header).kind;
-        let payload = (*header).payload;
-
-        match kind {
-            0 => {
-                // ADDRESS
-                let bytes = asc_to_bytes(AscPtr::new(payload as u32));
-                Token::Address(Address::from(bytes.as_slice()))
-            }
-            1 => {
-                // FIXED_BYTES
-                let bytes = asc_to_bytes(AscPtr::new(payload as u32));
-                Token::FixedBytes(bytes)
-            }
-            2 => {
-                // BYTES
-                let bytes = asc_to_bytes(AscPtr::new(payload as u32));
-                Token::Bytes(Bytes::from_vec(bytes))
-            }
-            3 => {
-                // INT
-                Token::Int(BigInt::from_ptr(AscPtr::new(payload as u32)))
-            }
-            4 => {
-                // UINT
-                Token::Uint(BigInt::from_ptr(AscPtr::new(payload as u32)))
-            }
-            5 => {
-                // BOOL
-                Token::Bool(payload != 0)
-            }
-            6 => {
-                // STRING
-                let s = asc_to_string(AscPtr::new(payload as u32));
-                Token::String(s)
-            }
-            7 => {
-                // FIXED_ARRAY
-                let arr = deserialize_token_array(payload as u32);
-                Token::FixedArray(arr)
-            }
-            8 => {
-                // ARRAY
-                let arr = deserialize_token_array(payload as u32);
-                Token::Array(arr)
-            }
-            9 => {
-                // TUPLE
-                let arr = deserialize_token_array(payload as u32);
-                Token::Tuple(arr)
-            }
-            _ => Token::Bool(false), // Unknown type
-        }
+    // EthereumValue enum: kind (i32), _padding (u32), payload (u64)
+    let (kind, payload) = unsafe { (read_i32_at(ptr, 0), read_u64_at(ptr, 8)) };
+    let payload_ptr = payload as u32;
+
+    match kind {
+        0 => Token::Address(Address::from_asc_ptr(payload_ptr)),
+        1 => Token::FixedBytes(asc_to_bytes(AscPtr::new(payload_ptr))),
+        2 => Token::Bytes(Bytes::from_asc_ptr(payload_ptr)),
+        3 => Token::Int(BigInt::from_asc_ptr(payload_ptr)),
+        4 => Token::Uint(BigInt::from_asc_ptr(payload_ptr)),
+        5 => Token::Bool(payload != 0),
+        6 => Token::String(asc_to_string(AscPtr::new(payload_ptr))),
+        7 => Token::FixedArray(deserialize_token_array(payload_ptr)),
+        8 => Token::Array(deserialize_token_array(payload_ptr)),
+        9 => Token::Tuple(deserialize_token_array(payload_ptr)),
+        _ => Token::Bool(false), // Unknown type
+    }
+}
+
+/// Deserialize an `ethereum.Value` from an AssemblyScript pointer.
+///
+/// Generated params structs use this for tuple and array parameters, which
+/// are then converted to their Rust types like contract call results.
+impl FromAscPtr for Token {
+    fn from_asc_ptr(ptr: u32) -> Self {
+        deserialize_token(ptr)
     }
 }
 
-/// Encode parameters for a contract call.
+/// ABI-encode values as the arguments of a contract call.
 ///
-/// Note: This is a low-level utility for manual ABI encoding.
-/// For most use cases, use the generated contract bindings which handle
-/// encoding internally via `ethereum::call()`.
+/// The generated contract bindings encode their calls already; this is for
+/// building calldata or hashing encoded values by hand. Returns empty bytes
+/// if a value does not fit its type; use [`try_encode`] to tell that apart.
 ///
-/// Not yet implemented - returns empty bytes.
-#[cfg(target_arch = "wasm32")]
-pub fn encode(_params: &[Token]) -> Bytes {
-    // Low-level ABI encoding - not commonly needed as contract bindings
-    // handle encoding internally. Use generated contract methods instead.
-    Bytes::new()
+/// # Example
+///
+/// ```ignore
+/// let data = ethereum::encode(&[
+///     Token::Address(recipient),
+///     Token::Uint(amount),
+/// ]);
+/// ```
+pub fn encode(params: &[Token]) -> Bytes {
+    try_encode(params).unwrap_or_default()
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn encode(_params: &[Token]) -> Bytes {
-    Bytes::new()
+/// Like [`encode`], but `None` if a value does not fit its type, such as a
+/// negative `Uint` (see [`abi::encode`]).
+pub fn try_encode(params: &[Token]) -> Option<Bytes> {
+    abi::encode(params).map(Bytes::from_vec)
 }
 
-/// Decode return data from a contract call.
+/// ABI-decode `data` according to a Solidity type string.
 ///
-/// Note: This is a low-level utility for manual ABI decoding.
-/// For most use cases, use the generated contract bindings which handle
-/// decoding internally via `ethereum::call()`.
+/// A tuple type such as `"(address,uint256[])"` yields one token per
+/// component; any other type yields a single token. Returns `None` if the
+/// type string is invalid or the data does not match it.
 ///
-/// Not yet implemented - returns None.
-#[cfg(target_arch = "wasm32")]
-pub fn decode(_types: &str, _data: &Bytes) -> Option<Vec<Token>> {
-    // Low-level ABI decoding - not commonly needed as contract bindings
-    // handle decoding internally. Use generated contract methods instead.
-    None
-}
-
-#[cfg(not(target_arch = "wasm32"))]
-pub fn decode(_types: &str, _data: &Bytes) -> Option<Vec<Token>> {
-    None
+/// # Example
+///
+/// ```ignore
+/// // Arguments of `transfer(address,uint256)`, after the 4-byte selector
+/// let input = &event.transaction.input;
+/// let args = ethereum::decode("(address,uint256)", &Bytes::from(&input.as_slice()[4..]));
+/// ```
+pub fn decode(types: &str, data: &Bytes) -> Option<Vec<Token>> {
+    match abi::ParamType::parse(types)? {
+        abi::ParamType::Tuple(types) => abi::decode(&types, data.as_slice()),
+        ty => abi::decode(&[ty], data.as_slice()),
+    }
 }
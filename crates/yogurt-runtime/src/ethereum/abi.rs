@@ -0,0 +1,621 @@
+//! Solidity ABI encoding and decoding over [`Token`].
+//!
+//! Pure Rust, so it behaves the same natively and in `no_std` WASM without a
+//! host round-trip. [`ethereum::encode`](super::encode) and
+//! [`ethereum::decode`](super::decode) are the usual entry points; this module
+//! exposes the parsed [`ParamType`] for callers that decode the same layout
+//! repeatedly.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use yogurt_runtime::ethereum::abi::{self, ParamType};
+//!
+//! // Skip the 4-byte selector of `transfer(address,uint256)` calldata
+//! let types = ParamType::parse("(address,uint256)").unwrap();
+//! let args = abi::decode_value(&types, &tx.input.as_slice()[4..]);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::Token;
+use crate::types::{Address, BigInt, Bytes};
+
+const WORD: usize = 32;
+
+/// A Solidity ABI type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamType {
+    Address,
+    Bool,
+    String,
+    Bytes,
+    /// `bytesN`, with `N` in `1..=32`.
+    FixedBytes(usize),
+    /// `intN`, with the bit width.
+    Int(usize),
+    /// `uintN`, with the bit width.
+    Uint(usize),
+    /// `T[]`
+    Array(Box<ParamType>),
+    /// `T[N]`
+    FixedArray(Box<ParamType>, usize),
+    /// `(T1,T2,...)`
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Parse a Solidity type string such as `uint256`, `bytes32[]` or
+    /// `(address,(uint8,string)[])`.
+    ///
+    /// `uint`/`int` are read as their 256-bit forms, `tuple(...)` is accepted
+    /// as a synonym for `(...)`, and tuple components may carry names
+    /// (`(address from, uint256 value)`). Returns `None` for anything else.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parser = Parser { input: s };
+        let ty = parser.param_type()?;
+        parser.skip_whitespace();
+        parser.input.is_empty().then_some(ty)
+    }
+
+    /// Whether values of this type are encoded out of line (behind an offset).
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::String | ParamType::Bytes | ParamType::Array(_) => true,
+            ParamType::FixedArray(inner, _) => inner.is_dynamic(),
+            ParamType::Tuple(types) => types.iter().any(ParamType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Size of the in-line (head) encoding.
+    fn head_size(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => WORD,
+            ParamType::FixedArray(inner, len) => inner.head_size() * len,
+            ParamType::Tuple(types) => types.iter().map(ParamType::head_size).sum(),
+            _ => WORD,
+        }
+    }
+}
+
+/// The canonical form used in signatures, e.g. `(address,uint256)[]`.
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamType::Address => f.write_str("address"),
+            ParamType::Bool => f.write_str("bool"),
+            ParamType::String => f.write_str("string"),
+            ParamType::Bytes => f.write_str("bytes"),
+            ParamType::FixedBytes(len) => write!(f, "bytes{}", len),
+            ParamType::Int(bits) => write!(f, "int{}", bits),
+            ParamType::Uint(bits) => write!(f, "uint{}", bits),
+            ParamType::Array(inner) => write!(f, "{}[]", inner),
+            ParamType::FixedArray(inner, len) => write!(f, "{}[{}]", inner, len),
+            ParamType::Tuple(types) => {
+                f.write_str("(")?;
+                for (i, ty) in types.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", ty)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.input.strip_prefix(prefix) {
+            Some(rest) => {
+                self.input = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let end = self
+            .input
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(self.input.len());
+        let (ident, rest) = self.input.split_at(end);
+        self.input = rest;
+        ident
+    }
+
+    fn param_type(&mut self) -> Option<ParamType> {
+        self.skip_whitespace();
+        let mut ty = if self.eat("(") || self.eat("tuple(") {
+            self.tuple_components()?
+        } else {
+            elementary(self.identifier())?
+        };
+
+        while self.eat("[") {
+            ty = if self.eat("]") {
+                ParamType::Array(Box::new(ty))
+            } else {
+                let digits = self.identifier();
+                if !self.eat("]") || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let len = digits.parse().ok()?;
+                ParamType::FixedArray(Box::new(ty), len)
+            };
+        }
+        Some(ty)
+    }
+
+    /// Components after the opening parenthesis, through the closing one.
+    fn tuple_components(&mut self) -> Option<ParamType> {
+        let mut types = Vec::new();
+        self.skip_whitespace();
+        if self.eat(")") {
+            return Some(ParamType::Tuple(types));
+        }
+        loop {
+            types.push(self.param_type()?);
+            // Optional component name (and `indexed`, as in event signatures)
+            self.skip_whitespace();
+            while !self.input.is_empty() && !self.input.starts_with([',', ')']) {
+                if self.identifier().is_empty() {
+                    return None;
+                }
+                self.skip_whitespace();
+            }
+            if self.eat(")") {
+                return Some(ParamType::Tuple(types));
+            }
+            if !self.eat(",") {
+                return None;
+            }
+        }
+    }
+}
+
+fn elementary(name: &str) -> Option<ParamType> {
+    let sized = |digits: &str, max: usize, step: usize| -> Option<usize> {
+        let n: usize = digits.parse().ok()?;
+        (n > 0 && n <= max && n.is_multiple_of(step) && !digits.starts_with('0')).then_some(n)
+    };
+
+    Some(match name {
+        "address" => ParamType::Address,
+        "bool" => ParamType::Bool,
+        "string" => ParamType::String,
+        "bytes" => ParamType::Bytes,
+        "uint" => ParamType::Uint(256),
+        "int" => ParamType::Int(256),
+        // A function reference is an address followed by a selector
+        "function" => ParamType::FixedBytes(24),
+        _ => {
+            if let Some(digits) = name.strip_prefix("bytes") {
+                ParamType::FixedBytes(sized(digits, 32, 1)?)
+            } else if let Some(digits) = name.strip_prefix("uint") {
+                ParamType::Uint(sized(digits, 256, 8)?)
+            } else if let Some(digits) = name.strip_prefix("int") {
+                ParamType::Int(sized(digits, 256, 8)?)
+            } else {
+                return None;
+            }
+        }
+    })
+}
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+/// ABI-encode `tokens` as the arguments of a call (a tuple of them).
+///
+/// Returns `None` when a value does not fit its type: a negative `Uint`, an
+/// integer wider than 256 bits, or `FixedBytes` longer than 32 bytes.
+pub fn encode(tokens: &[Token]) -> Option<Vec<u8>> {
+    encode_sequence(tokens)
+}
+
+fn is_dynamic(token: &Token) -> bool {
+    match token {
+        Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+        Token::FixedArray(items) | Token::Tuple(items) => items.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+/// Heads of every token, then the tails of the dynamic ones.
+fn encode_sequence(tokens: &[Token]) -> Option<Vec<u8>> {
+    let encoded: Vec<Vec<u8>> = tokens.iter().map(encode_token).collect::<Option<_>>()?;
+    let heads_len: usize = tokens
+        .iter()
+        .zip(&encoded)
+        .map(|(t, e)| if is_dynamic(t) { WORD } else { e.len() })
+        .sum();
+
+    let mut heads = Vec::with_capacity(heads_len);
+    let mut tails = Vec::new();
+    for (token, enc) in tokens.iter().zip(encoded) {
+        if is_dynamic(token) {
+            heads.extend_from_slice(&usize_word(heads_len + tails.len()));
+            tails.extend(enc);
+        } else {
+            heads.extend(enc);
+        }
+    }
+    heads.extend(tails);
+    Some(heads)
+}
+
+fn encode_token(token: &Token) -> Option<Vec<u8>> {
+    Some(match token {
+        Token::Address(a) => {
+            let mut word = vec![0u8; WORD];
+            word[12..].copy_from_slice(a.as_bytes());
+            word
+        }
+        Token::FixedBytes(b) => {
+            if b.len() > WORD {
+                return None;
+            }
+            let mut word = b.clone();
+            word.resize(WORD, 0);
+            word
+        }
+        Token::Bytes(b) => encode_dynamic_bytes(b.as_slice()),
+        Token::String(s) => encode_dynamic_bytes(s.as_bytes()),
+        Token::Int(n) => int_word(n, false)?.to_vec(),
+        Token::Uint(n) => int_word(n, true)?.to_vec(),
+        Token::Bool(b) => usize_word(*b as usize).to_vec(),
+        Token::Array(items) => {
+            let mut out = usize_word(items.len()).to_vec();
+            out.extend(encode_sequence(items)?);
+            out
+        }
+        Token::FixedArray(items) | Token::Tuple(items) => encode_sequence(items)?,
+    })
+}
+
+fn encode_dynamic_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut out = usize_word(bytes.len()).to_vec();
+    out.extend_from_slice(bytes);
+    out.resize(WORD + bytes.len().next_multiple_of(WORD), 0);
+    out
+}
+
+fn usize_word(n: usize) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    word[WORD - 8..].copy_from_slice(&(n as u64).to_be_bytes());
+    word
+}
+
+/// Big-endian 256-bit two's complement word for an integer token.
+fn int_word(n: &BigInt, unsigned: bool) -> Option<[u8; WORD]> {
+    let mut le = n.to_signed_bytes();
+    let negative = le.last().is_some_and(|b| b & 0x80 != 0);
+    if unsigned && negative {
+        return None;
+    }
+    // Values up to 2^256 - 1 carry a 33rd sign byte in two's complement
+    if unsigned && le.len() == WORD + 1 && le[WORD] == 0 {
+        le.pop();
+    }
+    if le.len() > WORD {
+        return None;
+    }
+
+    let fill = if negative { 0xff } else { 0 };
+    let mut word = [fill; WORD];
+    for (i, byte) in le.iter().enumerate() {
+        word[WORD - 1 - i] = *byte;
+    }
+    Some(word)
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Decode `data` as a tuple of `types`, as returned by a call.
+///
+/// Returns `None` if the data is too short or an offset points outside it.
+pub fn decode(types: &[ParamType], data: &[u8]) -> Option<Vec<Token>> {
+    decode_sequence(types, data, 0)
+}
+
+/// Decode `data` as a single value of type `ty`, like graph-node's
+/// `ethereum.decode`: a tuple type yields a [`Token::Tuple`].
+pub fn decode_value(ty: &ParamType, data: &[u8]) -> Option<Token> {
+    decode_sequence(core::slice::from_ref(ty), data, 0).map(|mut tokens| tokens.remove(0))
+}
+
+/// Decode a sequence whose heads start at `base`. Offsets are relative to `base`.
+fn decode_sequence(types: &[ParamType], data: &[u8], base: usize) -> Option<Vec<Token>> {
+    let mut cursor = base;
+    let mut tokens = Vec::with_capacity(types.len());
+    for ty in types {
+        let token = if ty.is_dynamic() {
+            let offset = read_usize(data, cursor)?;
+            decode_at(ty, data, base.checked_add(offset)?)?
+        } else {
+            decode_at(ty, data, cursor)?
+        };
+        tokens.push(token);
+        cursor = cursor.checked_add(ty.head_size())?;
+    }
+    Some(tokens)
+}
+
+fn decode_at(ty: &ParamType, data: &[u8], pos: usize) -> Option<Token> {
+    Some(match ty {
+        ParamType::Address => Token::Address(Address::from(&word(data, pos)?[12..])),
+        ParamType::Bool => Token::Bool(word(data, pos)?.iter().any(|b| *b != 0)),
+        ParamType::FixedBytes(len) => Token::FixedBytes(word(data, pos)?[..*len].to_vec()),
+        ParamType::Uint(_) => {
+            let le: Vec<u8> = word(data, pos)?.iter().rev().copied().collect();
+            Token::Uint(BigInt::from_unsigned_bytes(&le))
+        }
+        ParamType::Int(_) => {
+            let le: Vec<u8> = word(data, pos)?.iter().rev().copied().collect();
+            Token::Int(BigInt::from_signed_bytes(&le))
+        }
+        ParamType::Bytes => Token::Bytes(Bytes::from(dynamic_bytes(data, pos)?)),
+        ParamType::String => {
+            Token::String(String::from_utf8_lossy(dynamic_bytes(data, pos)?).into_owned())
+        }
+        ParamType::Array(inner) => {
+            let len = read_usize(data, pos)?;
+            let start = pos.checked_add(WORD)?;
+            // Every element takes at least one word, so bound `len` before allocating
+            if len > data.len().saturating_sub(start) / WORD {
+                return None;
+            }
+            Token::Array(decode_sequence(&vec![(**inner).clone(); len], data, start)?)
+        }
+        ParamType::FixedArray(inner, len) => {
+            if *len > data.len() / WORD + 1 {
+                return None;
+            }
+            Token::FixedArray(decode_sequence(&vec![(**inner).clone(); *len], data, pos)?)
+        }
+        ParamType::Tuple(types) => Token::Tuple(decode_sequence(types, data, pos)?),
+    })
+}
+
+fn word(data: &[u8], pos: usize) -> Option<&[u8]> {
+    data.get(pos..pos.checked_add(WORD)?)
+}
+
+fn read_usize(data: &[u8], pos: usize) -> Option<usize> {
+    let word = word(data, pos)?;
+    if word[..WORD - 4].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(u32::from_be_bytes(word[WORD - 4..].try_into().ok()?) as usize)
+}
+
+fn dynamic_bytes(data: &[u8], pos: usize) -> Option<&[u8]> {
+    let len = read_usize(data, pos)?;
+    let start = pos.checked_add(WORD)?;
+    data.get(start..start.checked_add(len)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        let s: String = s.split_whitespace().collect();
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn uint(n: u64) -> Token {
+        Token::Uint(BigInt::from_u64(n))
+    }
+
+    fn round_trip(ty: &str, tokens: Vec<Token>) {
+        let ParamType::Tuple(types) = ParamType::parse(ty).unwrap() else {
+            panic!("expected a tuple type");
+        };
+        let encoded = encode(&tokens).unwrap();
+        assert_eq!(decode(&types, &encoded).unwrap(), tokens, "{}", ty);
+    }
+
+    #[test]
+    fn test_parse_type_strings() {
+        for canonical in [
+            "address",
+            "uint8",
+            "int256",
+            "bytes32",
+            "bytes",
+            "string[]",
+            "uint256[3][]",
+            "(address,uint256[])",
+            "((bool,bytes4)[2],string)",
+            "()",
+        ] {
+            let ty = ParamType::parse(canonical).unwrap();
+            assert_eq!(ty.to_string(), canonical);
+        }
+
+        assert_eq!(ParamType::parse("uint"), Some(ParamType::Uint(256)));
+        assert_eq!(
+            ParamType::parse(" tuple( address from , uint value ) ")
+                .unwrap()
+                .to_string(),
+            "(address,uint256)"
+        );
+        for invalid in [
+            "uint7",
+            "uint264",
+            "bytes33",
+            "bytes0",
+            "int08",
+            "foo",
+            "(address",
+            "uint256[x]",
+            "address,",
+        ] {
+            assert_eq!(ParamType::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_encode_static_arguments() {
+        // transfer(address,uint256) arguments
+        let encoded = encode(&[Token::Address(Address::from([0x11; 20])), uint(1000)]).unwrap();
+        assert_eq!(
+            encoded,
+            hex(
+                "0000000000000000000000001111111111111111111111111111111111111111
+                 00000000000000000000000000000000000000000000000000000000000003e8"
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_dynamic_arguments() {
+        // The example from the Solidity ABI spec: f(uint256,uint32[],bytes10,bytes)
+        let encoded = encode(&[
+            uint(0x123),
+            Token::Array(vec![uint(0x456), uint(0x789)]),
+            Token::FixedBytes(b"1234567890".to_vec()),
+            Token::Bytes(Bytes::from(&b"Hello, world!"[..])),
+        ])
+        .unwrap();
+        assert_eq!(
+            encoded,
+            hex(
+                "0000000000000000000000000000000000000000000000000000000000000123
+                 0000000000000000000000000000000000000000000000000000000000000080
+                 3132333435363738393000000000000000000000000000000000000000000000
+                 00000000000000000000000000000000000000000000000000000000000000e0
+                 0000000000000000000000000000000000000000000000000000000000000002
+                 0000000000000000000000000000000000000000000000000000000000000456
+                 0000000000000000000000000000000000000000000000000000000000000789
+                 000000000000000000000000000000000000000000000000000000000000000d
+                 48656c6c6f2c20776f726c642100000000000000000000000000000000000000"
+            )
+        );
+    }
+
+    #[test]
+    fn test_ethereum_encode_returns_empty_bytes_on_failure() {
+        let negative = [Token::Uint(BigInt::from_i64(-1))];
+        assert_eq!(crate::ethereum::try_encode(&negative), None);
+        assert!(crate::ethereum::encode(&negative).is_empty());
+        assert_eq!(
+            crate::ethereum::encode(&[uint(5)]),
+            crate::ethereum::try_encode(&[uint(5)]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_integer_bounds() {
+        let max = BigInt::from_string(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        )
+        .unwrap();
+        assert_eq!(encode(&[Token::Uint(max.clone())]).unwrap(), vec![0xff; 32]);
+        assert_eq!(encode(&[Token::Uint(max.plus(&BigInt::from_u64(1)))]), None);
+        assert_eq!(encode(&[Token::Uint(BigInt::from_i64(-1))]), None);
+        assert_eq!(
+            encode(&[Token::Int(BigInt::from_i64(-1))]).unwrap(),
+            vec![0xff; 32]
+        );
+        assert_eq!(encode(&[Token::FixedBytes(vec![0; 33])]), None);
+
+        let types = [ParamType::Uint(256), ParamType::Int(256)];
+        let decoded = decode(&types, &[vec![0xff; 32], vec![0xff; 32]].concat()).unwrap();
+        assert_eq!(
+            decoded,
+            [Token::Uint(max), Token::Int(BigInt::from_i64(-1))]
+        );
+    }
+
+    #[test]
+    fn test_round_trips() {
+        round_trip(
+            "(address,uint256[])",
+            vec![
+                Token::Address(Address::from([0xab; 20])),
+                Token::Array(vec![uint(1), uint(2), uint(3)]),
+            ],
+        );
+        round_trip(
+            "(string,(bool,bytes)[],int8[2],bytes3)",
+            vec![
+                Token::String("yogurt".into()),
+                Token::Array(vec![
+                    Token::Tuple(vec![
+                        Token::Bool(true),
+                        Token::Bytes(Bytes::from(&[1u8, 2][..])),
+                    ]),
+                    Token::Tuple(vec![Token::Bool(false), Token::Bytes(Bytes::new())]),
+                ]),
+                Token::FixedArray(vec![
+                    Token::Int(BigInt::from_i64(-128)),
+                    Token::Int(BigInt::from_i64(127)),
+                ]),
+                Token::FixedBytes(vec![0xaa, 0xbb, 0xcc]),
+            ],
+        );
+        round_trip(
+            "(string[2],(uint256,string))",
+            vec![
+                Token::FixedArray(vec![Token::String("a".into()), Token::String("b".into())]),
+                Token::Tuple(vec![uint(7), Token::String("c".into())]),
+            ],
+        );
+        round_trip("()", vec![]);
+    }
+
+    #[test]
+    fn test_decode_value_keeps_tuples() {
+        let ty = ParamType::parse("(uint8)").unwrap();
+        let encoded = encode(&[uint(5)]).unwrap();
+        assert_eq!(
+            decode_value(&ty, &encoded),
+            Some(Token::Tuple(vec![uint(5)]))
+        );
+    }
+
+    #[test]
+    fn test_malformed_data_is_rejected() {
+        let types = [ParamType::Bytes];
+        // Truncated
+        assert_eq!(decode(&[ParamType::Uint(256)], &[0; 31]), None);
+        // Offset past the end
+        assert_eq!(decode(&types, &usize_word(64)), None);
+        // Length past the end
+        assert_eq!(
+            decode(&types, &[usize_word(32), usize_word(100)].concat()),
+            None
+        );
+        // Absurd array length must not allocate
+        let array = [ParamType::Array(Box::new(ParamType::Uint(256)))];
+        assert_eq!(decode(&array, &[usize_word(32), [0xff; 32]].concat()), None);
+        assert_eq!(
+            decode(&array, &[usize_word(32), usize_word(1 << 30)].concat()),
+            None
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Errors returned by fallible handlers.
+//!
+//! A `#[handler]` function may return `Result<(), HandlerError>`. The
+//! generated export turns an `Err` into a critical log and a trap, which
+//! fails the handler in graph-node. Whether that stops indexing is up to
+//! graph-node: with `features: [nonFatalErrors]` in the manifest it discards
+//! the handler's store writes and moves on to the next trigger.
+//!
+//! ```ignore
+//! #[handler]
+//! fn handle_transfer(event: TransferEvent) -> Result<(), HandlerError> {
+//!     let mut token = Token::try_load(&event.address.to_hex())?;
+//!     let supply = ERC20::bind(event.address)
+//!         .try_total_supply()
+//!         .context("totalSupply reverted")?;
+//!     token.set_total_supply(supply);
+//!     token.save();
+//!     Ok(())
+//! }
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::ethereum::CallError;
+
+/// An error that aborts a handler, with the context it was raised in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandlerError {
+    message: String,
+    /// Context added while the error propagated, innermost first.
+    context: Vec<String>,
+}
+
+impl HandlerError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            context: Vec::new(),
+        }
+    }
+
+    /// An entity the handler expected to exist was not in the store.
+    pub fn entity_not_found(entity_type: &str, id: &str) -> Self {
+        Self::new(format!("{} `{}` not found", entity_type, id))
+    }
+
+    /// Wrap the error in a description of what was being attempted.
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context.push(context.into());
+        self
+    }
+
+    /// The underlying error, without context.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in self.context.iter().rev() {
+            write!(f, "{}: ", context)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+/// Error messages, such as those from `json::try_from_bytes`.
+impl From<String> for HandlerError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for HandlerError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+/// A reverted `try_*` contract call.
+impl<E: fmt::Debug> From<CallError<E>> for HandlerError {
+    fn from(error: CallError<E>) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// Attach context to an `Option` or `Result`, producing a [`HandlerError`].
+///
+/// On an `Option`, `None` becomes an error whose message is the context.
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, HandlerError>;
+
+    /// Like [`Context::context`], building the context only on failure.
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T, HandlerError>;
+}
+
+impl<T> Context<T> for Option<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, HandlerError> {
+        self.ok_or_else(|| HandlerError::new(context))
+    }
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T, HandlerError> {
+        self.ok_or_else(|| HandlerError::new(context()))
+    }
+}
+
+impl<T, E: Into<HandlerError>> Context<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, HandlerError> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T, HandlerError> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
+
+/// What a handler returns: nothing, or a `Result` whose error converts into
+/// and from a [`HandlerError`].
+///
+/// Lets native test drivers such as `testing::Chain` run infallible and
+/// fallible handlers alike.
+pub trait HandlerOutput {
+    fn into_result(self) -> Result<(), HandlerError>;
+
+    /// The output of a handler that ran in the WASM module under
+    /// `yogurt test --wasm`. An infallible handler cannot return the error,
+    /// so it panics with it.
+    fn from_result(result: Result<(), HandlerError>) -> Self;
+}
+
+impl HandlerOutput for () {
+    fn into_result(self) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    fn from_result(result: Result<(), HandlerError>) -> Self {
+        if let Err(e) = result {
+            panic!("{}", e);
+        }
+    }
+}
+
+impl<E: Into<HandlerError> + From<HandlerError>> HandlerOutput for Result<(), E> {
+    fn into_result(self) -> Result<(), HandlerError> {
+        self.map_err(Into::into)
+    }
+
+    fn from_result(result: Result<(), HandlerError>) -> Self {
+        result.map_err(E::from)
+    }
+}
+
+/// Fail the handler if a fallible handler returned an error.
+///
+/// Used by `#[handler]` wrappers. The handler always fails, so none of its
+/// store writes are committed; graph-node decides from the manifest's
+/// `nonFatalErrors` feature whether indexing continues. Natively, it panics
+/// with the critical log's message.
+#[doc(hidden)]
+pub fn finish<E: Into<HandlerError>>(handler: &str, result: Result<(), E>) {
+    if let Err(e) = result {
+        let message = format!("{} failed: {}", handler, e.into());
+        // graph-node fails the handler on a critical log
+        crate::log::critical(&message);
+        #[cfg(target_arch = "wasm32")]
+        core::arch::wasm32::unreachable();
+        #[cfg(not(target_arch = "wasm32"))]
+        panic!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::ContractError;
+    use crate::types::Bytes;
+
+    #[test]
+    fn test_handler_error_context() {
+        let err = HandlerError::entity_not_found("Token", "0xabc")
+            .context("loading token")
+            .context("handleTransfer");
+        assert_eq!(err.message(), "Token `0xabc` not found");
+        assert_eq!(
+            err.to_string(),
+            "handleTransfer: loading token: Token `0xabc` not found"
+        );
+
+        let call: Option<u32> = None;
+        assert_eq!(
+            call.context("balanceOf reverted").unwrap_err().to_string(),
+            "balanceOf reverted"
+        );
+        assert_eq!(Some(7).context("unused"), Ok(7));
+
+        let json = crate::json::try_from_bytes(&Bytes::from(&b"{"[..]))
+            .with_context(|| format!("metadata {}", 1))
+            .unwrap_err();
+        assert!(json.to_string().starts_with("metadata 1: JSON parse error"));
+    }
+
+    #[test]
+    fn test_finish_passes_ok() {
+        finish("handleTransfer", Ok::<(), HandlerError>(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "handleTransfer failed: loading token: Token `0xabc` not found")]
+    fn test_finish_fails_handler_on_err() {
+        let err = HandlerError::entity_not_found("Token", "0xabc").context("loading token");
+        finish("handleTransfer", Err::<(), _>(err));
+    }
+
+    /// Stands in for a generated `<Contract>Error` enum.
+    #[derive(Debug, PartialEq)]
+    enum TokenError {
+        Paused,
+    }
+
+    impl ContractError for TokenError {
+        fn decode(data: &[u8]) -> Option<Self> {
+            (data == [0x9e, 0x87, 0xfa, 0xc8]).then_some(TokenError::Paused)
+        }
+
+        fn encode(&self) -> Vec<u8> {
+            alloc::vec![0x9e, 0x87, 0xfa, 0xc8]
+        }
+    }
+
+    #[test]
+    fn test_call_error_from_revert_data() {
+        let reason = crate::ethereum::encode_revert_reason("ERC20: paused");
+        let revert = CallError::<TokenError>::from_revert_data(&reason);
+        assert_eq!(revert, CallError::Revert("ERC20: paused".into()));
+        assert_eq!(
+            CallError::<TokenError>::from_revert_data(&TokenError::Paused.encode()),
+            CallError::Custom(TokenError::Paused)
+        );
+        assert_eq!(
+            CallError::<TokenError>::from_revert_data(&[]),
+            CallError::Unknown(Bytes::new())
+        );
+
+        let result: Result<(), CallError<TokenError>> = Err(revert);
+        assert_eq!(
+            result.context("totalSupply").unwrap_err().to_string(),
+            "totalSupply: Call reverted: ERC20: paused"
+        );
+        let custom: Result<(), _> = Err(CallError::Custom(TokenError::Paused));
+        assert_eq!(
+            custom.context("totalSupply").unwrap_err().to_string(),
+            "totalSupply: Call reverted with Paused"
+        );
+    }
+}
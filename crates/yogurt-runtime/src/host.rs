@@ -21,6 +21,9 @@ mod imports {
         #[link_name = "store.get_in_block"]
         pub fn store_get_in_block(entity_type: i32, id: i32) -> i32;
 
+        #[link_name = "store.loadRelated"]
+        pub fn store_load_related(entity_type: i32, id: i32, field: i32) -> i32;
+
         // Ethereum
         #[link_name = "ethereum.call"]
         pub fn ethereum_call(call: i32) -> i32;
@@ -157,6 +160,27 @@ mod imports {
         #[link_name = "ens.nameByHash"]
         pub fn ens_name_by_hash(hash: i32) -> i32;
     }
+
+    // Store operations under the Rust ABI. Arguments and results are
+    // `rust_abi` messages; a result of 0 means "none".
+    #[cfg(feature = "rust-abi")]
+    #[link(wasm_import_module = "env")]
+    unsafe extern "C" {
+        #[link_name = "yogurt.store.get"]
+        pub fn yogurt_store_get(request: i32) -> i32;
+
+        #[link_name = "yogurt.store.set"]
+        pub fn yogurt_store_set(request: i32);
+
+        #[link_name = "yogurt.store.remove"]
+        pub fn yogurt_store_remove(request: i32);
+
+        #[link_name = "yogurt.store.get_in_block"]
+        pub fn yogurt_store_get_in_block(request: i32) -> i32;
+
+        #[link_name = "yogurt.store.load_related"]
+        pub fn yogurt_store_load_related(request: i32) -> i32;
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
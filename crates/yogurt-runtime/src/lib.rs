@@ -8,6 +8,10 @@
 
 extern crate alloc;
 
+// `#[handler]` expands to `yogurt_runtime::` paths, also in this crate's tests
+#[cfg(test)]
+extern crate self as yogurt_runtime;
+
 pub mod allocator;
 pub mod asc;
 mod host;
@@ -18,15 +22,25 @@ pub mod crypto;
 pub mod data_source;
 pub mod ens;
 pub mod ethereum;
+pub mod handler;
 pub mod ipfs;
 pub mod json;
 pub mod log;
+pub mod rust_abi;
 pub mod store;
 
 // Testing module is always available on native builds
 #[cfg(not(target_arch = "wasm32"))]
 pub mod testing;
 
+// AssemblyScript heap images for native `FromAscPtr` tests
+#[cfg(not(target_arch = "wasm32"))]
+pub mod asc_image;
+
+// WASM host emulator for `yogurt test --wasm` (native only)
+#[cfg(all(not(target_arch = "wasm32"), feature = "wasm-host"))]
+pub mod wasm_host;
+
 pub use types::*;
 
 /// Format a BigInt as a decimal string with the given number of decimal places.
@@ -116,6 +130,79 @@ macro_rules! block_id {
     };
 }
 
+/// Generate a `Bytes` entity ID from an event's transaction hash and log index.
+///
+/// The `Bytes` counterpart of [`log_id!`] for entities declared with
+/// `id: Bytes!`, which graph-node stores and indexes more cheaply than
+/// strings: the transaction hash followed by the log index as 4 bytes.
+///
+/// # Example
+///
+/// ```ignore
+/// use yogurt_runtime::log_bytes_id;
+///
+/// #[handler]
+/// fn handle_transfer(event: TransferEvent) {
+///     let id = log_bytes_id!(event);  // 0xabc...0000002a
+///     let transfer = Transfer::new(id);
+/// }
+/// ```
+#[macro_export]
+macro_rules! log_bytes_id {
+    ($event:expr) => {
+        $event.transaction.hash.concat_i32(
+            $event
+                .log_index
+                .to_i32()
+                .expect("log index out of i32 range"),
+        )
+    };
+}
+
+/// Generate a `Bytes` entity ID from a call's transaction hash.
+///
+/// The `Bytes` counterpart of [`call_id!`].
+///
+/// # Example
+///
+/// ```ignore
+/// use yogurt_runtime::call_bytes_id;
+///
+/// #[handler]
+/// fn handle_transfer_call(call: TransferCall) {
+///     let id = call_bytes_id!(call);  // 0xabc...
+///     let transfer = Transfer::new(id);
+/// }
+/// ```
+#[macro_export]
+macro_rules! call_bytes_id {
+    ($call:expr) => {
+        $call.transaction.hash.clone()
+    };
+}
+
+/// Generate a `Bytes` entity ID from a block's hash.
+///
+/// The `Bytes` counterpart of [`block_id!`], for block entities keyed by hash.
+///
+/// # Example
+///
+/// ```ignore
+/// use yogurt_runtime::block_bytes_id;
+///
+/// #[handler]
+/// fn handle_block(block: Block) {
+///     let id = block_bytes_id!(block);  // 0xdef...
+///     let block_entity = BlockEntity::new(id);
+/// }
+/// ```
+#[macro_export]
+macro_rules! block_bytes_id {
+    ($block:expr) => {
+        $block.hash.clone()
+    };
+}
+
 /// Generate a day-based entity ID from an event's block timestamp.
 ///
 /// Useful for creating daily aggregation entities (e.g., DailyVolume, DailyStats).
@@ -172,6 +259,26 @@ macro_rules! hour_id {
     }};
 }
 
+/// Decode a handler argument passed by graph-node.
+///
+/// Used by `#[handler]` wrappers so that the wire format follows the
+/// runtime's `rust-abi` feature rather than the mapping crate's features.
+#[doc(hidden)]
+#[cfg(not(feature = "rust-abi"))]
+pub fn handler_arg<T: asc::FromAscPtr>(ptr: u32) -> T {
+    T::from_asc_ptr(ptr)
+}
+
+/// Decode a handler argument passed by graph-node.
+///
+/// Used by `#[handler]` wrappers so that the wire format follows the
+/// runtime's `rust-abi` feature rather than the mapping crate's features.
+#[doc(hidden)]
+#[cfg(feature = "rust-abi")]
+pub fn handler_arg<T: rust_abi::Decode>(ptr: u32) -> T {
+    rust_abi::read_message(ptr)
+}
+
 /// The standard prelude for yogurt subgraph mappings.
 ///
 /// ```rust,ignore
@@ -179,12 +286,18 @@ macro_rules! hour_id {
 /// ```
 pub mod prelude {
     pub use crate::asc::FromAscPtr;
-    pub use crate::ethereum::{Block, Call, Event, Transaction, TransactionReceipt};
-    pub use crate::types::{Address, BigDecimal, BigInt, Bytes, Entity, Value};
+    pub use crate::ethereum::{
+        Block, BlockFilter, Call, CallError, Event, FromLog, Log, TopicFilter, Transaction,
+        TransactionReceipt,
+    };
+    pub use crate::handler::{Context, HandlerError};
+    pub use crate::types::{
+        Address, BigDecimal, BigInt, Bytes, Entity, EntityId, EntityKey, Timestamp, Value,
+    };
     pub use crate::{data_source, log};
 
     // Re-export ID generation macros
-    pub use crate::{block_id, call_id, day_id, hour_id, log_id};
+    pub use crate::{block_bytes_id, block_id, call_bytes_id, call_id, day_id, hour_id, log_bytes_id, log_id};
 
     // Re-export token formatting utilities
     pub use crate::format_units;
@@ -198,16 +311,47 @@ pub mod prelude {
 // WASM-specific panic handler and exports
 #[cfg(all(target_arch = "wasm32", not(feature = "std")))]
 mod wasm {
+    use alloc::format;
+    use alloc::string::String;
     use core::panic::PanicInfo;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    /// Set once the panic handler starts, so a panic while reporting traps directly.
+    static PANICKING: AtomicBool = AtomicBool::new(false);
 
+    /// Report the panic through a critical log, which makes graph-node fail
+    /// the handler with the message instead of an opaque `unreachable` trap.
     #[panic_handler]
-    fn panic(_info: &PanicInfo) -> ! {
+    fn panic(info: &PanicInfo) -> ! {
+        if !PANICKING.swap(true, Ordering::Relaxed) {
+            let message = match info.location() {
+                Some(location) => format!(
+                    "{} at {}:{}:{}",
+                    info.message(),
+                    location.file(),
+                    location.line(),
+                    location.column()
+                ),
+                None => format!("{}", info.message()),
+            };
+            crate::log::critical(&message);
+        }
         core::arch::wasm32::unreachable()
     }
 
     // AssemblyScript runtime exports required by graph-node
     #[unsafe(no_mangle)]
-    pub extern "C" fn abort(_msg: u32, _file: u32, _line: u32, _col: u32) -> ! {
+    pub extern "C" fn abort(msg: u32, file: u32, line: u32, col: u32) -> ! {
+        // Arguments are AssemblyScript string pointers, either of which may be null
+        let read = |ptr: u32| {
+            if ptr == 0 {
+                String::from("(null)")
+            } else {
+                crate::asc::asc_to_string(crate::asc::AscPtr::new(ptr))
+            }
+        };
+        let message = format!("{} at {}:{}:{}", read(msg), read(file), line, col);
+        crate::log::critical(&message);
         core::arch::wasm32::unreachable()
     }
 
@@ -0,0 +1,918 @@
+//! Compact binary ABI for Rust subgraphs.
+//!
+//! The default ABI emulates AssemblyScript: graph-node hands handlers managed
+//! AS objects and yogurt reproduces their layouts. This module defines a
+//! simpler alternative meant to be proposed to graph-node: every value
+//! crossing the host boundary is a length-prefixed message in the encoding
+//! below, and there is no object model to emulate.
+//!
+//! The mode is enabled with the `rust-abi` cargo feature, which `yogurt build`
+//! turns on for manifests declaring `language: wasm/rust`. The codec itself is
+//! always compiled so generated code and the test host can use it either way.
+//! The full specification lives in `docs/src/reference/rust-abi.md`.
+//!
+//! # Encoding
+//!
+//! All integers are little-endian.
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | `bool`, tags | 1 byte |
+//! | `i32`, `u32` | 4 bytes |
+//! | `i64` | 8 bytes |
+//! | `Vec<T>` | `u32` count, then each item |
+//! | `Bytes`, `Address` | `u32` length, then the bytes |
+//! | `String` | `u32` length, then UTF-8 bytes |
+//! | `BigInt` | `Bytes` of the two's complement little-endian value |
+//! | `BigDecimal` | `String` of the decimal value |
+//! | `Option<T>` | tag `0` (none) or `1` followed by `T` |
+//! | `(A, B, ..)` | each field in order |
+//!
+//! A message is a `u32` payload length followed by the payload.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ethereum::{
+    Block, Call, Event, EventParam, Log, ToEventParams, Token, Transaction, TransactionReceipt,
+};
+use crate::types::{Address, BigDecimal, BigInt, Bytes, EntityData, Timestamp, Value};
+
+/// ABI version reported by the `yogurt_abi_version` export.
+pub const ABI_VERSION: u32 = 1;
+
+/// A malformed message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError(&'static str);
+
+impl DecodeError {
+    const EOF: Self = Self("unexpected end of input");
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Types that can be written in the Rust ABI encoding.
+pub trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// Types that can be read from the Rust ABI encoding.
+///
+/// `input` is advanced past the bytes consumed.
+pub trait Decode: Sized {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// Encode a value as a message payload.
+pub fn to_bytes<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+/// Decode a message payload, rejecting trailing bytes.
+pub fn from_bytes<T: Decode>(mut input: &[u8]) -> Result<T, DecodeError> {
+    let value = T::decode(&mut input)?;
+    if !input.is_empty() {
+        return Err(DecodeError("trailing bytes after message"));
+    }
+    Ok(value)
+}
+
+/// Read and decode the message at `ptr` in linear memory.
+///
+/// Panics on a malformed message, which aborts the handler like any other
+/// trap would.
+pub fn read_message<T: Decode>(ptr: u32) -> T {
+    let payload = unsafe {
+        let len = crate::asc::read_u32_at(ptr, 0);
+        crate::asc::read_bytes_at(ptr + 4, len as usize)
+    };
+    match from_bytes(&payload) {
+        Ok(value) => value,
+        Err(e) => panic!("malformed Rust ABI message: {}", e),
+    }
+}
+
+/// Decode the named parameters of an event or call, returning the values
+/// in declaration order. Used by generated `Decode` impls.
+pub fn decode_event_params(input: &mut &[u8]) -> Result<Vec<Token>, DecodeError> {
+    let params = Vec::<EventParam>::decode(input)?;
+    Ok(params.into_iter().map(|p| p.value).collect())
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < len {
+        return Err(DecodeError::EOF);
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+fn take_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], DecodeError> {
+    let mut array = [0u8; N];
+    array.copy_from_slice(take(input, N)?);
+    Ok(array)
+}
+
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+    (len as u32).encode(out);
+}
+
+fn encode_slice(bytes: &[u8], out: &mut Vec<u8>) {
+    encode_len(bytes.len(), out);
+    out.extend_from_slice(bytes);
+}
+
+fn decode_slice<'a>(input: &mut &'a [u8]) -> Result<&'a [u8], DecodeError> {
+    let len = u32::decode(input)? as usize;
+    take(input, len)
+}
+
+// ============================================================================
+// Primitives
+// ============================================================================
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError("invalid bool")),
+        }
+    }
+}
+
+macro_rules! impl_int {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                Ok(<$ty>::from_le_bytes(take_array(input)?))
+            }
+        }
+    )*};
+}
+
+impl_int!(u8, u32, i32, i64);
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),*) => {
+        /// Tuples are their fields in order, with no framing.
+        impl<$($name: Encode),*> Encode for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)*) = self;
+                $($name.encode(out);)*
+            }
+        }
+
+        impl<$($name: Decode),*> Decode for ($($name,)*) {
+            fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                Ok(($($name::decode(input)?,)*))
+            }
+        }
+    };
+}
+
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let count = u32::decode(input)? as usize;
+        // Every item takes at least one byte, so a count past the end is malformed
+        if count > input.len() {
+            return Err(DecodeError::EOF);
+        }
+        (0..count).map(|_| T::decode(input)).collect()
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match u8::decode(input)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(input)?)),
+            _ => Err(DecodeError("invalid option tag")),
+        }
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_slice(self.as_bytes(), out);
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().encode(out);
+    }
+}
+
+impl Decode for String {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let bytes = decode_slice(input)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| DecodeError("invalid UTF-8 in string"))
+    }
+}
+
+// ============================================================================
+// Graph types
+// ============================================================================
+
+impl Encode for Bytes {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_slice(self.as_slice(), out);
+    }
+}
+
+impl Decode for Bytes {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Bytes::from(decode_slice(input)?))
+    }
+}
+
+impl Encode for Address {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_slice(self.as_bytes(), out);
+    }
+}
+
+impl Decode for Address {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let bytes = decode_slice(input)?;
+        if bytes.len() != 20 {
+            return Err(DecodeError("address must be 20 bytes"));
+        }
+        Ok(Address::from(bytes))
+    }
+}
+
+impl Encode for BigInt {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_slice(&self.to_signed_bytes(), out);
+    }
+}
+
+impl Decode for BigInt {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(BigInt::from_signed_bytes(decode_slice(input)?))
+    }
+}
+
+impl Encode for BigDecimal {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.to_string().encode(out);
+    }
+}
+
+impl Decode for BigDecimal {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(BigDecimal::from_string(&String::decode(input)?))
+    }
+}
+
+impl Encode for Value {
+    fn encode(&self, out: &mut Vec<u8>) {
+        // Tags follow `asc::StoreValueKind`
+        match self {
+            Value::String(s) => {
+                out.push(0);
+                s.encode(out);
+            }
+            Value::Int(i) => {
+                out.push(1);
+                i.encode(out);
+            }
+            Value::BigDecimal(d) => {
+                out.push(2);
+                d.encode(out);
+            }
+            Value::Bool(b) => {
+                out.push(3);
+                b.encode(out);
+            }
+            Value::Array(items) => {
+                out.push(4);
+                items.encode(out);
+            }
+            Value::Null => out.push(5),
+            Value::Bytes(b) => {
+                out.push(6);
+                b.encode(out);
+            }
+            Value::BigInt(n) => {
+                out.push(7);
+                n.encode(out);
+            }
+            Value::Int8(i) => {
+                out.push(8);
+                i.encode(out);
+            }
+            Value::Timestamp(ts) => {
+                out.push(9);
+                ts.as_micros().encode(out);
+            }
+        }
+    }
+}
+
+impl Decode for Value {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(match u8::decode(input)? {
+            0 => Value::String(String::decode(input)?),
+            1 => Value::Int(i32::decode(input)?),
+            2 => Value::BigDecimal(BigDecimal::decode(input)?),
+            3 => Value::Bool(bool::decode(input)?),
+            4 => Value::Array(Vec::decode(input)?),
+            5 => Value::Null,
+            6 => Value::Bytes(Bytes::decode(input)?),
+            7 => Value::BigInt(BigInt::decode(input)?),
+            8 => Value::Int8(i64::decode(input)?),
+            9 => Value::Timestamp(Timestamp::from_micros(i64::decode(input)?)),
+            _ => return Err(DecodeError("invalid store value kind")),
+        })
+    }
+}
+
+impl Encode for EntityData {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.iter().count(), out);
+        for (key, value) in self.iter() {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+}
+
+impl Decode for EntityData {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let count = u32::decode(input)?;
+        let mut data = EntityData::new();
+        for _ in 0..count {
+            let key = String::decode(input)?;
+            data.set(key, Value::decode(input)?);
+        }
+        Ok(data)
+    }
+}
+
+// ============================================================================
+// Ethereum types
+// ============================================================================
+
+impl Encode for Token {
+    fn encode(&self, out: &mut Vec<u8>) {
+        // Tags follow graph-ts's EthereumValueKind
+        match self {
+            Token::Address(a) => {
+                out.push(0);
+                a.encode(out);
+            }
+            Token::FixedBytes(b) => {
+                out.push(1);
+                encode_slice(b, out);
+            }
+            Token::Bytes(b) => {
+                out.push(2);
+                b.encode(out);
+            }
+            Token::Int(n) => {
+                out.push(3);
+                n.encode(out);
+            }
+            Token::Uint(n) => {
+                out.push(4);
+                n.encode(out);
+            }
+            Token::Bool(b) => {
+                out.push(5);
+                b.encode(out);
+            }
+            Token::String(s) => {
+                out.push(6);
+                s.encode(out);
+            }
+            Token::FixedArray(items) => {
+                out.push(7);
+                items.encode(out);
+            }
+            Token::Array(items) => {
+                out.push(8);
+                items.encode(out);
+            }
+            Token::Tuple(items) => {
+                out.push(9);
+                items.encode(out);
+            }
+        }
+    }
+}
+
+impl Decode for Token {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(match u8::decode(input)? {
+            0 => Token::Address(Address::decode(input)?),
+            1 => Token::FixedBytes(decode_slice(input)?.to_vec()),
+            2 => Token::Bytes(Bytes::decode(input)?),
+            3 => Token::Int(BigInt::decode(input)?),
+            4 => Token::Uint(BigInt::decode(input)?),
+            5 => Token::Bool(bool::decode(input)?),
+            6 => Token::String(String::decode(input)?),
+            7 => Token::FixedArray(Vec::decode(input)?),
+            8 => Token::Array(Vec::decode(input)?),
+            9 => Token::Tuple(Vec::decode(input)?),
+            _ => return Err(DecodeError("invalid ethereum value kind")),
+        })
+    }
+}
+
+impl Encode for EventParam {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.name.encode(out);
+        self.value.encode(out);
+    }
+}
+
+impl Decode for EventParam {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(EventParam {
+            name: String::decode(input)?,
+            value: Token::decode(input)?,
+        })
+    }
+}
+
+impl Encode for Block {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.hash.encode(out);
+        self.parent_hash.encode(out);
+        self.uncles_hash.encode(out);
+        self.author.encode(out);
+        self.state_root.encode(out);
+        self.transactions_root.encode(out);
+        self.receipts_root.encode(out);
+        self.number.encode(out);
+        self.gas_used.encode(out);
+        self.gas_limit.encode(out);
+        self.timestamp.encode(out);
+        self.difficulty.encode(out);
+        self.total_difficulty.encode(out);
+        self.size.encode(out);
+        self.base_fee_per_gas.encode(out);
+    }
+}
+
+impl Decode for Block {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Block {
+            hash: Decode::decode(input)?,
+            parent_hash: Decode::decode(input)?,
+            uncles_hash: Decode::decode(input)?,
+            author: Decode::decode(input)?,
+            state_root: Decode::decode(input)?,
+            transactions_root: Decode::decode(input)?,
+            receipts_root: Decode::decode(input)?,
+            number: Decode::decode(input)?,
+            gas_used: Decode::decode(input)?,
+            gas_limit: Decode::decode(input)?,
+            timestamp: Decode::decode(input)?,
+            difficulty: Decode::decode(input)?,
+            total_difficulty: Decode::decode(input)?,
+            size: Decode::decode(input)?,
+            base_fee_per_gas: Decode::decode(input)?,
+        })
+    }
+}
+
+impl Encode for Transaction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.hash.encode(out);
+        self.index.encode(out);
+        self.from.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.gas_limit.encode(out);
+        self.gas_price.encode(out);
+        self.input.encode(out);
+        self.nonce.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.transaction_type.encode(out);
+    }
+}
+
+impl Decode for Transaction {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Transaction {
+            hash: Decode::decode(input)?,
+            index: Decode::decode(input)?,
+            from: Decode::decode(input)?,
+            to: Decode::decode(input)?,
+            value: Decode::decode(input)?,
+            gas_limit: Decode::decode(input)?,
+            gas_price: Decode::decode(input)?,
+            input: Decode::decode(input)?,
+            nonce: Decode::decode(input)?,
+            max_fee_per_gas: Decode::decode(input)?,
+            max_priority_fee_per_gas: Decode::decode(input)?,
+            transaction_type: Decode::decode(input)?,
+        })
+    }
+}
+
+impl Encode for TransactionReceipt {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.transaction_hash.encode(out);
+        self.transaction_index.encode(out);
+        self.block_hash.encode(out);
+        self.block_number.encode(out);
+        self.cumulative_gas_used.encode(out);
+        self.gas_used.encode(out);
+        self.contract_address.encode(out);
+        self.logs.encode(out);
+        self.status.encode(out);
+        self.root.encode(out);
+        self.logs_bloom.encode(out);
+    }
+}
+
+impl Decode for TransactionReceipt {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(TransactionReceipt {
+            transaction_hash: Decode::decode(input)?,
+            transaction_index: Decode::decode(input)?,
+            block_hash: Decode::decode(input)?,
+            block_number: Decode::decode(input)?,
+            cumulative_gas_used: Decode::decode(input)?,
+            gas_used: Decode::decode(input)?,
+            contract_address: Decode::decode(input)?,
+            logs: Decode::decode(input)?,
+            status: Decode::decode(input)?,
+            root: Decode::decode(input)?,
+            logs_bloom: Decode::decode(input)?,
+        })
+    }
+}
+
+impl Encode for Log {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.address.encode(out);
+        self.topics.encode(out);
+        self.data.encode(out);
+        self.block_hash.encode(out);
+        self.block_number.encode(out);
+        self.transaction_hash.encode(out);
+        self.transaction_index.encode(out);
+        self.log_index.encode(out);
+        self.transaction_log_index.encode(out);
+        self.log_type.encode(out);
+        self.removed.encode(out);
+    }
+}
+
+impl Decode for Log {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Log {
+            address: Decode::decode(input)?,
+            topics: Decode::decode(input)?,
+            data: Decode::decode(input)?,
+            block_hash: Decode::decode(input)?,
+            block_number: Decode::decode(input)?,
+            transaction_hash: Decode::decode(input)?,
+            transaction_index: Decode::decode(input)?,
+            log_index: Decode::decode(input)?,
+            transaction_log_index: Decode::decode(input)?,
+            log_type: Decode::decode(input)?,
+            removed: Decode::decode(input)?,
+        })
+    }
+}
+
+/// Params are written as the named `Vec<EventParam>` list, so the host does
+/// not need to know the generated struct.
+impl<P: ToEventParams> Encode for Event<P> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.address.encode(out);
+        self.log_index.encode(out);
+        self.transaction_log_index.encode(out);
+        self.log_type.encode(out);
+        self.block.encode(out);
+        self.transaction.encode(out);
+        self.params.to_event_params().encode(out);
+        self.receipt.encode(out);
+    }
+}
+
+impl<P: Decode> Decode for Event<P> {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Event {
+            address: Decode::decode(input)?,
+            log_index: Decode::decode(input)?,
+            transaction_log_index: Decode::decode(input)?,
+            log_type: Decode::decode(input)?,
+            block: Decode::decode(input)?,
+            transaction: Decode::decode(input)?,
+            params: P::decode(input)?,
+            receipt: Decode::decode(input)?,
+        })
+    }
+}
+
+impl<I: ToEventParams, O: ToEventParams> Encode for Call<I, O> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.to.encode(out);
+        self.from.encode(out);
+        self.block.encode(out);
+        self.transaction.encode(out);
+        self.inputs.to_event_params().encode(out);
+        self.outputs.to_event_params().encode(out);
+    }
+}
+
+impl<I: Decode, O: Decode> Decode for Call<I, O> {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Call {
+            to: Decode::decode(input)?,
+            from: Decode::decode(input)?,
+            block: Decode::decode(input)?,
+            transaction: Decode::decode(input)?,
+            inputs: I::decode(input)?,
+            outputs: O::decode(input)?,
+        })
+    }
+}
+
+// ============================================================================
+// Host boundary (WASM, `rust-abi` feature)
+// ============================================================================
+
+/// Write `payload` as a message in freshly allocated linear memory.
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+pub(crate) fn write_message(payload: &[u8]) -> u32 {
+    let ptr = crate::allocator::asc_alloc(4 + payload.len() as u32, 0);
+    unsafe {
+        let dest = ptr as *mut u8;
+        core::ptr::copy_nonoverlapping((payload.len() as u32).to_le_bytes().as_ptr(), dest, 4);
+        core::ptr::copy_nonoverlapping(payload.as_ptr(), dest.add(4), payload.len());
+    }
+    ptr
+}
+
+/// Allocate `size` bytes for a message the host is about to write.
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+#[unsafe(no_mangle)]
+pub extern "C" fn yogurt_alloc(size: u32) -> u32 {
+    crate::allocator::asc_alloc(size, 0)
+}
+
+/// Marks the module as using the Rust ABI and reports its version.
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+#[unsafe(no_mangle)]
+pub extern "C" fn yogurt_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asc_image::{AscHeap, AscImage};
+    use crate::testing::{mock_block, mock_receipt, mock_transaction};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TransferParams {
+        from: Address,
+        value: BigInt,
+    }
+
+    impl ToEventParams for TransferParams {
+        fn to_event_params(&self) -> Vec<EventParam> {
+            vec![
+                EventParam {
+                    name: "from".into(),
+                    value: Token::Address(self.from.clone()),
+                },
+                EventParam {
+                    name: "value".into(),
+                    value: Token::Uint(self.value.clone()),
+                },
+            ]
+        }
+    }
+
+    /// Shaped like the `Decode` impls `yogurt codegen` emits.
+    impl Decode for TransferParams {
+        fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+            let params = decode_event_params(input)?;
+            Ok(Self {
+                from: match params.first() {
+                    Some(Token::Address(a)) => a.clone(),
+                    _ => Address::zero(),
+                },
+                value: match params.get(1) {
+                    Some(Token::Uint(n)) => n.clone(),
+                    _ => BigInt::zero(),
+                },
+            })
+        }
+    }
+
+    fn round_trip<T: Encode + Decode>(value: &T) -> T {
+        from_bytes(&to_bytes(value)).unwrap()
+    }
+
+    #[test]
+    fn test_primitive_encoding() {
+        assert_eq!(to_bytes(&0x0102_0304u32), [4, 3, 2, 1]);
+        assert_eq!(to_bytes(&true), [1]);
+        assert_eq!(to_bytes("hi"), [2, 0, 0, 0, b'h', b'i']);
+        assert_eq!(to_bytes(&Some(-1i32)), [1, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(to_bytes(&None::<i32>), [0]);
+        assert_eq!(to_bytes(&BigInt::from_i64(-2)), [1, 0, 0, 0, 0xfe]);
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let value = Value::Array(vec![
+            Value::String("ünïcode".into()),
+            Value::Int(-7),
+            Value::Int8(i64::MIN),
+            Value::Timestamp(Timestamp::from_seconds(1_700_000_000)),
+            Value::BigInt(BigInt::from_string("-123456789012345678901234567890").unwrap()),
+            Value::BigDecimal(BigDecimal::from_string("1.25")),
+            Value::Bool(true),
+            Value::Bytes(Bytes::from_vec(vec![0xde, 0xad])),
+            Value::Null,
+        ]);
+
+        let Value::Array(items) = round_trip(&value) else {
+            panic!("expected array");
+        };
+        assert_eq!(items.len(), 9);
+        assert_eq!(items[0].as_string(), Some("ünïcode"));
+        assert!(matches!(items[2], Value::Int8(i64::MIN)));
+        assert_eq!(
+            items[4].as_big_int().unwrap().to_string(),
+            "-123456789012345678901234567890"
+        );
+        assert_eq!(
+            items[5].as_big_decimal(),
+            Some(&BigDecimal::from_string("1.25"))
+        );
+        assert_eq!(
+            items[3].as_timestamp(),
+            Some(Timestamp::from_seconds(1_700_000_000))
+        );
+        assert!(items[8].is_null());
+    }
+
+    #[test]
+    fn test_entity_round_trip() {
+        let mut data = EntityData::new();
+        data.set("id", Value::String("0xabc".into()));
+        data.set("amount", Value::BigInt(BigInt::from_u64(1_000)));
+
+        let decoded = round_trip(&data);
+        assert_eq!(decoded.get_string("id"), "0xabc");
+        assert_eq!(decoded.get_bigint("amount"), BigInt::from_u64(1_000));
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let token = Token::Tuple(vec![
+            Token::Address(Address::from([7u8; 20])),
+            Token::FixedBytes(vec![1, 2, 3]),
+            Token::Array(vec![Token::Int(BigInt::from_i64(-1)), Token::Bool(false)]),
+        ]);
+
+        let Token::Tuple(items) = round_trip(&token) else {
+            panic!("expected tuple");
+        };
+        assert!(matches!(&items[0], Token::Address(a) if *a == Address::from([7u8; 20])));
+        assert!(matches!(&items[1], Token::FixedBytes(b) if b == &[1, 2, 3]));
+        assert!(matches!(&items[2], Token::Array(a) if a.len() == 2));
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let mut block = mock_block(19_000_000, 1_700_000_000);
+        block.base_fee_per_gas = Some(BigInt::from_u64(7));
+        let mut transaction =
+            mock_transaction([9u8; 32], Address::zero(), Some(Address::from([2u8; 20])));
+        transaction.max_fee_per_gas = Some(BigInt::from_u64(9));
+        transaction.transaction_type = Some(BigInt::from_u64(2));
+        let mut receipt = mock_receipt([9u8; 32], 19_000_000);
+        receipt.logs.push(Log {
+            topics: vec![Bytes::from_vec(vec![0xdd; 32])],
+            removed: Some(false),
+            ..Log::default()
+        });
+        let event = Event {
+            address: Address::from([1u8; 20]),
+            log_index: BigInt::from_u64(4),
+            transaction_log_index: BigInt::zero(),
+            log_type: None,
+            block,
+            transaction,
+            params: TransferParams {
+                from: Address::from([3u8; 20]),
+                value: BigInt::from_u64(500),
+            },
+            receipt: Some(receipt),
+        };
+
+        let decoded: Event<TransferParams> = from_bytes(&to_bytes(&event)).unwrap();
+        assert_eq!(decoded.address, event.address);
+        assert_eq!(decoded.log_index, event.log_index);
+        assert_eq!(decoded.block.number, event.block.number);
+        assert_eq!(decoded.block.base_fee_per_gas, event.block.base_fee_per_gas);
+        assert_eq!(decoded.transaction.to, event.transaction.to);
+        assert_eq!(decoded.transaction.max_fee_per_gas, Some(BigInt::from_u64(9)));
+        assert_eq!(decoded.transaction.max_priority_fee_per_gas, None);
+        assert_eq!(decoded.transaction.transaction_type, Some(BigInt::from_u64(2)));
+        assert_eq!(decoded.params, event.params);
+        let receipt = decoded.receipt.unwrap();
+        assert_eq!(receipt.block_number, BigInt::from_u64(19_000_000));
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(receipt.logs[0].topics, vec![Bytes::from_vec(vec![0xdd; 32])]);
+        assert_eq!(receipt.logs[0].removed, Some(false));
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        let bytes = to_bytes("hello");
+        assert_eq!(from_bytes::<String>(&bytes[..4]), Err(DecodeError::EOF));
+        assert!(from_bytes::<String>(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(from_bytes::<bool>(&[2]).is_err());
+        assert!(from_bytes::<Address>(&to_bytes(&Bytes::from_vec(vec![0; 19]))).is_err());
+        // A huge count must not allocate before running out of input
+        assert!(from_bytes::<Vec<u8>>(&[0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+
+    #[test]
+    fn test_read_message_from_linear_memory() {
+        let payload = to_bytes(&mock_block(42, 0));
+        let mut image = AscImage::new();
+        let ptr = image.alloc(4 + payload.len() as u32, 0).unwrap();
+        image
+            .write_raw(ptr, &(payload.len() as u32).to_le_bytes())
+            .unwrap();
+        image.write_raw(ptr + 4, &payload).unwrap();
+
+        let block: Block = image.enter(|| read_message(ptr));
+        assert_eq!(block.number, BigInt::from_u64(42));
+    }
+}
@@ -15,12 +15,12 @@ use crate::asc::{
     AscEntity, AscPtr, AscStoreValue, AscString, AscTypedArrayHeader, AscTypedMapEntry,
     AscTypedMapEntryHeader, AscTypedMapHeader, StoreValueKind,
 };
-use crate::types::{BigDecimal, BigInt, Bytes, EntityData, Value};
+use crate::types::{BigDecimal, BigInt, Bytes, EntityData, Timestamp, Value};
 
 /// Load an entity by type name and ID.
 ///
 /// Returns `None` if the entity does not exist.
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "rust-abi")))]
 pub fn get(entity_type: &str, id: &str) -> Option<EntityData> {
     let type_ptr = str_to_asc(entity_type);
     let id_ptr = str_to_asc(id);
@@ -34,13 +34,25 @@ pub fn get(entity_type: &str, id: &str) -> Option<EntityData> {
     }
 }
 
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+pub fn get(entity_type: &str, id: &str) -> Option<EntityData> {
+    let request = crate::rust_abi::write_message(&crate::rust_abi::to_bytes(&(entity_type, id)));
+    let result = unsafe { crate::host::yogurt_store_get(request as i32) };
+
+    if result == 0 {
+        None
+    } else {
+        Some(crate::rust_abi::read_message(result as u32))
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn get(entity_type: &str, id: &str) -> Option<EntityData> {
     crate::testing::store_get(entity_type, id)
 }
 
 /// Write an entity to the store.
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "rust-abi")))]
 pub fn set(entity_type: &str, id: &str, data: &EntityData) {
     let type_ptr = str_to_asc(entity_type);
     let id_ptr = str_to_asc(id);
@@ -51,13 +63,22 @@ pub fn set(entity_type: &str, id: &str, data: &EntityData) {
     }
 }
 
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+pub fn set(entity_type: &str, id: &str, data: &EntityData) {
+    let request = crate::rust_abi::write_message(&crate::rust_abi::to_bytes(&(entity_type, id, data)));
+
+    unsafe {
+        crate::host::yogurt_store_set(request as i32);
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn set(entity_type: &str, id: &str, data: &EntityData) {
     crate::testing::store_set(entity_type, id, data);
 }
 
 /// Remove an entity from the store.
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "rust-abi")))]
 pub fn remove(entity_type: &str, id: &str) {
     let type_ptr = str_to_asc(entity_type);
     let id_ptr = str_to_asc(id);
@@ -67,6 +88,15 @@ pub fn remove(entity_type: &str, id: &str) {
     }
 }
 
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+pub fn remove(entity_type: &str, id: &str) {
+    let request = crate::rust_abi::write_message(&crate::rust_abi::to_bytes(&(entity_type, id)));
+
+    unsafe {
+        crate::host::yogurt_store_remove(request as i32);
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn remove(entity_type: &str, id: &str) {
     crate::testing::store_remove(entity_type, id);
@@ -98,7 +128,7 @@ pub fn remove(entity_type: &str, id: &str) {
 /// This is useful when one handler creates an entity and a later handler
 /// (in the same block) needs to access it. Using `get_in_block` is faster
 /// because it only checks the current block's changes.
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "rust-abi")))]
 pub fn get_in_block(entity_type: &str, id: &str) -> Option<EntityData> {
     let type_ptr = str_to_asc(entity_type);
     let id_ptr = str_to_asc(id);
@@ -112,6 +142,18 @@ pub fn get_in_block(entity_type: &str, id: &str) -> Option<EntityData> {
     }
 }
 
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+pub fn get_in_block(entity_type: &str, id: &str) -> Option<EntityData> {
+    let request = crate::rust_abi::write_message(&crate::rust_abi::to_bytes(&(entity_type, id)));
+    let result = unsafe { crate::host::yogurt_store_get_in_block(request as i32) };
+
+    if result == 0 {
+        None
+    } else {
+        Some(crate::rust_abi::read_message(result as u32))
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn get_in_block(entity_type: &str, id: &str) -> Option<EntityData> {
     // In test mode, we simulate loadInBlock by checking if the entity
@@ -120,6 +162,57 @@ pub fn get_in_block(entity_type: &str, id: &str) -> Option<EntityData> {
     crate::testing::store_get_in_block(entity_type, id)
 }
 
+/// Load the entities referenced by a `@derivedFrom` field.
+///
+/// `field` is the derived field on `entity_type`; graph-node looks up which
+/// entity type and field it is derived from in the schema, and returns every
+/// entity of that type whose field refers back to `id`.
+#[cfg(all(target_arch = "wasm32", not(feature = "rust-abi")))]
+pub fn load_related(entity_type: &str, id: &str, field: &str) -> Vec<EntityData> {
+    let type_ptr = str_to_asc(entity_type);
+    let id_ptr = str_to_asc(id);
+    let field_ptr = str_to_asc(field);
+
+    let result = unsafe {
+        crate::host::store_load_related(type_ptr.as_i32(), id_ptr.as_i32(), field_ptr.as_i32())
+    };
+
+    deserialize_entity_array(AscPtr::new(result as u32))
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "rust-abi"))]
+pub fn load_related(entity_type: &str, id: &str, field: &str) -> Vec<EntityData> {
+    let request =
+        crate::rust_abi::write_message(&crate::rust_abi::to_bytes(&(entity_type, id, field)));
+    let result = unsafe { crate::host::yogurt_store_load_related(request as i32) };
+
+    crate::rust_abi::read_message(result as u32)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_related(entity_type: &str, id: &str, field: &str) -> Vec<EntityData> {
+    crate::testing::store_load_related(entity_type, id, field)
+}
+
+/// Allocate an ID for a new timeseries data point.
+///
+/// graph-node replaces the ID of every data point it stores with its own
+/// auto-incremented one, so on WASM this only has to be distinct within the
+/// module. Natively, IDs count up per entity type from 1 and restart on
+/// `testing::clear_store`.
+#[cfg(target_arch = "wasm32")]
+pub fn next_timeseries_id(_entity_type: &str) -> i64 {
+    use core::sync::atomic::{AtomicI64, Ordering};
+
+    static NEXT_ID: AtomicI64 = AtomicI64::new(1);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn next_timeseries_id(entity_type: &str) -> i64 {
+    crate::testing::next_timeseries_id(entity_type)
+}
+
 // ============================================================================
 // Serialization: Rust EntityData → AssemblyScript memory
 // ============================================================================
@@ -232,6 +325,7 @@ fn serialize_value(value: &Value) -> AscPtr<AscStoreValue> {
         }
         Value::Int(i) => (StoreValueKind::Int, *i as u64),
         Value::Int8(i) => (StoreValueKind::Int8, *i as u64),
+        Value::Timestamp(ts) => (StoreValueKind::Timestamp, ts.as_micros() as u64),
         Value::BigInt(bi) => {
             // Graph-node expects BigInt as Uint8Array wrapper, NOT raw ArrayBuffer
             // Extract the bytes and wrap in a TypedArray
@@ -424,11 +518,44 @@ fn deserialize_value(ptr: AscPtr<AscStoreValue>) -> Value {
                 // INT8 - sign-extend from i32 to i64
                 Value::Int8((payload as i32) as i64)
             }
+            9 => {
+                // TIMESTAMP - microseconds since the epoch
+                Value::Timestamp(Timestamp::from_micros(payload as i64))
+            }
             _ => Value::Null, // Unknown type, treat as null
         }
     }
 }
 
+/// Deserialize an `Array<Entity>`, as returned by `store.loadRelated`.
+#[cfg(all(target_arch = "wasm32", not(feature = "rust-abi")))]
+fn deserialize_entity_array(ptr: AscPtr<crate::asc::AscArray<AscEntity>>) -> Vec<EntityData> {
+    let mut entities = Vec::new();
+
+    if ptr.is_null() {
+        return entities;
+    }
+
+    unsafe {
+        let array_header = ptr.as_raw() as *const AscArrayHeader;
+        let buffer_ptr = (*array_header).buffer;
+        let length = (*array_header).length;
+
+        if buffer_ptr == 0 || length <= 0 {
+            return entities;
+        }
+
+        entities.reserve(length as usize);
+
+        for i in 0..length as usize {
+            let entity_ptr = core::ptr::read_unaligned((buffer_ptr as *const u32).add(i));
+            entities.push(deserialize_entity(AscPtr::new(entity_ptr)));
+        }
+    }
+
+    entities
+}
+
 /// Deserialize an array of StoreValues.
 #[cfg(target_arch = "wasm32")]
 fn deserialize_value_array(ptr: AscPtr<crate::asc::AscArray<AscStoreValue>>) -> Vec<Value> {
@@ -28,12 +28,18 @@
 //! }
 //! ```
 
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::ethereum::{Block, Call, Event, Token, Transaction, TransactionReceipt};
-use crate::types::{Address, BigInt, Bytes, Entity, EntityData};
+use crate::ethereum::{
+    Block, BlockFilter, Call, CallData, Event, FromLog, Log, ToLog, Token, TopicFilter, Transaction, TransactionReceipt,
+};
+use crate::handler::{HandlerError, HandlerOutput};
+use crate::types::{Address, BigInt, Bytes, Entity, EntityData, EntityKey, Value, value_key};
+#[cfg(feature = "wasm-host")]
+use crate::wasm_host::{WasmHost, WasmHostError};
 
 // ============================================================================
 // Thread-Local Mock Store
@@ -57,8 +63,17 @@ thread_local! {
     /// Mock data source state
     static MOCK_DATA_SOURCE: RefCell<MockDataSource> = RefCell::new(MockDataSource::default());
 
+    /// Data sources created from templates, in creation order
+    static CREATED_DATA_SOURCES: RefCell<Vec<CreatedDataSource>> = const { RefCell::new(Vec::new()) };
+
     /// Mock IPFS content: CID -> content
     static MOCK_IPFS: RefCell<BTreeMap<String, Vec<u8>>> = RefCell::new(BTreeMap::new());
+
+    /// Derived fields: (entity_type, field) -> (related entity type, field referring back)
+    static DERIVED_FIELDS: RefCell<BTreeMap<(String, String), (String, String)>> = const { RefCell::new(BTreeMap::new()) };
+
+    /// Last timeseries ID handed out per entity type
+    static TIMESERIES_IDS: RefCell<BTreeMap<String, i64>> = const { RefCell::new(BTreeMap::new()) };
 }
 
 /// Mocked data source state.
@@ -74,7 +89,8 @@ pub struct MockDataSource {
 pub struct MockEthereumCall {
     pub address: Address,
     pub signature: String,
-    pub returns: Option<Vec<Token>>,
+    /// The return values, or the revert data.
+    pub returns: Result<Vec<Token>, Bytes>,
 }
 
 // ============================================================================
@@ -130,6 +146,74 @@ pub fn clear_store() {
     BLOCK_MODIFIED_ENTITIES.with(|modified| {
         modified.borrow_mut().clear();
     });
+    TIMESERIES_IDS.with(|ids| {
+        ids.borrow_mut().clear();
+    });
+}
+
+/// Declare a `@derivedFrom` field so `store_load_related` can resolve it.
+///
+/// graph-node reads this from the schema. Generated `<field>_loaded` methods
+/// register their own field when called natively; tests that only run
+/// handlers through `WasmHost` register the fields they need themselves.
+///
+/// # Example
+///
+/// ```ignore
+/// // type Account @entity { tokens: [Token!]! @derivedFrom(field: "owner") }
+/// register_derived_field("Account", "tokens", "Token", "owner");
+/// ```
+pub fn register_derived_field(entity_type: &str, field: &str, related_type: &str, related_field: &str) {
+    DERIVED_FIELDS.with(|fields| {
+        fields.borrow_mut().insert(
+            (entity_type.to_string(), field.to_string()),
+            (related_type.to_string(), related_field.to_string()),
+        );
+    });
+}
+
+/// Answer `store.loadRelated` by scanning the mock store for entities that
+/// refer back to `id`, either directly or from an array of references.
+///
+/// Returns an empty list for fields that were never registered with
+/// [`register_derived_field`].
+pub fn store_load_related(entity_type: &str, id: &str, field: &str) -> Vec<EntityData> {
+    let Some((related_type, related_field)) = DERIVED_FIELDS
+        .with(|fields| fields.borrow().get(&(entity_type.to_string(), field.to_string())).cloned())
+    else {
+        return Vec::new();
+    };
+
+    // References hold the related ID with its own value kind, so compare keys
+    let is_id = |value: &Value| value_key(value).is_some_and(|key| key == id);
+    let refers_to_id = |value: &Value| match value {
+        Value::Array(items) => items.iter().any(is_id),
+        other => is_id(other),
+    };
+
+    MOCK_STORE.with(|store| {
+        store
+            .borrow()
+            .get(&related_type)
+            .map(|entities| {
+                entities
+                    .values()
+                    .filter(|data| data.get(&related_field).is_some_and(refers_to_id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// Allocate the next ID for a timeseries data point, starting from 1.
+pub fn next_timeseries_id(entity_type: &str) -> i64 {
+    TIMESERIES_IDS.with(|ids| {
+        let mut ids = ids.borrow_mut();
+        let id = ids.entry(entity_type.to_string()).or_insert(0);
+        *id += 1;
+        *id
+    })
 }
 
 /// Start a new block in the test context.
@@ -189,12 +273,13 @@ pub fn entity_count<E: Entity>() -> usize {
 }
 
 /// Assert that an entity exists in the store.
-pub fn assert_entity_exists<E: Entity>(id: &str) {
+pub fn assert_entity_exists<E: Entity>(id: impl EntityKey<E::Id>) {
+    let id = id.to_key();
     let exists = MOCK_STORE.with(|store| {
         store
             .borrow()
             .get(E::ENTITY_TYPE)
-            .map(|entities| entities.contains_key(id))
+            .map(|entities| entities.contains_key(id.as_ref()))
             .unwrap_or(false)
     });
 
@@ -207,12 +292,13 @@ pub fn assert_entity_exists<E: Entity>(id: &str) {
 }
 
 /// Assert that an entity does not exist in the store.
-pub fn assert_entity_not_exists<E: Entity>(id: &str) {
+pub fn assert_entity_not_exists<E: Entity>(id: impl EntityKey<E::Id>) {
+    let id = id.to_key();
     let exists = MOCK_STORE.with(|store| {
         store
             .borrow()
             .get(E::ENTITY_TYPE)
-            .map(|entities| entities.contains_key(id))
+            .map(|entities| entities.contains_key(id.as_ref()))
             .unwrap_or(false)
     });
 
@@ -234,18 +320,25 @@ pub fn mock_call(address: Address, signature: &str, returns: Vec<Token>) {
         calls.borrow_mut().push(MockEthereumCall {
             address,
             signature: signature.to_string(),
-            returns: Some(returns),
+            returns: Ok(returns),
         });
     });
 }
 
-/// Register a mock Ethereum call that reverts.
-pub fn mock_call_reverts(address: Address, signature: &str) {
+/// Register a mock Ethereum call that reverts with `revert_data`.
+///
+/// The data decides the `CallError` a `try_*` binding returns: use
+/// [`encode_revert_reason`] for `Error(string)`, a generated error's
+/// `ContractError::encode` for a custom error, or empty data for an
+/// unknown revert.
+///
+/// [`encode_revert_reason`]: crate::ethereum::encode_revert_reason
+pub fn mock_call_reverts(address: Address, signature: &str, revert_data: impl Into<Vec<u8>>) {
     MOCK_ETH_CALLS.with(|calls| {
         calls.borrow_mut().push(MockEthereumCall {
             address,
             signature: signature.to_string(),
-            returns: None,
+            returns: Err(Bytes::from_vec(revert_data.into())),
         });
     });
 }
@@ -258,7 +351,7 @@ pub fn clear_mocks() {
 }
 
 /// Execute a mock Ethereum call (called internally by ethereum::call).
-pub fn execute_mock_call(address: &Address, signature: &str) -> Option<Vec<Token>> {
+pub fn execute_mock_call(address: &Address, signature: &str) -> Result<Vec<Token>, Bytes> {
     MOCK_ETH_CALLS.with(|calls| {
         // Find matching mock (most recent first)
         for mock in calls.borrow().iter().rev() {
@@ -266,8 +359,8 @@ pub fn execute_mock_call(address: &Address, signature: &str) -> Option<Vec<Token
                 return mock.returns.clone();
             }
         }
-        // No mock found, return None (simulates revert)
-        None
+        // No mock found, simulate a revert without data
+        Err(Bytes::new())
     })
 }
 
@@ -345,6 +438,55 @@ pub fn get_mock_data_source_context() -> EntityData {
     })
 }
 
+// ============================================================================
+// Data Source Creation
+// ============================================================================
+
+/// A data source a handler created from a template with
+/// `data_source::create` or `data_source::create_with_context`.
+#[derive(Clone, Debug)]
+pub struct CreatedDataSource {
+    pub template: String,
+    /// The contract address as hex, or a file data source's content ID.
+    pub params: Vec<String>,
+    pub context: Option<EntityData>,
+}
+
+/// Record a created data source (called internally by data_source::create).
+pub fn record_created_data_source(template: &str, params: &[String], context: Option<EntityData>) {
+    CREATED_DATA_SOURCES.with(|created| {
+        created.borrow_mut().push(CreatedDataSource {
+            template: String::from(template),
+            params: params.to_vec(),
+            context,
+        });
+    });
+}
+
+/// The data sources created from templates since the last
+/// `clear_created_data_sources`, in creation order.
+///
+/// # Example
+///
+/// ```ignore
+/// handle_pair_created(event);
+/// let created = created_data_sources();
+/// assert_eq!(created[0].template, "Pair");
+/// assert_eq!(created[0].params, vec![pair.to_hex()]);
+/// ```
+pub fn created_data_sources() -> Vec<CreatedDataSource> {
+    CREATED_DATA_SOURCES.with(|created| created.borrow().clone())
+}
+
+/// Forget the recorded data sources.
+pub fn clear_created_data_sources() {
+    CREATED_DATA_SOURCES.with(|created| created.borrow_mut().clear());
+}
+
+fn take_created_data_sources() -> Vec<CreatedDataSource> {
+    CREATED_DATA_SOURCES.with(|created| core::mem::take(&mut *created.borrow_mut()))
+}
+
 // ============================================================================
 // IPFS Mocking
 // ============================================================================
@@ -395,6 +537,7 @@ pub struct EventBuilder<P> {
     transaction_from: Address,
     transaction_to: Option<Address>,
     params: Option<P>,
+    receipt: Option<TransactionReceipt>,
 }
 
 impl<P: Default> EventBuilder<P> {
@@ -411,6 +554,7 @@ impl<P: Default> EventBuilder<P> {
             transaction_from: Address::zero(),
             transaction_to: None,
             params: None,
+            receipt: None,
         }
     }
 
@@ -462,6 +606,12 @@ impl<P: Default> EventBuilder<P> {
         self
     }
 
+    /// Attach a transaction receipt, as with `receipt: true` in the manifest.
+    pub fn receipt(mut self, receipt: TransactionReceipt) -> Self {
+        self.receipt = Some(receipt);
+        self
+    }
+
     /// Build the event.
     pub fn build(self) -> Event<P> {
         Event {
@@ -476,7 +626,7 @@ impl<P: Default> EventBuilder<P> {
                 self.transaction_to,
             ),
             params: self.params.unwrap_or_default(),
-            receipt: None,
+            receipt: self.receipt,
         }
     }
 }
@@ -492,6 +642,540 @@ pub fn create_event<P: Default>(params: P) -> Event<P> {
     EventBuilder::new().params(params).build()
 }
 
+/// Run `handler` on `event` only if graph-node would deliver it through
+/// `filter`, the generated `<HANDLER>_TOPIC_FILTER` of the handler.
+///
+/// Returns the handler's result, or `None` if the filter dropped the event.
+///
+/// ```rust,ignore
+/// let ran = dispatch_event(&HANDLE_DIRECTED_TRANSFER_TOPIC_FILTER, event, handle_directed_transfer);
+/// assert!(ran.is_none());
+/// ```
+pub fn dispatch_event<P: ToLog, R>(
+    filter: &TopicFilter,
+    event: Event<P>,
+    handler: impl FnOnce(Event<P>) -> R,
+) -> Option<R> {
+    if filter.matches(&event.params.to_log(event.address.clone())) {
+        Some(handler(event))
+    } else {
+        None
+    }
+}
+
+/// Runs a block handler on the blocks graph-node would pick for its filter,
+/// so polling intervals and `once` handlers can be tested natively.
+///
+/// ```rust,ignore
+/// let dispatcher = BlockDispatcher::new(HANDLE_BLOCK_BLOCK_FILTER, 100);
+/// let ran = dispatcher.run((95..=130).map(|n| create_block(n, n * 12)), handle_block);
+/// assert_eq!(ran.len(), 4); // polling every 10: blocks 100, 110, 120 and 130
+/// ```
+pub struct BlockDispatcher {
+    filter: BlockFilter,
+    start_block: u64,
+    call_blocks: BTreeSet<u64>,
+}
+
+impl BlockDispatcher {
+    /// A dispatcher for a handler with `filter`, the generated
+    /// `<HANDLER>_BLOCK_FILTER`, in a data source starting at `start_block`.
+    pub fn new(filter: BlockFilter, start_block: u64) -> Self {
+        Self {
+            filter,
+            start_block,
+            call_blocks: BTreeSet::new(),
+        }
+    }
+
+    /// Mark blocks as calling the data source's contract, the blocks a
+    /// `call` filter picks.
+    pub fn with_calls_in(mut self, blocks: impl IntoIterator<Item = u64>) -> Self {
+        self.call_blocks.extend(blocks);
+        self
+    }
+
+    /// Whether graph-node runs the handler on block `number`.
+    pub fn picks(&self, number: u64) -> bool {
+        self.filter.matches(number, self.start_block, self.call_blocks.contains(&number))
+    }
+
+    /// Run `handler` on `block` if the filter picks it.
+    ///
+    /// Returns the handler's result, or `None` if the block was skipped.
+    pub fn dispatch<R>(&self, block: Block, handler: impl FnOnce(Block) -> R) -> Option<R> {
+        let number = u64::try_from(block.number.inner()).ok()?;
+        if self.picks(number) {
+            Some(handler(block))
+        } else {
+            None
+        }
+    }
+
+    /// Feed `blocks` in order, starting a new block in the store before
+    /// each, and run `handler` on those the filter picks.
+    ///
+    /// Returns the results of the handler runs.
+    pub fn run<R>(
+        &self,
+        blocks: impl IntoIterator<Item = Block>,
+        mut handler: impl FnMut(Block) -> R,
+    ) -> Vec<R> {
+        blocks
+            .into_iter()
+            .filter_map(|block| {
+                start_block();
+                self.dispatch(block, &mut handler)
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Chain Simulation
+// ============================================================================
+
+/// A raw call to a contract, which triggers call handlers.
+#[derive(Clone, Debug)]
+pub struct RawCall {
+    pub from: Address,
+    pub to: Address,
+    /// The 4-byte function selector followed by the ABI-encoded inputs.
+    pub input: Bytes,
+    /// The ABI-encoded return values.
+    pub output: Bytes,
+    pub transaction: Transaction,
+}
+
+impl RawCall {
+    /// A call from `from` to `to`, encoding the generated inputs and outputs
+    /// of a call handler.
+    pub fn new<I: CallData, O: CallData>(from: Address, to: Address, inputs: &I, outputs: &O) -> Self {
+        let transaction = mock_transaction([0u8; 32], from.clone(), Some(to.clone()));
+        Self {
+            from,
+            to,
+            input: Bytes::from_vec(inputs.to_call_data()),
+            output: Bytes::from_vec(outputs.to_call_data()),
+            transaction,
+        }
+    }
+}
+
+/// A block fed to a [`Chain`], with the logs and calls it triggers
+/// handlers on.
+#[derive(Clone, Debug)]
+pub struct ChainBlock {
+    block: Block,
+    logs: Vec<(Log, Transaction)>,
+    calls: Vec<RawCall>,
+}
+
+impl ChainBlock {
+    /// A block without logs or calls.
+    pub fn new(block: Block) -> Self {
+        Self {
+            block,
+            logs: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    /// Add a log, setting its block and log index.
+    ///
+    /// A log without a transaction hash gets one unique to its block and
+    /// position, so IDs from `log_id!` do not collide.
+    pub fn log(mut self, mut log: Log) -> Self {
+        let index = self.logs.len() as u64;
+        log.block_number = self.block.number.clone();
+        log.block_hash = self.block.hash.clone();
+        log.log_index = BigInt::from_u64(index);
+        if log.transaction_hash.is_empty() {
+            let mut hash = [0u8; 32];
+            hash[16..24].copy_from_slice(&block_number(&self.block).to_be_bytes());
+            hash[24..].copy_from_slice(&index.to_be_bytes());
+            log.transaction_hash = Bytes::from(hash.as_slice());
+        }
+
+        let mut transaction = mock_transaction([0u8; 32], Address::zero(), Some(log.address.clone()));
+        transaction.hash = log.transaction_hash.clone();
+        self.logs.push((log, transaction));
+        self
+    }
+
+    /// Add the log of an event emitted by `address`.
+    pub fn event(self, address: Address, params: &impl ToLog) -> Self {
+        self.log(params.to_log(address))
+    }
+
+    /// Add a call, after the logs in trigger order.
+    pub fn call(mut self, call: RawCall) -> Self {
+        self.calls.push(call);
+        self
+    }
+}
+
+/// An event handler in a [`DispatchTable`].
+pub struct EventHandlerSpec {
+    /// The handler's name in the manifest.
+    pub handler: &'static str,
+    pub filter: TopicFilter,
+    /// Run the handler on a log, or return `None` if it is another event.
+    pub run: fn(&Log, &Block, &Transaction) -> Option<Result<(), HandlerError>>,
+}
+
+/// A call handler in a [`DispatchTable`].
+pub struct CallHandlerSpec {
+    pub handler: &'static str,
+    /// Run the handler on a call, or return `None` if it calls another
+    /// function.
+    pub run: fn(&RawCall, &Block) -> Option<Result<(), HandlerError>>,
+}
+
+/// A block handler in a [`DispatchTable`].
+pub struct BlockHandlerSpec {
+    pub handler: &'static str,
+    pub filter: BlockFilter,
+    pub run: fn(&Block) -> Result<(), HandlerError>,
+}
+
+/// A data source or template in a [`DispatchTable`], with its handlers in
+/// manifest order.
+pub struct DataSourceSpec {
+    pub name: &'static str,
+    /// The contract address; `None` for templates, and for data sources
+    /// that match every contract.
+    pub address: Option<Address>,
+    pub start_block: u64,
+    pub events: Vec<EventHandlerSpec>,
+    pub calls: Vec<CallHandlerSpec>,
+    pub blocks: Vec<BlockHandlerSpec>,
+}
+
+/// The handlers of a manifest's data sources and templates.
+///
+/// `yogurt codegen` generates one as the `dispatch_table()` function, which
+/// runs the handler functions of `crate::mappings`:
+///
+/// ```rust,ignore
+/// let mut chain = Chain::new(crate::generated::dispatch_table());
+/// ```
+pub struct DispatchTable {
+    pub data_sources: Vec<DataSourceSpec>,
+    pub templates: Vec<DataSourceSpec>,
+}
+
+/// Run an event handler on `log` if it decodes as the handler's event.
+///
+/// Used by the generated dispatch table.
+pub fn run_event<P: FromLog, R: HandlerOutput>(
+    log: &Log,
+    block: &Block,
+    transaction: &Transaction,
+    handler: impl FnOnce(Event<P>) -> R,
+) -> Option<Result<(), HandlerError>> {
+    let params = P::from_log(log)?;
+    let event = Event {
+        address: log.address.clone(),
+        log_index: log.log_index.clone(),
+        transaction_log_index: log.transaction_log_index.clone(),
+        log_type: log.log_type.clone(),
+        block: block.clone(),
+        transaction: transaction.clone(),
+        params,
+        receipt: None,
+    };
+    Some(handler(event).into_result())
+}
+
+/// Run a call handler on `call` if it calls the handler's function.
+///
+/// Used by the generated dispatch table.
+pub fn run_call<I: CallData, O: CallData, R: HandlerOutput>(
+    call: &RawCall,
+    block: &Block,
+    handler: impl FnOnce(Call<I, O>) -> R,
+) -> Option<Result<(), HandlerError>> {
+    let call = Call {
+        to: call.to.clone(),
+        from: call.from.clone(),
+        block: block.clone(),
+        transaction: call.transaction.clone(),
+        inputs: I::from_call_data(call.input.as_slice())?,
+        outputs: O::from_call_data(call.output.as_slice())?,
+    };
+    Some(handler(call).into_result())
+}
+
+/// Run a block handler on `block`.
+///
+/// Used by the generated dispatch table.
+pub fn run_block<R: HandlerOutput>(block: &Block, handler: impl FnOnce(Block) -> R) -> Result<(), HandlerError> {
+    handler(block.clone()).into_result()
+}
+
+/// A data source a [`Chain`] runs, from the manifest or a template.
+struct Instance {
+    template: bool,
+    index: usize,
+    address: Option<Address>,
+    start_block: u64,
+    context: Option<EntityData>,
+}
+
+impl Instance {
+    fn matches(&self, address: &Address, number: u64) -> bool {
+        number >= self.start_block && self.address.as_ref().is_none_or(|a| a == address)
+    }
+}
+
+/// Feeds blocks through a [`DispatchTable`] the way graph-node triggers
+/// handlers, so tests can check routing, template instantiation and the
+/// order of handlers within a block.
+///
+/// Data sources only see blocks from their start block. Within a block,
+/// logs run first, in log order, then calls, then block handlers; each
+/// trigger runs the matching handlers of every data source in creation
+/// order. A data source created from a template with `data_source::create`
+/// starts at the current block, and processes it once the existing data
+/// sources have. Templates missing from the table, such as file data
+/// sources, are not instantiated.
+///
+/// ```rust,ignore
+/// let mut chain = Chain::new(crate::generated::dispatch_table());
+/// chain.run([
+///     ChainBlock::new(create_block(100, 1200)).event(factory, &pair_created),
+///     ChainBlock::new(create_block(101, 1212)).event(pair, &swap),
+/// ])?;
+/// assert_eq!(chain.history(), [(100, "handlePairCreated"), (101, "handleSwap")]);
+/// ```
+pub struct Chain {
+    table: DispatchTable,
+    instances: Vec<Instance>,
+    history: Vec<(u64, &'static str)>,
+}
+
+impl Chain {
+    /// A chain running the data sources of `table`.
+    pub fn new(table: DispatchTable) -> Self {
+        let instances = table
+            .data_sources
+            .iter()
+            .enumerate()
+            .map(|(index, ds)| Instance {
+                template: false,
+                index,
+                address: ds.address.clone(),
+                start_block: ds.start_block,
+                context: None,
+            })
+            .collect();
+        Self {
+            table,
+            instances,
+            history: Vec::new(),
+        }
+    }
+
+    /// Process `blocks` in order, stopping at the first handler error.
+    pub fn run(&mut self, blocks: impl IntoIterator<Item = ChainBlock>) -> Result<(), HandlerError> {
+        blocks.into_iter().try_for_each(|block| self.process(block))
+    }
+
+    /// Start a new block in the store and run the handlers it triggers.
+    ///
+    /// A handler error is returned with the handler and block as context,
+    /// and the rest of the block is skipped.
+    pub fn process(&mut self, block: ChainBlock) -> Result<(), HandlerError> {
+        start_block();
+        let number = block_number(&block.block);
+
+        let mut round = 0..self.instances.len();
+        while !round.is_empty() {
+            let created = self.instances.len();
+
+            for (log, transaction) in &block.logs {
+                for i in self.matching(round.clone(), &log.address, number) {
+                    let handlers: Vec<_> = self
+                        .spec(i)
+                        .events
+                        .iter()
+                        .filter(|h| h.filter.matches(log))
+                        .map(|h| (h.handler, h.run))
+                        .collect();
+                    for (handler, run) in handlers {
+                        self.invoke(i, number, handler, || run(log, &block.block, transaction))?;
+                    }
+                }
+            }
+
+            for call in &block.calls {
+                for i in self.matching(round.clone(), &call.to, number) {
+                    let handlers: Vec<_> = self.spec(i).calls.iter().map(|h| (h.handler, h.run)).collect();
+                    for (handler, run) in handlers {
+                        self.invoke(i, number, handler, || run(call, &block.block))?;
+                    }
+                }
+            }
+
+            for i in round.clone() {
+                let instance = &self.instances[i];
+                let has_call = block.calls.iter().any(|call| instance.matches(&call.to, number));
+                let handlers: Vec<_> = self
+                    .spec(i)
+                    .blocks
+                    .iter()
+                    .filter(|h| h.filter.matches(number, instance.start_block, has_call))
+                    .map(|h| (h.handler, h.run))
+                    .collect();
+                for (handler, run) in handlers {
+                    self.invoke(i, number, handler, || Some(run(&block.block)))?;
+                }
+            }
+
+            round = created..self.instances.len();
+        }
+
+        Ok(())
+    }
+
+    /// The handlers run so far, in order, with the block each ran on.
+    pub fn history(&self) -> &[(u64, &'static str)] {
+        &self.history
+    }
+
+    /// The addresses of the data sources created from `template`, in
+    /// creation order.
+    pub fn template_instances(&self, template: &str) -> Vec<Address> {
+        self.instances
+            .iter()
+            .filter(|instance| instance.template && self.table.templates[instance.index].name == template)
+            .filter_map(|instance| instance.address.clone())
+            .collect()
+    }
+
+    /// The instances in `round` a trigger of `address` reaches.
+    fn matching(&self, round: core::ops::Range<usize>, address: &Address, number: u64) -> Vec<usize> {
+        round.filter(|&i| self.instances[i].matches(address, number)).collect()
+    }
+
+    fn spec(&self, instance: usize) -> &DataSourceSpec {
+        let Instance { template, index, .. } = self.instances[instance];
+        if template {
+            &self.table.templates[index]
+        } else {
+            &self.table.data_sources[index]
+        }
+    }
+
+    /// Run a handler as data source `instance`, then instantiate the
+    /// templates it created.
+    fn invoke(
+        &mut self,
+        instance: usize,
+        number: u64,
+        handler: &'static str,
+        run: impl FnOnce() -> Option<Result<(), HandlerError>>,
+    ) -> Result<(), HandlerError> {
+        let Instance { address, context, .. } = &self.instances[instance];
+        MOCK_DATA_SOURCE.with(|ds| {
+            let mut ds = ds.borrow_mut();
+            ds.address = address.clone();
+            ds.context = context.clone();
+        });
+
+        let Some(result) = run() else {
+            return Ok(());
+        };
+        self.history.push((number, handler));
+
+        for created in take_created_data_sources() {
+            let Some(index) = self.table.templates.iter().position(|t| t.name == created.template) else {
+                continue;
+            };
+            let Some(address) = created.params.first().and_then(|param| Address::from_string(param)) else {
+                continue;
+            };
+            self.instances.push(Instance {
+                template: true,
+                index,
+                address: Some(address),
+                start_block: number,
+                context: created.context,
+            });
+        }
+
+        result.map_err(|e| e.context(format!("{} failed at block {}", handler, number)))
+    }
+}
+
+fn block_number(block: &Block) -> u64 {
+    u64::try_from(block.number.inner()).unwrap_or(0)
+}
+
+// ============================================================================
+// WASM Mode
+// ============================================================================
+
+/// A handler argument: an event, a call, a block, or a file's contents.
+///
+/// Lets `#[handler]` functions hand their argument to the compiled module
+/// under `yogurt test --wasm`.
+pub trait HandlerArg {
+    /// Run `handler`, the export, on this argument in `host`.
+    #[cfg(feature = "wasm-host")]
+    fn run_in(&self, host: &mut WasmHost, handler: &str) -> Result<(), WasmHostError>;
+}
+
+impl<P: crate::ethereum::ToEventParams> HandlerArg for Event<P> {
+    #[cfg(feature = "wasm-host")]
+    fn run_in(&self, host: &mut WasmHost, handler: &str) -> Result<(), WasmHostError> {
+        host.handle_event(handler, self)
+    }
+}
+
+impl<I: crate::ethereum::ToEventParams, O: crate::ethereum::ToEventParams> HandlerArg for Call<I, O> {
+    #[cfg(feature = "wasm-host")]
+    fn run_in(&self, host: &mut WasmHost, handler: &str) -> Result<(), WasmHostError> {
+        host.handle_call(handler, self)
+    }
+}
+
+impl HandlerArg for Block {
+    #[cfg(feature = "wasm-host")]
+    fn run_in(&self, host: &mut WasmHost, handler: &str) -> Result<(), WasmHostError> {
+        host.handle_block(handler, self)
+    }
+}
+
+impl HandlerArg for Bytes {
+    #[cfg(feature = "wasm-host")]
+    fn run_in(&self, host: &mut WasmHost, handler: &str) -> Result<(), WasmHostError> {
+        host.handle_file(handler, self)
+    }
+}
+
+/// Run the export of a `#[handler]` function in the module `yogurt test
+/// --wasm` points [`WASM_MODULE_ENV`](crate::wasm_host::WASM_MODULE_ENV) at,
+/// instead of its Rust body.
+///
+/// Returns `None` to run the body natively: when the variable is unset, or
+/// the runtime was built without the `wasm-host` feature. A fallible handler
+/// that returned `Err` in the module returns it here too; any other failure,
+/// such as a panic in the module, panics with the handler's critical log.
+#[doc(hidden)]
+pub fn run_in_wasm_host<A: HandlerArg, R: HandlerOutput>(handler: &str, arg: &A) -> Option<R> {
+    #[cfg(feature = "wasm-host")]
+    if std::env::var_os(crate::wasm_host::WASM_MODULE_ENV).is_some() {
+        return match crate::wasm_host::run_env_handler(handler, arg) {
+            Ok(result) => Some(R::from_result(result)),
+            Err(e) => panic!("{}", e),
+        };
+    }
+    let _ = (handler, arg);
+    None
+}
+
 // ============================================================================
 // Mock Block/Transaction Helpers
 // ============================================================================
@@ -529,6 +1213,9 @@ pub fn mock_transaction(hash: [u8; 32], from: Address, to: Option<Address>) -> T
         gas_price: BigInt::from_u64(1_000_000_000),
         input: Bytes::new(),
         nonce: BigInt::zero(),
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        transaction_type: None,
     }
 }
 
@@ -542,12 +1229,122 @@ pub fn mock_receipt(tx_hash: [u8; 32], block_number: u64) -> TransactionReceipt
         cumulative_gas_used: BigInt::from_u64(21_000),
         gas_used: BigInt::from_u64(21_000),
         contract_address: None,
+        logs: Vec::new(),
         status: BigInt::one(),
         root: Bytes::new(),
         logs_bloom: Bytes::from(alloc::vec![0u8; 256]),
     }
 }
 
+/// Create a mock log emitted by `address`, with 32-byte topics and
+/// ABI-encoded data.
+pub fn mock_log(address: Address, topics: &[[u8; 32]], data: impl Into<Vec<u8>>) -> Log {
+    Log {
+        address,
+        topics: topics.iter().map(|topic| Bytes::from(topic.as_slice())).collect(),
+        data: Bytes::from_vec(data.into()),
+        removed: Some(false),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Receipt Builder
+// ============================================================================
+
+/// Builder for constructing test transaction receipts with sensible defaults.
+pub struct ReceiptBuilder {
+    transaction_hash: [u8; 32],
+    block_number: u64,
+    gas_used: u64,
+    contract_address: Option<Address>,
+    logs: Vec<Log>,
+    status: u64,
+}
+
+impl ReceiptBuilder {
+    /// Create a new receipt builder with default values.
+    pub fn new() -> Self {
+        Self {
+            transaction_hash: [0u8; 32],
+            block_number: 1,
+            gas_used: 21_000,
+            contract_address: None,
+            logs: Vec::new(),
+            status: 1,
+        }
+    }
+
+    /// Set the transaction hash.
+    pub fn transaction_hash(mut self, hash: [u8; 32]) -> Self {
+        self.transaction_hash = hash;
+        self
+    }
+
+    /// Set the block number.
+    pub fn block_number(mut self, num: u64) -> Self {
+        self.block_number = num;
+        self
+    }
+
+    /// Set the gas used by the transaction.
+    pub fn gas_used(mut self, gas: u64) -> Self {
+        self.gas_used = gas;
+        self
+    }
+
+    /// Set the address of the contract the transaction created.
+    pub fn contract_address(mut self, addr: Address) -> Self {
+        self.contract_address = Some(addr);
+        self
+    }
+
+    /// Mark the transaction as reverted.
+    pub fn reverted(mut self) -> Self {
+        self.status = 0;
+        self
+    }
+
+    /// Append a log, such as one from [`mock_log`].
+    ///
+    /// Its transaction hash, block number and indexes are filled in by
+    /// [`build`](Self::build).
+    pub fn log(mut self, log: Log) -> Self {
+        self.logs.push(log);
+        self
+    }
+
+    /// Build the receipt.
+    pub fn build(self) -> TransactionReceipt {
+        let mut receipt = mock_receipt(self.transaction_hash, self.block_number);
+        receipt.gas_used = BigInt::from_u64(self.gas_used);
+        receipt.cumulative_gas_used = BigInt::from_u64(self.gas_used);
+        receipt.contract_address = self.contract_address;
+        receipt.status = BigInt::from_u64(self.status);
+        receipt.logs = self
+            .logs
+            .into_iter()
+            .enumerate()
+            .map(|(i, log)| Log {
+                block_hash: receipt.block_hash.clone(),
+                block_number: receipt.block_number.clone(),
+                transaction_hash: receipt.transaction_hash.clone(),
+                transaction_index: receipt.transaction_index.clone(),
+                log_index: BigInt::from_u64(i as u64),
+                transaction_log_index: BigInt::from_u64(i as u64),
+                ..log
+            })
+            .collect();
+        receipt
+    }
+}
+
+impl Default for ReceiptBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ============================================================================
 // Call Builder
 // ============================================================================
@@ -798,6 +1595,7 @@ pub trait TestableEntity: Entity {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data_source;
     use crate::types::Value;
 
     #[test]
@@ -866,4 +1664,291 @@ mod tests {
         let loaded = store_get_in_block("TestEntity", "test-1").unwrap();
         assert_eq!(loaded.get_int_opt("value"), Some(200));
     }
+
+    #[test]
+    fn test_store_load_related() {
+        clear_store();
+        register_derived_field("Pool", "swaps", "Swap", "pool");
+
+        for (id, pool) in [("s1", "p1"), ("s2", "p2"), ("s3", "p1")] {
+            let mut data = EntityData::new();
+            data.set("id", Value::String(id.into()));
+            data.set("pool", Value::String(pool.into()));
+            store_set("Swap", id, &data);
+        }
+
+        let related = store_load_related("Pool", "p1", "swaps");
+        let ids: Vec<&str> = related.iter().map(|d| d.get_string("id")).collect();
+        assert_eq!(ids, ["s1", "s3"]);
+
+        assert!(store_load_related("Pool", "p1", "unregistered").is_empty());
+    }
+
+    #[test]
+    fn test_store_load_related_bytes_ids() {
+        clear_store();
+        register_derived_field("Pool", "swaps", "Swap", "pool");
+
+        let pool = Bytes::from(vec![0xaa, 0xbb]);
+        for (id, pool) in [(1, pool.clone()), (2, Bytes::from(vec![0xcc]))] {
+            let mut data = EntityData::new();
+            data.set("id", Value::Int8(id));
+            data.set("pool", Value::Bytes(pool));
+            store_set("Swap", &id.to_string(), &data);
+        }
+
+        let related = store_load_related("Pool", &pool.to_hex(), "swaps");
+        let ids: Vec<Option<i64>> = related.iter().map(|d| d.get_int8_opt("id")).collect();
+        assert_eq!(ids, [Some(1)]);
+    }
+
+    #[test]
+    fn test_next_timeseries_id() {
+        clear_store();
+
+        assert_eq!(next_timeseries_id("Price"), 1);
+        assert_eq!(next_timeseries_id("Price"), 2);
+        assert_eq!(next_timeseries_id("Volume"), 1);
+
+        clear_store();
+        assert_eq!(next_timeseries_id("Price"), 1);
+    }
+
+    /// Stands in for a generated params struct: `Ping(uint256 n)`.
+    struct PingParams {
+        n: BigInt,
+    }
+
+    impl crate::ethereum::FromLog for PingParams {
+        fn from_log(log: &Log) -> Option<Self> {
+            if log.topic0()?.as_slice() != [0x01; 32] {
+                return None;
+            }
+            use crate::ethereum::abi::{self, ParamType};
+            match abi::decode_value(&ParamType::parse("uint256")?, log.data.as_slice())? {
+                Token::Uint(n) => Some(PingParams { n }),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_receipt_builder_logs() {
+        let mut n = [0u8; 32];
+        n[31] = 7;
+        let receipt = ReceiptBuilder::new()
+            .transaction_hash([0x22; 32])
+            .block_number(42)
+            .log(mock_log(Address::from([1u8; 20]), &[[0x02; 32]], Vec::new()))
+            .log(mock_log(Address::from([1u8; 20]), &[[0x01; 32]], n))
+            .build();
+
+        assert_eq!(receipt.logs.len(), 2);
+        assert_eq!(receipt.logs[1].log_index, BigInt::from_u64(1));
+        assert_eq!(receipt.logs[1].block_number, BigInt::from_u64(42));
+        assert_eq!(receipt.logs[1].transaction_hash.as_slice(), &[0x22; 32]);
+
+        let pings: Vec<PingParams> = receipt.decode_logs().collect();
+        assert_eq!(pings.len(), 1);
+        assert_eq!(pings[0].n, BigInt::from_u64(7));
+
+        let event = EventBuilder::<()>::new().receipt(receipt).build();
+        assert!(event.receipt.unwrap().logs[0].decode::<PingParams>().is_none());
+    }
+
+    /// Stands in for a generated params struct:
+    /// `Transfer(address indexed from, address indexed to, uint256 value)`.
+    #[derive(Clone, Default)]
+    struct TransferParams {
+        from: Address,
+        to: Address,
+    }
+
+    impl ToLog for TransferParams {
+        fn to_log(&self, address: Address) -> Log {
+            let topic = |a: &Address| {
+                let mut topic = [0u8; 32];
+                topic[12..].copy_from_slice(&a.0);
+                Bytes::from(topic)
+            };
+            Log {
+                address,
+                topics: alloc::vec![Bytes::from([0x03; 32]), topic(&self.from), topic(&self.to)],
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_dispatch_event_topic_filter() {
+        const TO: [u8; 32] = {
+            let mut topic = [0u8; 32];
+            topic[31] = 0x22;
+            topic
+        };
+        const FILTER: TopicFilter = TopicFilter { topic1: None, topic2: Some(&[TO]), topic3: None };
+
+        let mut to = [0u8; 20];
+        to[19] = 0x22;
+        let matching = EventBuilder::new()
+            .params(TransferParams { from: Address::from([0x11; 20]), to: Address::from(to) })
+            .build();
+        let other = EventBuilder::new()
+            .params(TransferParams { from: Address::from(to), to: Address::from([0x11; 20]) })
+            .build();
+
+        assert_eq!(dispatch_event(&FILTER, matching.clone(), |_| 1), Some(1));
+        assert_eq!(dispatch_event(&FILTER, other.clone(), |_| 1), None);
+        assert_eq!(dispatch_event(&TopicFilter::ANY, other, |_| 1), Some(1));
+
+        // A filter on a topic the log does not have matches nothing
+        let topic3 = TopicFilter { topic3: Some(&[TO]), ..TopicFilter::ANY };
+        assert_eq!(dispatch_event(&topic3, matching, |_| 1), None);
+    }
+
+    #[test]
+    fn test_block_dispatcher_filters() {
+        let numbers = |dispatcher: &BlockDispatcher| {
+            dispatcher.run((95..=131).map(|n| create_block(n, n * 12)), |block| {
+                u64::try_from(block.number.inner()).unwrap()
+            })
+        };
+
+        let polling = BlockDispatcher::new(BlockFilter::Polling { every: 10 }, 100);
+        assert_eq!(numbers(&polling), [100, 110, 120, 130]);
+
+        let once = BlockDispatcher::new(BlockFilter::Once, 100);
+        assert_eq!(numbers(&once), [100]);
+
+        let call = BlockDispatcher::new(BlockFilter::Call, 100).with_calls_in([97, 105, 131]);
+        assert_eq!(numbers(&call), [105, 131]);
+
+        let all = BlockDispatcher::new(BlockFilter::All, 129);
+        assert_eq!(numbers(&all), [129, 130, 131]);
+    }
+
+    /// Stands in for a generated params struct: `PoolCreated(address pool)`.
+    struct PoolCreatedParams {
+        pool: Address,
+    }
+
+    impl crate::ethereum::FromLog for PoolCreatedParams {
+        fn from_log(log: &Log) -> Option<Self> {
+            if log.topic0()?.as_slice() != [0x04; 32] {
+                return None;
+            }
+            Some(PoolCreatedParams { pool: Address::from(&log.data.as_slice()[12..32]) })
+        }
+    }
+
+    fn pool_created(factory: &Address, pool: &Address) -> Log {
+        let mut data = alloc::vec![0u8; 12];
+        data.extend_from_slice(&pool.0);
+        mock_log(factory.clone(), &[[0x04; 32]], data)
+    }
+
+    fn ping(address: &Address, n: u8) -> Log {
+        let mut data = [0u8; 32];
+        data[31] = n;
+        mock_log(address.clone(), &[[0x01; 32]], data.to_vec())
+    }
+
+    fn chain_table(factory: &Address) -> DispatchTable {
+        DispatchTable {
+            data_sources: alloc::vec![DataSourceSpec {
+                name: "Factory",
+                address: Some(factory.clone()),
+                start_block: 10,
+                events: alloc::vec![EventHandlerSpec {
+                    handler: "handlePoolCreated",
+                    filter: TopicFilter::ANY,
+                    run: |log, block, transaction| {
+                        run_event(log, block, transaction, |event: Event<PoolCreatedParams>| {
+                            let mut context = EntityData::new();
+                            context.set("factory", Value::Bytes(Bytes::from(data_source::address().0)));
+                            data_source::create_with_context("Pool", &[event.params.pool.to_hex()], context);
+                        })
+                    },
+                }],
+                calls: Vec::new(),
+                blocks: Vec::new(),
+            }],
+            templates: alloc::vec![DataSourceSpec {
+                name: "Pool",
+                address: None,
+                start_block: 0,
+                events: alloc::vec![EventHandlerSpec {
+                    handler: "handlePing",
+                    filter: TopicFilter::ANY,
+                    run: |log, block, transaction| {
+                        run_event(log, block, transaction, |event: Event<PingParams>| {
+                            if event.params.n.is_zero() {
+                                return Err(HandlerError::new("zero ping"));
+                            }
+                            // Handlers see the data source they run as
+                            assert_eq!(data_source::address(), event.address);
+                            assert!(data_source::context().get("factory").is_some());
+                            Ok(())
+                        })
+                    },
+                }],
+                calls: Vec::new(),
+                blocks: alloc::vec![BlockHandlerSpec {
+                    handler: "handlePoolInit",
+                    filter: BlockFilter::Once,
+                    run: |block| run_block(block, |_| ()),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_chain_dispatches_templates() {
+        let factory = Address::from([0xfa; 20]);
+        let pool = Address::from([0xb0; 20]);
+        let mut chain = Chain::new(chain_table(&factory));
+
+        chain
+            .run([
+                // Before the factory's start block
+                ChainBlock::new(create_block(9, 108)).log(pool_created(&factory, &Address::from([0xb1; 20]))),
+                // The pool pings in the block it is created in, before its
+                // creation, and processes it once the factory has
+                ChainBlock::new(create_block(10, 120))
+                    .log(ping(&pool, 1))
+                    .log(pool_created(&factory, &pool)),
+                // Pings of unknown contracts are ignored
+                ChainBlock::new(create_block(11, 132)).log(ping(&pool, 2)).log(ping(&factory, 3)),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            chain.history(),
+            [
+                (10, "handlePoolCreated"),
+                (10, "handlePing"),
+                (10, "handlePoolInit"),
+                (11, "handlePing"),
+            ]
+        );
+        assert_eq!(chain.template_instances("Pool"), alloc::vec![pool.clone()]);
+        assert!(created_data_sources().is_empty());
+
+        let err = chain.process(ChainBlock::new(create_block(12, 144)).log(ping(&pool, 0))).unwrap_err();
+        assert_eq!(err.to_string(), "handlePing failed at block 12: zero ping");
+    }
+
+    #[test]
+    fn test_created_data_sources() {
+        clear_created_data_sources();
+
+        data_source::create("Pool", &[String::from("0xb0")]);
+        let created = created_data_sources();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].template, "Pool");
+        assert!(created[0].context.is_none());
+
+        clear_created_data_sources();
+        assert!(created_data_sources().is_empty());
+    }
 }
@@ -5,16 +5,18 @@
 
 // Note: We use Vec instead of BTreeMap for EntityData to avoid complex
 // pointer arithmetic that triggers graph-node's memory sanitization.
+use alloc::borrow::Cow;
 use alloc::string::String;
 use alloc::vec::Vec;
 
 #[cfg(target_arch = "wasm32")]
 use crate::asc::AscPtr;
+use crate::handler::HandlerError;
 
 use alloc::string::ToString;
 
 /// A 20-byte Ethereum address.
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
 pub struct Address(pub [u8; 20]);
 
 impl Address {
@@ -1287,6 +1289,33 @@ impl From<u64> for BigInt {
     }
 }
 
+impl Default for BigInt {
+    fn default() -> Self {
+        BigInt::zero()
+    }
+}
+
+impl BigInt {
+    /// Convert to an `i32`, or `None` if the value is out of range.
+    pub fn to_i32(&self) -> Option<i32> {
+        let le = self.to_signed_bytes();
+        let negative = le.last().is_some_and(|b| b & 0x80 != 0);
+        let fill = if negative { 0xff } else { 0 };
+
+        let mut bytes = [fill; 4];
+        for (i, byte) in le.iter().enumerate() {
+            match bytes.get_mut(i) {
+                Some(slot) => *slot = *byte,
+                None if *byte == fill => {}
+                None => return None,
+            }
+        }
+
+        let value = i32::from_le_bytes(bytes);
+        ((value < 0) == negative).then_some(value)
+    }
+}
+
 // ============================================================================
 // BigDecimal - WASM implementation (backed by host calls)
 // ============================================================================
@@ -1939,6 +1968,64 @@ impl From<&BigInt> for BigDecimal {
     }
 }
 
+// ============================================================================
+// Timestamp
+// ============================================================================
+
+/// A point in time, as stored in fields of GraphQL type `Timestamp`.
+///
+/// graph-node represents timestamps as microseconds since the Unix epoch;
+/// block timestamps are in seconds, so use [`Timestamp::from_seconds`] or
+/// `Timestamp::from(&event.block)` to convert them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Create a timestamp from microseconds since the Unix epoch.
+    pub const fn from_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    /// Create a timestamp from milliseconds since the Unix epoch.
+    pub const fn from_millis(millis: i64) -> Self {
+        Self(millis.saturating_mul(1_000))
+    }
+
+    /// Create a timestamp from seconds since the Unix epoch.
+    pub const fn from_seconds(seconds: i64) -> Self {
+        Self(seconds.saturating_mul(1_000_000))
+    }
+
+    /// Create a timestamp from a block timestamp, in seconds.
+    ///
+    /// Saturates for values outside the `i64` range.
+    pub fn from_block_timestamp(seconds: &BigInt) -> Self {
+        let le = seconds.to_signed_bytes();
+        let negative = le.last().is_some_and(|b| b & 0x80 != 0);
+        if le.len() > 8 {
+            return Self::from_seconds(if negative { i64::MIN } else { i64::MAX });
+        }
+        let mut bytes = [if negative { 0xff } else { 0 }; 8];
+        bytes[..le.len()].copy_from_slice(&le);
+        Self::from_seconds(i64::from_le_bytes(bytes))
+    }
+
+    /// Microseconds since the Unix epoch.
+    pub const fn as_micros(&self) -> i64 {
+        self.0
+    }
+
+    /// Whole milliseconds since the Unix epoch.
+    pub const fn as_millis(&self) -> i64 {
+        self.0.div_euclid(1_000)
+    }
+
+    /// Whole seconds since the Unix epoch.
+    pub const fn as_seconds(&self) -> i64 {
+        self.0.div_euclid(1_000_000)
+    }
+}
+
 /// A value that can be stored in an entity field.
 #[derive(Clone, Debug)]
 pub enum Value {
@@ -1949,6 +2036,7 @@ pub enum Value {
     BigDecimal(BigDecimal),
     Bool(bool),
     Bytes(Bytes),
+    Timestamp(Timestamp),
     Array(Vec<Value>),
     Null,
 }
@@ -1986,6 +2074,14 @@ impl Value {
         }
     }
 
+    /// Try to get as a Timestamp.
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        match self {
+            Value::Timestamp(ts) => Some(*ts),
+            _ => None,
+        }
+    }
+
     /// Check if this value is null.
     pub fn is_null(&self) -> bool {
         matches!(self, Value::Null)
@@ -2026,6 +2122,13 @@ impl EntityData {
         self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
     }
 
+    /// Get the `id` field as the entity's ID type, or panic.
+    pub fn get_id<I: EntityId>(&self) -> &I {
+        self.get("id")
+            .and_then(I::from_value)
+            .expect("expected id field")
+    }
+
     /// Get a string field or panic.
     pub fn get_string(&self, key: &str) -> &str {
         self.get(key)
@@ -2101,6 +2204,11 @@ impl EntityData {
         })
     }
 
+    /// Get an optional Timestamp field.
+    pub fn get_timestamp_opt(&self, key: &str) -> Option<Timestamp> {
+        self.get(key).and_then(|v| v.as_timestamp())
+    }
+
     /// Iterate over all fields.
     pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
         self.fields.iter().map(|(k, v)| (k, v))
@@ -2184,6 +2292,20 @@ impl EntityData {
             .unwrap_or_default()
     }
 
+    /// Get an i64 array field.
+    pub fn get_int8_array(&self, key: &str) -> Vec<i64> {
+        self.get_array(key)
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| match v {
+                        Value::Int8(i) => Some(*i),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Get a bool array field.
     pub fn get_bool_array(&self, key: &str) -> Vec<bool> {
         self.get_array(key)
@@ -2197,24 +2319,160 @@ impl EntityData {
             })
             .unwrap_or_default()
     }
+
+    /// Get a Timestamp array field.
+    pub fn get_timestamp_array(&self, key: &str) -> Vec<Timestamp> {
+        self.get_array(key)
+            .map(|arr| arr.iter().filter_map(|v| v.as_timestamp()).collect())
+            .unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Entity IDs
+// ============================================================================
+
+/// The type of an entity's `id` field: `ID`/`String`, `Bytes` or `Int8`.
+///
+/// The ID is stored in the entity with its own value kind, but graph-node
+/// addresses entities by the ID's string form: the ID itself for strings,
+/// `0x`-prefixed hex for `Bytes` and decimal for `Int8`.
+pub trait EntityId: EntityKey<Self> + Sized {
+    /// Wrap the ID as a field value.
+    fn into_value(self) -> Value;
+
+    /// Borrow the ID from a field value of the matching kind.
+    fn from_value(value: &Value) -> Option<&Self>;
+}
+
+/// Anything that can address an entity whose ID type is `I`.
+///
+/// This lets `load` and friends take a `&str` for string IDs, a `Bytes` or
+/// `Address` for `Bytes` IDs and an `i64` for `Int8` IDs.
+pub trait EntityKey<I> {
+    /// The key graph-node stores the entity under.
+    fn to_key(&self) -> Cow<'_, str>;
+}
+
+impl EntityId for String {
+    fn into_value(self) -> Value {
+        Value::String(self)
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl EntityId for Bytes {
+    fn into_value(self) -> Value {
+        Value::Bytes(self)
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+impl EntityId for i64 {
+    fn into_value(self) -> Value {
+        Value::Int8(self)
+    }
+
+    fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+            Value::Int8(i) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl EntityKey<String> for String {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl EntityKey<String> for str {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl EntityKey<Bytes> for Bytes {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_hex())
+    }
+}
+
+impl EntityKey<Bytes> for Address {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_hex())
+    }
+}
+
+impl EntityKey<i64> for i64 {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+impl<I, K: EntityKey<I> + ?Sized> EntityKey<I> for &K {
+    fn to_key(&self) -> Cow<'_, str> {
+        (**self).to_key()
+    }
+}
+
+/// The store key of an ID held in a field value, whatever its ID type.
+///
+/// Used to match references against keys, e.g. when resolving `@derivedFrom`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn value_key(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::String(s) => Some(s.to_key()),
+        Value::Bytes(b) => Some(b.to_key()),
+        Value::Int8(i) => Some(i.to_key()),
+        _ => None,
+    }
 }
 
 /// Trait that all generated entity types implement.
 pub trait Entity: Sized {
+    /// The type of the entity's `id` field.
+    type Id: EntityId;
+
     /// The entity type name as it appears in the GraphQL schema.
     const ENTITY_TYPE: &'static str;
 
     /// Get the entity's ID.
-    fn id(&self) -> &str;
+    fn id(&self) -> &Self::Id;
 
     /// Save the entity to the store.
     fn save(&self);
 
     /// Load an entity from the store by ID.
-    fn load(id: &str) -> Option<Self>;
+    fn load(id: impl EntityKey<Self::Id>) -> Option<Self>;
+
+    /// Load an entity that must exist, for handlers returning a `Result`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut pair = Pair::try_load(&event.address.to_hex())?;
+    /// ```
+    fn try_load(id: impl EntityKey<Self::Id>) -> Result<Self, HandlerError> {
+        Self::load(&id)
+            .ok_or_else(|| HandlerError::entity_not_found(Self::ENTITY_TYPE, &id.to_key()))
+    }
 
     /// Remove an entity from the store.
-    fn remove(id: &str);
+    fn remove(id: impl EntityKey<Self::Id>);
 
     /// Check if an entity exists in the store without loading it.
     ///
@@ -2228,7 +2486,7 @@ pub trait Entity: Sized {
     ///     // Create the token...
     /// }
     /// ```
-    fn exists(id: &str) -> bool {
+    fn exists(id: impl EntityKey<Self::Id>) -> bool {
         Self::load(id).is_some()
     }
 
@@ -2236,7 +2494,7 @@ pub trait Entity: Sized {
     ///
     /// This is used by `load_or_create` to construct new entities.
     /// Generated entities implement this by delegating to `new()`.
-    fn create(id: impl Into<String>) -> Self;
+    fn create(id: impl Into<Self::Id>) -> Self;
 
     /// Load an entity if it exists, or create a new one with the given initializer.
     ///
@@ -2251,8 +2509,9 @@ pub trait Entity: Sized {
     ///     f.set_total_volume(BigDecimal::zero());
     /// });
     /// ```
-    fn load_or_create(id: &str, init: impl FnOnce(&mut Self)) -> Self {
-        match Self::load(id) {
+    fn load_or_create(id: impl Into<Self::Id>, init: impl FnOnce(&mut Self)) -> Self {
+        let id = id.into();
+        match Self::load(&id) {
             Some(entity) => entity,
             None => {
                 let mut entity = Self::create(id);
@@ -2275,7 +2534,7 @@ pub trait Entity: Sized {
     ///     p.set_tx_count(p.tx_count() + BigInt::from(1));
     /// });
     /// ```
-    fn update(id: &str, updater: impl FnOnce(&mut Self)) {
+    fn update(id: impl EntityKey<Self::Id>, updater: impl FnOnce(&mut Self)) {
         if let Some(mut entity) = Self::load(id) {
             updater(&mut entity);
             entity.save();
@@ -2295,8 +2554,9 @@ pub trait Entity: Sized {
     ///     t.set_tx_count(t.tx_count() + BigInt::from(1));
     /// });
     /// ```
-    fn upsert(id: &str, updater: impl FnOnce(&mut Self)) {
-        let mut entity = Self::load(id).unwrap_or_else(|| Self::create(id));
+    fn upsert(id: impl Into<Self::Id>, updater: impl FnOnce(&mut Self)) {
+        let id = id.into();
+        let mut entity = Self::load(&id).unwrap_or_else(|| Self::create(id));
         updater(&mut entity);
         entity.save();
     }
@@ -2445,4 +2705,66 @@ mod tests {
         let neg = BigInt::from_i32(-4);
         neg.sqrt();
     }
+
+    #[test]
+    fn test_timestamp_from_block_timestamp() {
+        let ts = Timestamp::from_block_timestamp(&BigInt::from_u64(1_700_000_000));
+        assert_eq!(ts.as_micros(), 1_700_000_000_000_000);
+        assert_eq!(ts.as_seconds(), 1_700_000_000);
+        assert_eq!(ts.as_millis(), 1_700_000_000_000);
+
+        let before_epoch = Timestamp::from_block_timestamp(&BigInt::from_i64(-1));
+        assert_eq!(before_epoch.as_seconds(), -1);
+
+        let huge = BigInt::from_string("100000000000000000000").unwrap();
+        assert_eq!(Timestamp::from_block_timestamp(&huge).as_micros(), i64::MAX);
+    }
+
+    #[test]
+    fn test_timestamp_entity_fields() {
+        let mut data = EntityData::new();
+        data.set("at", Value::Timestamp(Timestamp::from_millis(1_500)));
+        data.set(
+            "history",
+            Value::Array(vec![
+                Value::Timestamp(Timestamp::from_seconds(1)),
+                Value::Timestamp(Timestamp::from_seconds(2)),
+            ]),
+        );
+
+        assert_eq!(data.get_timestamp_opt("at"), Some(Timestamp::from_micros(1_500_000)));
+        assert_eq!(data.get_timestamp_opt("missing"), None);
+        assert_eq!(
+            data.get_timestamp_array("history"),
+            vec![Timestamp::from_seconds(1), Timestamp::from_seconds(2)]
+        );
+    }
+
+    #[test]
+    fn test_bigint_to_i32() {
+        assert_eq!(BigInt::from_i32(42).to_i32(), Some(42));
+        assert_eq!(BigInt::from_i32(-42).to_i32(), Some(-42));
+        assert_eq!(BigInt::from_i32(0).to_i32(), Some(0));
+        assert_eq!(BigInt::from_i32(i32::MAX).to_i32(), Some(i32::MAX));
+        assert_eq!(BigInt::from_i32(i32::MIN).to_i32(), Some(i32::MIN));
+        assert_eq!(BigInt::from_i64(i32::MAX as i64 + 1).to_i32(), None);
+        assert_eq!(BigInt::from_i64(i32::MIN as i64 - 1).to_i32(), None);
+    }
+
+    #[test]
+    fn test_entity_id_keys() {
+        let bytes = Bytes::from(vec![0xab, 0x01]);
+        assert_eq!(EntityKey::<Bytes>::to_key(&bytes), "0xab01");
+        let address = Address::from([0x11u8; 20]);
+        assert_eq!(EntityKey::<Bytes>::to_key(&address), address.to_hex());
+        assert_eq!(EntityKey::<i64>::to_key(&-7i64), "-7");
+        assert_eq!(EntityKey::<String>::to_key("token-1"), "token-1");
+
+        let mut data = EntityData::new();
+        data.set("id", bytes.clone().into_value());
+        assert_eq!(data.get_id::<Bytes>(), &bytes);
+        assert_eq!(value_key(data.get("id").unwrap()).unwrap(), "0xab01");
+        assert_eq!(value_key(&Value::Int8(12)).unwrap(), "12");
+        assert!(value_key(&Value::Bool(true)).is_none());
+    }
 }
@@ -0,0 +1,1882 @@
+//! Embedded WASM host emulator for high-fidelity tests.
+//!
+//! Native tests call handlers as plain Rust functions, so they never exercise
+//! the AssemblyScript memory layouts, the allocator, or the host import
+//! surface. This module loads the compiled `build/subgraph.wasm` into an
+//! interpreter and plays graph-node's part: it serialises test events, calls
+//! and blocks into guest memory in the layout graph-node uses, invokes the
+//! exported handler, and services every host import the runtime declares.
+//!
+//! Host imports are backed by the same mocks as native mode (`crate::testing`),
+//! so store assertions, `mock_call` and `mock_ipfs_cat` work unchanged.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use yogurt_runtime::testing::*;
+//! use yogurt_runtime::wasm_host::WasmHost;
+//!
+//! #[test]
+//! fn transfer_in_wasm() {
+//!     clear_store();
+//!     let mut host = WasmHost::from_env().unwrap();
+//!     let event = EventBuilder::<TransferParams>::new().params(params).build();
+//!     host.handle_event("handleTransfer", &event).unwrap();
+//!     assert_entity_exists::<Transfer>("0x00...-0");
+//! }
+//! ```
+//!
+//! Tests need not name the host: while [`WASM_MODULE_ENV`] is set, calling a
+//! `#[handler]` function runs its export in a host kept per test thread (see
+//! [`with_env_host`]) instead of its Rust body, so native tests run against
+//! the module unchanged.
+//!
+//! Modules built for the Rust ABI (`language: wasm/rust`, see [`crate::rust_abi`])
+//! are detected by their `yogurt_abi_version` export. For those, handler
+//! arguments and `yogurt.store.*` traffic use the compact message encoding,
+//! and the remaining imports keep their AssemblyScript layouts.
+//!
+//! `yogurt test --wasm` builds nothing itself: it expects `yogurt build` to have
+//! produced the module and points [`WASM_MODULE_ENV`] at it.
+//!
+//! Modules built with the runtime's `alloc-profile` feature export their
+//! allocation counters. The host then attributes allocations to each handler
+//! it runs; see [`WasmHost::alloc_profiles`].
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use wasmi::{
+    AsContext, AsContextMut, Caller, Engine, Error, Extern, Instance, Linker, Memory, Module,
+    Store, TypedFunc,
+};
+
+use crate::allocator::{AllocProfile, PROFILE_WORDS};
+use crate::asc_image::{AscError, AscHeap};
+use crate::ethereum::abi::{self, ParamType};
+use crate::ethereum::{Block, Call, Event, ToEventParams};
+use crate::handler::HandlerError;
+use crate::log::Level;
+use crate::rust_abi::{self, Decode, Encode};
+use crate::testing;
+use crate::types::{Address, BigDecimal, BigInt, Bytes, EntityData, value_key};
+
+/// Environment variable `yogurt test --wasm` sets to the compiled module path.
+pub const WASM_MODULE_ENV: &str = "YOGURT_WASM_MODULE";
+
+/// Environment variable `yogurt test --wasm --alloc-profile` sets to have each
+/// host print its allocation report when dropped.
+pub const ALLOC_PROFILE_ENV: &str = "YOGURT_ALLOC_PROFILE";
+
+type HostResult<T> = Result<T, Error>;
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Errors raised while loading a module or running a handler.
+#[derive(Debug)]
+pub enum WasmHostError {
+    /// [`WASM_MODULE_ENV`] is not set — tests were not started via `yogurt test --wasm`.
+    ModuleNotConfigured,
+    /// The module file could not be read.
+    Io(std::io::Error),
+    /// The module lacks a required export (memory, `__new`, or the handler).
+    MissingExport(String),
+    /// The module was built for a Rust ABI version this host does not speak.
+    UnsupportedAbiVersion(u32),
+    /// Compilation, linking, or a trap raised while running the guest.
+    Wasm(Error),
+}
+
+impl fmt::Display for WasmHostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WasmHostError::ModuleNotConfigured => write!(
+                f,
+                "{} is not set; run the tests with `yogurt test --wasm`",
+                WASM_MODULE_ENV
+            ),
+            WasmHostError::Io(e) => write!(f, "failed to read WASM module: {}", e),
+            WasmHostError::MissingExport(name) => {
+                write!(f, "WASM module does not export `{}`", name)
+            }
+            WasmHostError::UnsupportedAbiVersion(version) => write!(
+                f,
+                "WASM module uses Rust ABI version {}, expected {}",
+                version,
+                rust_abi::ABI_VERSION
+            ),
+            WasmHostError::Wasm(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for WasmHostError {}
+
+impl From<std::io::Error> for WasmHostError {
+    fn from(e: std::io::Error) -> Self {
+        WasmHostError::Io(e)
+    }
+}
+
+impl From<Error> for WasmHostError {
+    fn from(e: Error) -> Self {
+        WasmHostError::Wasm(e)
+    }
+}
+
+// ============================================================================
+// Host State
+// ============================================================================
+
+/// A `log.log` call made by the guest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub level: Level,
+    pub message: String,
+}
+
+/// A `dataSource.create` / `dataSource.createWithContext` call made by the guest.
+#[derive(Clone, Debug)]
+pub struct CreatedDataSource {
+    pub name: String,
+    pub params: Vec<String>,
+    pub context: Option<EntityData>,
+}
+
+#[derive(Default)]
+struct HostState {
+    memory: Option<Memory>,
+    new: Option<TypedFunc<(i32, i32), i32>>,
+    /// `yogurt_alloc`, bound only for Rust ABI modules.
+    message_alloc: Option<TypedFunc<i32, i32>>,
+    logs: Vec<LogEntry>,
+    created_data_sources: Vec<CreatedDataSource>,
+}
+
+// ============================================================================
+// WasmHost
+// ============================================================================
+
+/// An instantiated subgraph module with graph-node's host imports linked in.
+pub struct WasmHost {
+    store: Store<HostState>,
+    instance: Instance,
+    /// Address of the guest's allocation counters, if it exports them.
+    alloc_counters: Option<u32>,
+    alloc_profiles: BTreeMap<String, AllocProfile>,
+}
+
+impl WasmHost {
+    /// Load and instantiate a module from disk.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WasmHostError> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Load the module named by [`WASM_MODULE_ENV`].
+    pub fn from_env() -> Result<Self, WasmHostError> {
+        let path = std::env::var_os(WASM_MODULE_ENV).ok_or(WasmHostError::ModuleNotConfigured)?;
+        Self::load(path)
+    }
+
+    /// Instantiate a module from its binary (or text, in tests) form.
+    ///
+    /// Fails if the module imports anything graph-node does not provide.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WasmHostError> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, bytes)?;
+        let mut store = Store::new(&engine, HostState::default());
+        let mut linker = Linker::<HostState>::new(&engine);
+        link_imports(&mut linker)?;
+
+        let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| WasmHostError::MissingExport("memory".into()))?;
+        let new = instance
+            .get_typed_func::<(i32, i32), i32>(&store, "__new")
+            .map_err(|_| WasmHostError::MissingExport("__new".into()))?;
+        store.data_mut().memory = Some(memory);
+        store.data_mut().new = Some(new);
+
+        if let Ok(version) = instance.get_typed_func::<(), i32>(&store, "yogurt_abi_version") {
+            let version = version.call(&mut store, ())? as u32;
+            if version != rust_abi::ABI_VERSION {
+                return Err(WasmHostError::UnsupportedAbiVersion(version));
+            }
+            let alloc = instance
+                .get_typed_func::<i32, i32>(&store, "yogurt_alloc")
+                .map_err(|_| WasmHostError::MissingExport("yogurt_alloc".into()))?;
+            store.data_mut().message_alloc = Some(alloc);
+        }
+
+        // graph-node calls `_start` once after instantiation when it is exported
+        if let Ok(start) = instance.get_typed_func::<(), ()>(&store, "_start") {
+            start.call(&mut store, ())?;
+        }
+
+        let alloc_counters =
+            match instance.get_typed_func::<(), i32>(&store, "yogurt_alloc_profile") {
+                Ok(counters) => Some(counters.call(&mut store, ())? as u32),
+                Err(_) => None,
+            };
+
+        Ok(Self {
+            store,
+            instance,
+            alloc_counters,
+            alloc_profiles: BTreeMap::new(),
+        })
+    }
+
+    /// Run an event handler.
+    pub fn handle_event<P: ToEventParams>(
+        &mut self,
+        handler: &str,
+        event: &Event<P>,
+    ) -> Result<(), WasmHostError> {
+        let rust_abi = self.uses_rust_abi();
+        self.invoke(handler, |store| {
+            if rust_abi {
+                write_message(store, event)
+            } else {
+                store.write_event(event)
+            }
+        })
+    }
+
+    /// Run a call handler.
+    pub fn handle_call<I: ToEventParams, O: ToEventParams>(
+        &mut self,
+        handler: &str,
+        call: &Call<I, O>,
+    ) -> Result<(), WasmHostError> {
+        let rust_abi = self.uses_rust_abi();
+        self.invoke(handler, |store| {
+            if rust_abi {
+                write_message(store, call)
+            } else {
+                store.write_call(call)
+            }
+        })
+    }
+
+    /// Run a block handler.
+    pub fn handle_block(&mut self, handler: &str, block: &Block) -> Result<(), WasmHostError> {
+        let rust_abi = self.uses_rust_abi();
+        self.invoke(handler, |store| {
+            if rust_abi {
+                write_message(store, block)
+            } else {
+                store.write_block(block)
+            }
+        })
+    }
+
+    /// Run a file data source handler with the file's contents.
+    pub fn handle_file(&mut self, handler: &str, content: &Bytes) -> Result<(), WasmHostError> {
+        let rust_abi = self.uses_rust_abi();
+        self.invoke(handler, |store| {
+            if rust_abi {
+                write_message(store, content)
+            } else {
+                store.write_uint8_array(content.as_slice())
+            }
+        })
+    }
+
+    /// Whether the module was built for the Rust ABI rather than AssemblyScript.
+    pub fn uses_rust_abi(&self) -> bool {
+        self.store.data().message_alloc.is_some()
+    }
+
+    /// Messages logged by the guest so far, in order.
+    pub fn logs(&self) -> &[LogEntry] {
+        &self.store.data().logs
+    }
+
+    /// Data source templates instantiated by the guest so far, in order.
+    pub fn created_data_sources(&self) -> &[CreatedDataSource] {
+        &self.store.data().created_data_sources
+    }
+
+    /// Size of the guest's linear memory in bytes.
+    pub fn memory_size(&self) -> u64 {
+        let memory = self.store.data().memory.expect("memory is bound on instantiation");
+        memory.data(&self.store).len() as u64
+    }
+
+    /// Where the guest allocates next: the address `__new` returns for an
+    /// empty object.
+    ///
+    /// The runtime's arena releases the object together with the next
+    /// handler's argument, so sampling between handlers does not move the
+    /// heap. An arena that stays flat returns the same address every time.
+    pub fn heap_top(&mut self) -> Result<u32, WasmHostError> {
+        Ok(self.store.alloc(0, 0)?)
+    }
+
+    /// Allocations per handler, for modules built with `alloc-profile`.
+    ///
+    /// Each handler's profile covers the argument the host allocated for it
+    /// and everything the handler allocated, over all its invocations.
+    pub fn alloc_profiles(&self) -> &BTreeMap<String, AllocProfile> {
+        &self.alloc_profiles
+    }
+
+    /// A printable report of [`WasmHost::alloc_profiles`], or `None` if the
+    /// module was built without the `alloc-profile` feature.
+    pub fn alloc_report(&self) -> Option<String> {
+        self.alloc_counters?;
+        let mut report = String::new();
+        for (handler, profile) in &self.alloc_profiles {
+            report.push_str(&format!("{}: {}", handler, profile));
+        }
+        Some(report)
+    }
+
+    /// Write the handler's argument and run the handler.
+    fn invoke(
+        &mut self,
+        handler: &str,
+        write_arg: impl FnOnce(&mut Store<HostState>) -> HostResult<u32>,
+    ) -> Result<(), WasmHostError> {
+        let func = self
+            .instance
+            .get_typed_func::<i32, ()>(&self.store, handler)
+            .map_err(|_| WasmHostError::MissingExport(handler.to_string()))?;
+        let before = self.read_alloc_counters()?;
+        let ptr = write_arg(&mut self.store)?;
+        func.call(&mut self.store, ptr as i32)?;
+
+        if let (Some(before), Some(after)) = (before, self.read_alloc_counters()?) {
+            self.alloc_profiles
+                .entry(handler.to_string())
+                .or_default()
+                .merge(&after.since(&before));
+        }
+        Ok(())
+    }
+
+    fn read_alloc_counters(&self) -> HostResult<Option<AllocProfile>> {
+        let Some(ptr) = self.alloc_counters else {
+            return Ok(None);
+        };
+        let bytes = self.store.read_raw(ptr, (PROFILE_WORDS * 4) as u32)?;
+        let words: Vec<u32> = bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        Ok(Some(AllocProfile::from_words(&words)))
+    }
+}
+
+impl Drop for WasmHost {
+    fn drop(&mut self) {
+        if std::env::var_os(ALLOC_PROFILE_ENV).is_some()
+            && let Some(report) = self.alloc_report()
+        {
+            eprint!("{}", report);
+        }
+    }
+}
+
+// ============================================================================
+// Handler Functions
+// ============================================================================
+
+thread_local! {
+    /// The host `#[handler]` functions run in on this thread, loaded from
+    /// [`WASM_MODULE_ENV`] on first use.
+    static ENV_HOST: RefCell<Option<WasmHost>> = const { RefCell::new(None) };
+
+    /// Whether the last handler trapped in [`ENV_HOST`], leaving the
+    /// instance in whatever state the trap cut short.
+    static ENV_HOST_TRAPPED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` on the host that `#[handler]` functions called on this thread run
+/// in under `yogurt test --wasm`, loading the module named by
+/// [`WASM_MODULE_ENV`] on first use.
+///
+/// Each test runs on its own thread, so the host lives as long as the test:
+/// its logs, created data sources and allocation profiles cover every
+/// handler the test called. A handler that traps is the exception: the next
+/// handler runs in a fresh instance, as graph-node would not reuse one.
+///
+/// ```ignore
+/// handle_transfer(event);
+/// let logs = with_env_host(|host| host.logs().to_vec()).unwrap();
+/// ```
+pub fn with_env_host<R>(f: impl FnOnce(&mut WasmHost) -> R) -> Result<R, WasmHostError> {
+    ENV_HOST.with(|host| {
+        let mut host = host.borrow_mut();
+        let host = match host.as_mut() {
+            Some(host) => host,
+            None => host.insert(WasmHost::from_env()?),
+        };
+        Ok(f(host))
+    })
+}
+
+/// Run the export `handler` on `arg` in this thread's host.
+///
+/// A trap that follows the critical log of [`handler::finish`] is the
+/// handler returning `Err`, and comes back as that error. Any other failure
+/// is described by the critical log the guest left, if any.
+///
+/// [`handler::finish`]: crate::handler::finish
+pub(crate) fn run_env_handler<A: testing::HandlerArg>(
+    handler: &str,
+    arg: &A,
+) -> Result<Result<(), HandlerError>, String> {
+    if ENV_HOST_TRAPPED.replace(false) {
+        ENV_HOST.with(|host| host.borrow_mut().take());
+    }
+    let result = with_env_host(|host| {
+        let logged = host.logs().len();
+        let Err(e) = arg.run_in(host, handler) else {
+            return Ok(Ok(()));
+        };
+        ENV_HOST_TRAPPED.set(true);
+        let Some(log) = host.logs()[logged..].iter().find(|log| log.level == Level::Critical) else {
+            return Err(format!("{} failed in the WASM module: {}", handler, e));
+        };
+        match log.message.strip_prefix(handler).and_then(|rest| rest.strip_prefix(" failed: ")) {
+            Some(message) => Ok(Err(HandlerError::new(message))),
+            None => Err(format!("{} failed in the WASM module: {}", handler, log.message)),
+        }
+    });
+    result.map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// AssemblyScript Memory Access
+// ============================================================================
+
+/// Guest memory as an AssemblyScript heap.
+///
+/// Implemented for anything that can reach the store (the `Store` itself
+/// outside calls, the `Caller` inside host imports). Allocation goes through
+/// the guest's own `__new`, so the runtime's allocator is exercised too.
+impl<C: AsContextMut<Data = HostState>> AscHeap for C {
+    type Error = Error;
+
+    fn alloc(&mut self, size: u32, class_id: u32) -> HostResult<u32> {
+        let new = self
+            .as_context()
+            .data()
+            .new
+            .expect("__new is bound at instantiation");
+        Ok(new.call(&mut *self, (size as i32, class_id as i32))? as u32)
+    }
+
+    fn read_raw(&self, ptr: u32, len: u32) -> HostResult<Vec<u8>> {
+        let mut buf = vec![0; len as usize];
+        guest_memory(self)
+            .read(self, ptr as usize, &mut buf)
+            .map_err(|_| out_of_bounds(ptr, len))?;
+        Ok(buf)
+    }
+
+    fn write_raw(&mut self, ptr: u32, data: &[u8]) -> HostResult<()> {
+        guest_memory(self)
+            .write(&mut *self, ptr as usize, data)
+            .map_err(|_| out_of_bounds(ptr, data.len() as u32))
+    }
+}
+
+fn guest_memory(ctx: &impl AsContext<Data = HostState>) -> Memory {
+    ctx.as_context()
+        .data()
+        .memory
+        .expect("memory is bound at instantiation")
+}
+
+impl From<AscError> for Error {
+    fn from(e: AscError) -> Self {
+        Error::new(e.to_string())
+    }
+}
+
+fn out_of_bounds(ptr: u32, len: u32) -> Error {
+    Error::new(format!(
+        "out of bounds memory access: {} bytes at {:#x}",
+        len, ptr
+    ))
+}
+
+// ============================================================================
+// Rust ABI Messages
+// ============================================================================
+
+/// Encode `value` into a `[len][payload]` message allocated by `yogurt_alloc`.
+fn write_message<T: Encode + ?Sized>(
+    ctx: &mut impl AsContextMut<Data = HostState>,
+    value: &T,
+) -> HostResult<u32> {
+    let payload = rust_abi::to_bytes(value);
+    let alloc = ctx
+        .as_context()
+        .data()
+        .message_alloc
+        .ok_or_else(|| Error::new("module does not use the Rust ABI"))?;
+    let ptr = alloc.call(&mut *ctx, 4 + payload.len() as i32)? as u32;
+    ctx.write_raw(ptr, &(payload.len() as u32).to_le_bytes())?;
+    ctx.write_raw(ptr + 4, &payload)?;
+    Ok(ptr)
+}
+
+/// Decode the message at `ptr`.
+fn read_message<T: Decode>(ctx: &impl AsContextMut<Data = HostState>, ptr: u32) -> HostResult<T> {
+    let len = ctx.read_u32(ptr)?;
+    let payload = ctx.read_raw(ptr + 4, len)?;
+    rust_abi::from_bytes(&payload)
+        .map_err(|e| Error::new(format!("malformed Rust ABI message: {}", e)))
+}
+
+/// Bitcoin-alphabet base58, as used for IPFS CIDs.
+fn to_base58(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    // Base58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut out = "1".repeat(zeros);
+    out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    out
+}
+
+// ============================================================================
+// Host Imports
+// ============================================================================
+
+type Ctx<'a> = Caller<'a, HostState>;
+
+fn link_imports(linker: &mut Linker<HostState>) -> HostResult<()> {
+    link_store(linker)?;
+    link_rust_abi_store(linker)?;
+    link_ethereum(linker)?;
+    link_type_conversion(linker)?;
+    link_big_int(linker)?;
+    link_big_decimal(linker)?;
+    link_json(linker)?;
+    link_ipfs(linker)?;
+    link_data_source(linker)?;
+    link_misc(linker)?;
+    Ok(())
+}
+
+fn link_store(linker: &mut Linker<HostState>) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        "store.get",
+        |mut c: Ctx, entity_type: i32, id: i32| -> HostResult<i32> {
+            let entity_type = c.read_string(entity_type as u32)?;
+            let id = c.read_string(id as u32)?;
+            match testing::store_get(&entity_type, &id) {
+                Some(data) => Ok(c.write_entity(&data)? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "store.get_in_block",
+        |mut c: Ctx, entity_type: i32, id: i32| -> HostResult<i32> {
+            let entity_type = c.read_string(entity_type as u32)?;
+            let id = c.read_string(id as u32)?;
+            match testing::store_get_in_block(&entity_type, &id) {
+                Some(data) => Ok(c.write_entity(&data)? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "store.loadRelated",
+        |mut c: Ctx, entity_type: i32, id: i32, field: i32| -> HostResult<i32> {
+            let entity_type = c.read_string(entity_type as u32)?;
+            let id = c.read_string(id as u32)?;
+            let field = c.read_string(field as u32)?;
+            let related = testing::store_load_related(&entity_type, &id, &field);
+            Ok(c.write_entity_array(&related)? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "store.set",
+        |c: Ctx, entity_type: i32, id: i32, data: i32| -> HostResult<()> {
+            let entity_type = c.read_string(entity_type as u32)?;
+            let id = c.read_string(id as u32)?;
+            let data = c.read_entity(data as u32)?;
+            check_entity_id(&id, &data)?;
+            testing::store_set(&entity_type, &id, &data);
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "store.remove",
+        |c: Ctx, entity_type: i32, id: i32| -> HostResult<()> {
+            let entity_type = c.read_string(entity_type as u32)?;
+            let id = c.read_string(id as u32)?;
+            testing::store_remove(&entity_type, &id);
+            Ok(())
+        },
+    )?;
+
+    Ok(())
+}
+
+/// graph-node rejects entities whose `id` field disagrees with the key.
+fn check_entity_id(id: &str, data: &EntityData) -> HostResult<()> {
+    if let Some(field) = data.get("id").and_then(value_key)
+        && field != id
+    {
+        return Err(Error::new(format!(
+            "Value of id attribute '{}' conflicts with ID passed to store.set(): '{}'",
+            field, id
+        )));
+    }
+    Ok(())
+}
+
+/// The `yogurt.store.*` imports used by Rust ABI modules.
+fn link_rust_abi_store(linker: &mut Linker<HostState>) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        "yogurt.store.get",
+        |mut c: Ctx, request: i32| -> HostResult<i32> {
+            let (entity_type, id): (String, String) = read_message(&c, request as u32)?;
+            match testing::store_get(&entity_type, &id) {
+                Some(data) => Ok(write_message(&mut c, &data)? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "yogurt.store.get_in_block",
+        |mut c: Ctx, request: i32| -> HostResult<i32> {
+            let (entity_type, id): (String, String) = read_message(&c, request as u32)?;
+            match testing::store_get_in_block(&entity_type, &id) {
+                Some(data) => Ok(write_message(&mut c, &data)? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "yogurt.store.load_related",
+        |mut c: Ctx, request: i32| -> HostResult<i32> {
+            let (entity_type, id, field): (String, String, String) =
+                read_message(&c, request as u32)?;
+            let related = testing::store_load_related(&entity_type, &id, &field);
+            Ok(write_message(&mut c, &related)? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "yogurt.store.set",
+        |c: Ctx, request: i32| -> HostResult<()> {
+            let (entity_type, id, data): (String, String, EntityData) =
+                read_message(&c, request as u32)?;
+            check_entity_id(&id, &data)?;
+            testing::store_set(&entity_type, &id, &data);
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "yogurt.store.remove",
+        |c: Ctx, request: i32| -> HostResult<()> {
+            let (entity_type, id): (String, String) = read_message(&c, request as u32)?;
+            testing::store_remove(&entity_type, &id);
+            Ok(())
+        },
+    )?;
+
+    Ok(())
+}
+
+fn link_ethereum(linker: &mut Linker<HostState>) -> HostResult<()> {
+    // SmartContractCall: {contract_name, contract_address, function_name,
+    //                     function_signature, function_params}
+    linker.func_wrap(
+        "env",
+        "ethereum.call",
+        |mut c: Ctx, call: i32| -> HostResult<i32> {
+            let call = call as u32;
+            let address = Address::from(c.read_bytes(c.read_u32(call + 4)?)?.as_slice());
+            let signature = c.read_string(c.read_u32(call + 12)?)?;
+            match testing::execute_mock_call(&address, &signature) {
+                Ok(tokens) => Ok(c.write_token_array(&tokens)? as i32),
+                // graph-node returns null without the revert data
+                Err(_) => Ok(0),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "ethereum.encode",
+        |mut c: Ctx, token: i32| -> HostResult<i32> {
+            let token = c.read_token(token as u32)?;
+            match crate::ethereum::try_encode(&[token]) {
+                Some(encoded) => Ok(c.write_uint8_array(encoded.as_slice())? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "ethereum.decode",
+        |mut c: Ctx, types: i32, data: i32| -> HostResult<i32> {
+            let types = c.read_string(types as u32)?;
+            let data = c.read_bytes(data as u32)?;
+            let token = ParamType::parse(&types).and_then(|ty| abi::decode_value(&ty, &data));
+            match token {
+                Some(token) => Ok(c.write_token(&token)? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    Ok(())
+}
+
+fn link_type_conversion(linker: &mut Linker<HostState>) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        "typeConversion.bytesToString",
+        |mut c: Ctx, bytes: i32| -> HostResult<i32> {
+            let bytes = c.read_bytes(bytes as u32)?;
+            let s = String::from_utf8_lossy(&bytes);
+            Ok(c.write_string(s.trim_end_matches('\u{0}'))? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "typeConversion.bytesToHex",
+        |mut c: Ctx, bytes: i32| -> HostResult<i32> {
+            let hex = Bytes::from_vec(c.read_bytes(bytes as u32)?).to_hex();
+            Ok(c.write_string(&hex)? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "typeConversion.bigIntToString",
+        |mut c: Ctx, n: i32| -> HostResult<i32> {
+            let n = c.read_big_int(n as u32)?;
+            Ok(c.write_string(&n.to_string())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "typeConversion.bigIntToHex",
+        |mut c: Ctx, n: i32| -> HostResult<i32> {
+            let n = c.read_big_int(n as u32)?;
+            Ok(c.write_string(&n.to_hex())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "typeConversion.stringToH160",
+        |mut c: Ctx, s: i32| -> HostResult<i32> {
+            let s = c.read_string(s as u32)?;
+            let address = Address::from_string(&s).ok_or_else(|| {
+                Error::new(format!("Failed to convert string to Address/H160: '{}'", s))
+            })?;
+            Ok(c.write_uint8_array(address.as_bytes())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "typeConversion.bytesToBase58",
+        |mut c: Ctx, bytes: i32| -> HostResult<i32> {
+            let bytes = c.read_bytes(bytes as u32)?;
+            Ok(c.write_string(&to_base58(&bytes))? as i32)
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Link a `(BigInt, BigInt) -> BigInt` import.
+fn link_big_int_op(
+    linker: &mut Linker<HostState>,
+    name: &str,
+    op: fn(&BigInt, &BigInt) -> HostResult<BigInt>,
+) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        name,
+        move |mut c: Ctx, a: i32, b: i32| -> HostResult<i32> {
+            let a = c.read_big_int(a as u32)?;
+            let b = c.read_big_int(b as u32)?;
+            Ok(c.write_big_int(&op(&a, &b)?)? as i32)
+        },
+    )?;
+    Ok(())
+}
+
+/// Link a `(BigInt, u8) -> BigInt` import.
+fn link_big_int_u8_op(
+    linker: &mut Linker<HostState>,
+    name: &str,
+    op: fn(&BigInt, u8) -> BigInt,
+) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        name,
+        move |mut c: Ctx, a: i32, b: i32| -> HostResult<i32> {
+            let a = c.read_big_int(a as u32)?;
+            Ok(c.write_big_int(&op(&a, b as u8))? as i32)
+        },
+    )?;
+    Ok(())
+}
+
+fn link_big_int(linker: &mut Linker<HostState>) -> HostResult<()> {
+    link_big_int_op(linker, "bigInt.plus", |a, b| Ok(a.plus(b)))?;
+    link_big_int_op(linker, "bigInt.minus", |a, b| Ok(a.minus(b)))?;
+    link_big_int_op(linker, "bigInt.times", |a, b| Ok(a.times(b)))?;
+    link_big_int_op(linker, "bigInt.dividedBy", |a, b| {
+        if b.is_zero() {
+            return Err(Error::new("attempted to divide BigInt by zero"));
+        }
+        Ok(a.divided_by(b))
+    })?;
+    link_big_int_op(linker, "bigInt.mod", |a, b| {
+        if b.is_zero() {
+            return Err(Error::new("attempted to compute BigInt modulo zero"));
+        }
+        Ok(a.modulo(b))
+    })?;
+    link_big_int_op(linker, "bigInt.bitOr", |a, b| Ok(a.bit_or(b)))?;
+    link_big_int_op(linker, "bigInt.bitAnd", |a, b| Ok(a.bit_and(b)))?;
+    link_big_int_u8_op(linker, "bigInt.pow", BigInt::pow)?;
+    link_big_int_u8_op(linker, "bigInt.leftShift", BigInt::left_shift)?;
+    link_big_int_u8_op(linker, "bigInt.rightShift", BigInt::right_shift)?;
+
+    linker.func_wrap(
+        "env",
+        "bigInt.fromString",
+        |mut c: Ctx, s: i32| -> HostResult<i32> {
+            let s = c.read_string(s as u32)?;
+            let n = BigInt::from_string(&s)
+                .ok_or_else(|| Error::new(format!("failed to parse BigInt from '{}'", s)))?;
+            Ok(c.write_big_int(&n)? as i32)
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Link a `(BigDecimal, BigDecimal) -> BigDecimal` import.
+fn link_big_decimal_op(
+    linker: &mut Linker<HostState>,
+    name: &str,
+    op: fn(&BigDecimal, &BigDecimal) -> HostResult<BigDecimal>,
+) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        name,
+        move |mut c: Ctx, a: i32, b: i32| -> HostResult<i32> {
+            let a = c.read_big_decimal(a as u32)?;
+            let b = c.read_big_decimal(b as u32)?;
+            Ok(c.write_big_decimal(&op(&a, &b)?)? as i32)
+        },
+    )?;
+    Ok(())
+}
+
+fn link_big_decimal(linker: &mut Linker<HostState>) -> HostResult<()> {
+    link_big_decimal_op(linker, "bigDecimal.plus", |a, b| Ok(a.plus(b)))?;
+    link_big_decimal_op(linker, "bigDecimal.minus", |a, b| Ok(a.minus(b)))?;
+    link_big_decimal_op(linker, "bigDecimal.times", |a, b| Ok(a.times(b)))?;
+    link_big_decimal_op(linker, "bigDecimal.dividedBy", |a, b| {
+        if b.is_zero() {
+            return Err(Error::new("attempted to divide BigDecimal by zero"));
+        }
+        Ok(a.divided_by(b))
+    })?;
+
+    linker.func_wrap(
+        "env",
+        "bigDecimal.equals",
+        |c: Ctx, a: i32, b: i32| -> HostResult<i32> {
+            let a = c.read_big_decimal(a as u32)?;
+            let b = c.read_big_decimal(b as u32)?;
+            Ok((a == b) as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "bigDecimal.toString",
+        |mut c: Ctx, d: i32| -> HostResult<i32> {
+            let d = c.read_big_decimal(d as u32)?;
+            Ok(c.write_string(&d.to_string())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "bigDecimal.fromString",
+        |mut c: Ctx, s: i32| -> HostResult<i32> {
+            let s = c.read_string(s as u32)?;
+            Ok(c.write_big_decimal(&BigDecimal::from_string(&s))? as i32)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn link_json(linker: &mut Linker<HostState>) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        "json.fromBytes",
+        |mut c: Ctx, bytes: i32| -> HostResult<i32> {
+            let bytes = c.read_bytes(bytes as u32)?;
+            let value: serde_json::Value = serde_json::from_slice(&bytes)
+                .map_err(|e| Error::new(format!("Failed to parse JSON from byte array: {}", e)))?;
+            Ok(c.write_json(&value)? as i32)
+        },
+    )?;
+
+    linker.func_wrap("env", "json.toI64", |c: Ctx, s: i32| -> HostResult<i64> {
+        let s = c.read_string(s as u32)?;
+        s.parse()
+            .map_err(|_| Error::new(format!("JSON number `{}` cannot be represented as i64", s)))
+    })?;
+
+    linker.func_wrap("env", "json.toU64", |c: Ctx, s: i32| -> HostResult<u64> {
+        let s = c.read_string(s as u32)?;
+        s.parse()
+            .map_err(|_| Error::new(format!("JSON number `{}` cannot be represented as u64", s)))
+    })?;
+
+    linker.func_wrap("env", "json.toF64", |c: Ctx, s: i32| -> HostResult<f64> {
+        let s = c.read_string(s as u32)?;
+        s.parse()
+            .map_err(|_| Error::new(format!("JSON number `{}` cannot be represented as f64", s)))
+    })?;
+
+    linker.func_wrap(
+        "env",
+        "json.toBigInt",
+        |mut c: Ctx, s: i32| -> HostResult<i32> {
+            let s = c.read_string(s as u32)?;
+            let n = BigInt::from_string(&s)
+                .ok_or_else(|| Error::new(format!("JSON number `{}` is not an integer", s)))?;
+            Ok(c.write_big_int(&n)? as i32)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn link_ipfs(linker: &mut Linker<HostState>) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        "ipfs.cat",
+        |mut c: Ctx, hash: i32| -> HostResult<i32> {
+            let hash = c.read_string(hash as u32)?;
+            match testing::get_mock_ipfs_content(&hash) {
+                Some(content) => Ok(c.write_uint8_array(&content)? as i32),
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    // Streams newline-delimited JSON values from the mocked file into the
+    // exported callback, as graph-node does for `ipfs.map`.
+    linker.func_wrap(
+        "env",
+        "ipfs.map",
+        |mut c: Ctx, hash: i32, callback: i32, user_data: i32, _flags: i32| -> HostResult<()> {
+            let hash = c.read_string(hash as u32)?;
+            let callback = c.read_string(callback as u32)?;
+            let user_data = c.read_value(user_data as u32)?;
+            let content = testing::get_mock_ipfs_content(&hash)
+                .ok_or_else(|| Error::new(format!("ipfs.map: no content mocked for `{}`", hash)))?;
+            let func = c
+                .get_export(&callback)
+                .and_then(Extern::into_func)
+                .ok_or_else(|| {
+                    Error::new(format!("ipfs.map: callback `{}` is not exported", callback))
+                })?
+                .typed::<(i32, i32), ()>(&c)?;
+
+            for line in content.split(|&b| b == b'\n') {
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                let value: serde_json::Value = serde_json::from_slice(line).map_err(|e| {
+                    Error::new(format!("ipfs.map: invalid JSON in `{}`: {}", hash, e))
+                })?;
+                let value = c.write_json(&value)?;
+                let user_data = c.write_value(&user_data)?;
+                func.call(&mut c, (value as i32, user_data as i32))?;
+            }
+            Ok(())
+        },
+    )?;
+
+    Ok(())
+}
+
+fn link_data_source(linker: &mut Linker<HostState>) -> HostResult<()> {
+    fn read_params(c: &Ctx, params: i32) -> HostResult<Vec<String>> {
+        c.read_array(params as u32)?
+            .into_iter()
+            .map(|p| c.read_string(p))
+            .collect()
+    }
+
+    linker.func_wrap(
+        "env",
+        "dataSource.create",
+        |mut c: Ctx, name: i32, params: i32| -> HostResult<()> {
+            let name = c.read_string(name as u32)?;
+            let params = read_params(&c, params)?;
+            testing::record_created_data_source(&name, &params, None);
+            c.data_mut().created_data_sources.push(CreatedDataSource {
+                name,
+                params,
+                context: None,
+            });
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "dataSource.createWithContext",
+        |mut c: Ctx, name: i32, params: i32, context: i32| -> HostResult<()> {
+            let name = c.read_string(name as u32)?;
+            let params = read_params(&c, params)?;
+            let context = c.read_entity(context as u32)?;
+            testing::record_created_data_source(&name, &params, Some(context.clone()));
+            c.data_mut().created_data_sources.push(CreatedDataSource {
+                name,
+                params,
+                context: Some(context),
+            });
+            Ok(())
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "dataSource.address",
+        |mut c: Ctx| -> HostResult<i32> {
+            let address = testing::get_mock_data_source_address();
+            Ok(c.write_uint8_array(address.as_bytes())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "dataSource.network",
+        |mut c: Ctx| -> HostResult<i32> {
+            Ok(c.write_string(&testing::get_mock_data_source_network())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "dataSource.context",
+        |mut c: Ctx| -> HostResult<i32> {
+            Ok(c.write_entity(&testing::get_mock_data_source_context())? as i32)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn link_misc(linker: &mut Linker<HostState>) -> HostResult<()> {
+    linker.func_wrap(
+        "env",
+        "crypto.keccak256",
+        |mut c: Ctx, data: i32| -> HostResult<i32> {
+            let data = c.read_bytes(data as u32)?;
+            let hash = crate::crypto::keccak256(&data);
+            Ok(c.write_uint8_array(hash.as_slice())? as i32)
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "log.log",
+        |mut c: Ctx, level: i32, msg: i32| -> HostResult<()> {
+            let message = c.read_string(msg as u32)?;
+            let level = match level {
+                0 => Level::Critical,
+                1 => Level::Error,
+                2 => Level::Warning,
+                3 => Level::Info,
+                _ => Level::Debug,
+            };
+            c.data_mut().logs.push(LogEntry {
+                level,
+                message: message.clone(),
+            });
+            // A critical log aborts the handler in graph-node
+            if level == Level::Critical {
+                return Err(Error::new(format!(
+                    "Critical error logged in mapping: {}",
+                    message
+                )));
+            }
+            Ok(())
+        },
+    )?;
+
+    // ENS lookups are not mocked, mirroring `ens::name_by_hash` on native
+    linker.func_wrap(
+        "env",
+        "ens.nameByHash",
+        |_c: Ctx, _hash: i32| -> HostResult<i32> { Ok(0) },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "abort",
+        |c: Ctx, msg: i32, file: i32, line: i32, column: i32| -> HostResult<()> {
+            let read = |ptr: i32| {
+                if ptr == 0 {
+                    Ok(String::from("(null)"))
+                } else {
+                    c.read_string(ptr as u32)
+                }
+            };
+            Err(Error::new(format!(
+                "Mapping aborted at {}, line {}, column {}, with message: {}",
+                read(file)?,
+                line,
+                column,
+                read(msg)?
+            )))
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::{EventParam, Token};
+    use crate::testing::{
+        EventBuilder, clear_store, mock_ipfs_cat, register_derived_field, store_get, store_set,
+    };
+    use crate::types::Value;
+
+    /// Every import the runtime declares in `host.rs`, with its signature.
+    const IMPORTS: &str = r#"
+        (import "env" "store.get" (func (param i32 i32) (result i32)))
+        (import "env" "store.set" (func $store_set (param i32 i32 i32)))
+        (import "env" "store.remove" (func (param i32 i32)))
+        (import "env" "store.get_in_block" (func (param i32 i32) (result i32)))
+        (import "env" "store.loadRelated" (func $load_related (param i32 i32 i32) (result i32)))
+        (import "env" "ethereum.call" (func (param i32) (result i32)))
+        (import "env" "ethereum.encode" (func (param i32) (result i32)))
+        (import "env" "ethereum.decode" (func (param i32 i32) (result i32)))
+        (import "env" "typeConversion.bytesToString" (func (param i32) (result i32)))
+        (import "env" "typeConversion.bytesToHex" (func (param i32) (result i32)))
+        (import "env" "typeConversion.bigIntToString" (func (param i32) (result i32)))
+        (import "env" "typeConversion.bigIntToHex" (func (param i32) (result i32)))
+        (import "env" "typeConversion.stringToH160" (func (param i32) (result i32)))
+        (import "env" "typeConversion.bytesToBase58" (func (param i32) (result i32)))
+        (import "env" "bigInt.plus" (func $plus (param i32 i32) (result i32)))
+        (import "env" "bigInt.minus" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.times" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.dividedBy" (func $div (param i32 i32) (result i32)))
+        (import "env" "bigInt.mod" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.pow" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.bitOr" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.bitAnd" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.leftShift" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.rightShift" (func (param i32 i32) (result i32)))
+        (import "env" "bigInt.fromString" (func (param i32) (result i32)))
+        (import "env" "bigDecimal.plus" (func (param i32 i32) (result i32)))
+        (import "env" "bigDecimal.minus" (func (param i32 i32) (result i32)))
+        (import "env" "bigDecimal.times" (func (param i32 i32) (result i32)))
+        (import "env" "bigDecimal.dividedBy" (func (param i32 i32) (result i32)))
+        (import "env" "bigDecimal.equals" (func (param i32 i32) (result i32)))
+        (import "env" "bigDecimal.toString" (func (param i32) (result i32)))
+        (import "env" "bigDecimal.fromString" (func (param i32) (result i32)))
+        (import "env" "crypto.keccak256" (func (param i32) (result i32)))
+        (import "env" "json.fromBytes" (func (param i32) (result i32)))
+        (import "env" "json.toI64" (func (param i32) (result i64)))
+        (import "env" "json.toU64" (func (param i32) (result i64)))
+        (import "env" "json.toF64" (func (param i32) (result f64)))
+        (import "env" "json.toBigInt" (func (param i32) (result i32)))
+        (import "env" "ipfs.cat" (func (param i32) (result i32)))
+        (import "env" "ipfs.map" (func $ipfs_map (param i32 i32 i32 i32)))
+        (import "env" "log.log" (func $log (param i32 i32)))
+        (import "env" "dataSource.create" (func (param i32 i32)))
+        (import "env" "dataSource.createWithContext" (func (param i32 i32 i32)))
+        (import "env" "dataSource.address" (func (result i32)))
+        (import "env" "dataSource.network" (func (result i32)))
+        (import "env" "dataSource.context" (func (result i32)))
+        (import "env" "ens.nameByHash" (func (param i32) (result i32)))
+    "#;
+
+    /// A bump `__new` that writes the AssemblyScript header like the runtime does.
+    const ALLOCATOR: &str = r#"
+        (memory (export "memory") 4)
+        (global $heap (mut i32) (i32.const 1024))
+        (func (export "__new") (param $size i32) (param $id i32) (result i32)
+          (local $ptr i32)
+          (local.set $ptr (i32.add (global.get $heap) (i32.const 20)))
+          (i32.store offset=12 (global.get $heap) (local.get $id))
+          (i32.store offset=16 (global.get $heap) (local.get $size))
+          (global.set $heap
+            (i32.and (i32.add (i32.add (local.get $ptr) (local.get $size)) (i32.const 7))
+                     (i32.const -8)))
+          (local.get $ptr))
+    "#;
+
+    const EXPORTS: &str = r#"
+        ;; log.log(INFO, event.logType); log.log(INFO, event.params[0].name)
+        (func (export "handleEvent") (param $event i32)
+          (call $log (i32.const 3) (i32.load offset=12 (local.get $event)))
+          (call $log (i32.const 3)
+            (i32.load (i32.load (i32.load offset=4 (i32.load offset=24 (local.get $event)))))))
+        (func (export "set") (param i32 i32 i32)
+          (call $store_set (local.get 0) (local.get 1) (local.get 2)))
+        (func (export "loadRelated") (param i32 i32 i32) (result i32)
+          (call $load_related (local.get 0) (local.get 1) (local.get 2)))
+        (func (export "plus") (param i32 i32) (result i32)
+          (call $plus (local.get 0) (local.get 1)))
+        (func (export "div") (param i32 i32) (result i32)
+          (call $div (local.get 0) (local.get 1)))
+        (func (export "log") (param i32 i32)
+          (call $log (local.get 0) (local.get 1)))
+        (func (export "map") (param i32 i32 i32)
+          (call $ipfs_map (local.get 0) (local.get 1) (local.get 2) (i32.const 0)))
+        ;; ipfs.map callback: log.log(INFO, value) for string values
+        (func (export "onItem") (param $value i32) (param $user_data i32)
+          (call $log (i32.const 3) (i32.load offset=8 (local.get $value))))
+    "#;
+
+    fn host() -> WasmHost {
+        let wat = format!("(module {} {} {})", IMPORTS, ALLOCATOR, EXPORTS);
+        WasmHost::from_bytes(&wat::parse_str(wat).unwrap()).unwrap()
+    }
+
+    fn call<P: wasmi::WasmParams, R: wasmi::WasmResults>(
+        host: &mut WasmHost,
+        name: &str,
+        params: P,
+    ) -> HostResult<R> {
+        let func = host.instance.get_typed_func::<P, R>(&host.store, name)?;
+        func.call(&mut host.store, params)
+    }
+
+    struct TransferParams {
+        from: Address,
+    }
+
+    impl Default for TransferParams {
+        fn default() -> Self {
+            Self {
+                from: Address::zero(),
+            }
+        }
+    }
+
+    impl ToEventParams for TransferParams {
+        fn to_event_params(&self) -> Vec<EventParam> {
+            vec![EventParam {
+                name: "from".into(),
+                value: Token::Address(self.from.clone()),
+            }]
+        }
+    }
+
+    #[test]
+    fn test_all_runtime_imports_link() {
+        host();
+    }
+
+    #[test]
+    fn test_unknown_import_fails_to_link() {
+        let wat = format!(
+            r#"(module (import "env" "store.loadEverything" (func)) {})"#,
+            ALLOCATOR
+        );
+        let err = WasmHost::from_bytes(&wat::parse_str(wat).unwrap())
+            .err()
+            .unwrap();
+        assert!(matches!(err, WasmHostError::Wasm(_)));
+    }
+
+    #[test]
+    fn test_handler_receives_graph_node_event_layout() {
+        let mut host = host();
+        let mut event = EventBuilder::<TransferParams>::new()
+            .params(TransferParams {
+                from: Address::zero(),
+            })
+            .build();
+        event.log_type = Some("mined".into());
+
+        host.handle_event("handleEvent", &event).unwrap();
+
+        let messages: Vec<_> = host.logs().iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, ["mined", "from"]);
+    }
+
+    #[test]
+    fn test_missing_handler_is_reported() {
+        let mut host = host();
+        let err = host
+            .handle_block("handleBlock", &crate::testing::mock_block(1, 0))
+            .unwrap_err();
+        assert!(matches!(err, WasmHostError::MissingExport(name) if name == "handleBlock"));
+    }
+
+    #[test]
+    fn test_store_set_round_trips_entity() {
+        clear_store();
+        let mut host = host();
+
+        let mut data = EntityData::new();
+        data.set("id", Value::String("t1".into()));
+        data.set("count", Value::Int(-7));
+        data.set("nonce", Value::Int8(-(1 << 40)));
+        data.set("amount", Value::BigInt(BigInt::from_i64(-123456789)));
+        data.set("price", Value::BigDecimal(BigDecimal::from_string("1.25")));
+        data.set("active", Value::Bool(true));
+        data.set("hash", Value::Bytes(Bytes::from_vec(vec![0xde, 0xad])));
+        data.set(
+            "tags",
+            Value::Array(vec![Value::String("a".into()), Value::String("b".into())]),
+        );
+        data.set("owner", Value::Null);
+
+        let entity_type = host.store.write_string("Transfer").unwrap();
+        let id = host.store.write_string("t1").unwrap();
+        let ptr = host.store.write_entity(&data).unwrap();
+        call::<_, ()>(
+            &mut host,
+            "set",
+            (entity_type as i32, id as i32, ptr as i32),
+        )
+        .unwrap();
+
+        let stored = store_get("Transfer", "t1").unwrap();
+        assert_eq!(stored.get_int_opt("count"), Some(-7));
+        assert_eq!(stored.get_int8_opt("nonce"), Some(-(1 << 40)));
+        assert_eq!(stored.get_bigint("amount"), BigInt::from_i64(-123456789));
+        assert_eq!(
+            stored.get_big_decimal("price"),
+            BigDecimal::from_string("1.25")
+        );
+        assert_eq!(stored.get_bool_opt("active"), Some(true));
+        assert_eq!(stored.get_bytes("hash").as_slice(), &[0xde, 0xad]);
+        assert_eq!(stored.get_string_array("tags"), ["a", "b"]);
+        assert!(stored.get("owner").unwrap().is_null());
+    }
+
+    #[test]
+    fn test_store_load_related_returns_entity_array() {
+        clear_store();
+        register_derived_field("Account", "tokens", "Token", "owner");
+        for (id, owner) in [("t1", "a1"), ("t2", "a2"), ("t3", "a1")] {
+            let mut data = EntityData::new();
+            data.set("id", Value::String(id.into()));
+            data.set("owner", Value::String(owner.into()));
+            store_set("Token", id, &data);
+        }
+        let mut host = host();
+
+        let entity_type = host.store.write_string("Account").unwrap();
+        let id = host.store.write_string("a1").unwrap();
+        let field = host.store.write_string("tokens").unwrap();
+        let ptr = call::<_, i32>(
+            &mut host,
+            "loadRelated",
+            (entity_type as i32, id as i32, field as i32),
+        )
+        .unwrap();
+
+        let ids: Vec<String> = host
+            .store
+            .read_array(ptr as u32)
+            .unwrap()
+            .into_iter()
+            .map(|p| {
+                host.store
+                    .read_entity(p)
+                    .unwrap()
+                    .get_string("id")
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(ids, ["t1", "t3"]);
+    }
+
+    #[test]
+    fn test_store_set_rejects_mismatched_id() {
+        clear_store();
+        let mut host = host();
+
+        let mut data = EntityData::new();
+        data.set("id", Value::String("other".into()));
+        let entity_type = host.store.write_string("Transfer").unwrap();
+        let id = host.store.write_string("t1").unwrap();
+        let ptr = host.store.write_entity(&data).unwrap();
+
+        let err = call::<_, ()>(
+            &mut host,
+            "set",
+            (entity_type as i32, id as i32, ptr as i32),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("conflicts with ID"));
+        assert!(store_get("Transfer", "t1").is_none());
+    }
+
+    #[test]
+    fn test_store_set_bytes_id() {
+        clear_store();
+        let mut host = host();
+
+        let mut data = EntityData::new();
+        data.set("id", Value::Bytes(Bytes::from(vec![0xab, 0xcd])));
+        let entity_type = host.store.write_string("Account").unwrap();
+        let ptr = host.store.write_entity(&data).unwrap();
+
+        let wrong = host.store.write_string("abcd").unwrap();
+        call::<_, ()>(
+            &mut host,
+            "set",
+            (entity_type as i32, wrong as i32, ptr as i32),
+        )
+        .unwrap_err();
+
+        let id = host.store.write_string("0xabcd").unwrap();
+        call::<_, ()>(
+            &mut host,
+            "set",
+            (entity_type as i32, id as i32, ptr as i32),
+        )
+        .unwrap();
+        assert!(store_get("Account", "0xabcd").is_some());
+    }
+
+    #[test]
+    fn test_big_int_imports() {
+        let mut host = host();
+        let a = host.store.write_big_int(&BigInt::from_i32(-40)).unwrap();
+        let b = host.store.write_big_int(&BigInt::from_i32(2)).unwrap();
+        let sum = call::<_, i32>(&mut host, "plus", (a as i32, b as i32)).unwrap();
+        assert_eq!(
+            host.store.read_big_int(sum as u32).unwrap(),
+            BigInt::from_i32(-38)
+        );
+
+        let zero = host.store.write_big_int(&BigInt::zero()).unwrap();
+        let err = call::<_, i32>(&mut host, "div", (a as i32, zero as i32)).unwrap_err();
+        assert!(err.to_string().contains("divide BigInt by zero"));
+    }
+
+    #[test]
+    fn test_critical_log_aborts_handler() {
+        let mut host = host();
+        let msg = host.store.write_string("invariant broken").unwrap();
+        let err = call::<_, ()>(&mut host, "log", (0, msg as i32)).unwrap_err();
+        assert!(err.to_string().contains("invariant broken"));
+        assert_eq!(host.logs()[0].level, Level::Critical);
+    }
+
+    #[test]
+    fn test_ipfs_map_streams_json_lines() {
+        let mut host = host();
+        mock_ipfs_cat("QmList", "\"first\"\n\n\"second\"\n");
+        let hash = host.store.write_string("QmList").unwrap();
+        let callback = host.store.write_string("onItem").unwrap();
+        let user_data = host.store.write_value(&Value::Null).unwrap();
+
+        call::<_, ()>(
+            &mut host,
+            "map",
+            (hash as i32, callback as i32, user_data as i32),
+        )
+        .unwrap();
+
+        let messages: Vec<_> = host.logs().iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_alloc_profile_per_handler() {
+        use crate::allocator::{ClassAllocs, class_id};
+
+        assert_eq!(host().alloc_report(), None);
+
+        // Counters at 16 with one slot for TypedMapStringStoreValue (36). The
+        // handler counts two 40-byte allocations and a 512-byte peak.
+        let wat = format!(
+            r#"(module
+                {}
+                (data (i32.const 16) "\00\00\00\00\01\00\00\00\24\00\00\00")
+                (func (export "yogurt_alloc_profile") (result i32) (i32.const 16))
+                (func (export "handleFile") (param i32)
+                  (i32.store (i32.const 16) (i32.const 512))
+                  (i32.store (i32.const 28) (i32.add (i32.load (i32.const 28)) (i32.const 2)))
+                  (i32.store (i32.const 32) (i32.add (i32.load (i32.const 32)) (i32.const 80)))))"#,
+            ALLOCATOR
+        );
+        let mut host = WasmHost::from_bytes(&wat::parse_str(wat).unwrap()).unwrap();
+        let content = Bytes::from(&b"{}"[..]);
+        host.handle_file("handleFile", &content).unwrap();
+        host.handle_file("handleFile", &content).unwrap();
+
+        let profile = &host.alloc_profiles()["handleFile"];
+        assert_eq!(profile.invocations, 2);
+        assert_eq!(profile.peak_heap, 512);
+        assert_eq!(
+            profile.classes,
+            [ClassAllocs {
+                class_id: class_id::TYPED_MAP,
+                count: 4,
+                bytes: 160,
+            }]
+        );
+        assert!(
+            host.alloc_report()
+                .unwrap()
+                .starts_with("handleFile: 2 invocations")
+        );
+    }
+
+    /// A Rust ABI module: exports the version marker and `yogurt_alloc`, and
+    /// records the last handler argument in the `last` global.
+    fn rust_abi_host() -> WasmHost {
+        let wat = format!(
+            r#"(module
+                (import "env" "yogurt.store.get" (func $get (param i32) (result i32)))
+                (import "env" "yogurt.store.set" (func $set (param i32)))
+                (import "env" "yogurt.store.remove" (func (param i32)))
+                (import "env" "yogurt.store.get_in_block" (func (param i32) (result i32)))
+                (import "env" "yogurt.store.load_related" (func $related (param i32) (result i32)))
+                {}
+                (global $last (export "last") (mut i32) (i32.const 0))
+                (func (export "yogurt_abi_version") (result i32) (i32.const 1))
+                (func (export "yogurt_alloc") (param i32) (result i32)
+                  (call $new (local.get 0) (i32.const 0)))
+                (func (export "handleEvent") (param i32) (global.set $last (local.get 0)))
+                (func (export "get") (param i32) (result i32) (call $get (local.get 0)))
+                (func (export "set") (param i32) (call $set (local.get 0)))
+                (func (export "related") (param i32) (result i32) (call $related (local.get 0))))"#,
+            ALLOCATOR.replace(
+                r#"(func (export "__new")"#,
+                r#"(func $new (export "__new")"#
+            )
+        );
+        WasmHost::from_bytes(&wat::parse_str(wat).unwrap()).unwrap()
+    }
+
+    impl Decode for TransferParams {
+        fn decode(input: &mut &[u8]) -> Result<Self, rust_abi::DecodeError> {
+            let params = rust_abi::decode_event_params(input)?;
+            Ok(Self {
+                from: match params.first() {
+                    Some(Token::Address(a)) => a.clone(),
+                    _ => Address::zero(),
+                },
+            })
+        }
+    }
+
+    #[test]
+    fn test_rust_abi_module_is_detected() {
+        assert!(rust_abi_host().uses_rust_abi());
+        assert!(!host().uses_rust_abi());
+    }
+
+    #[test]
+    fn test_rust_abi_handler_receives_message() {
+        let mut host = rust_abi_host();
+        let event = EventBuilder::<TransferParams>::new()
+            .params(TransferParams {
+                from: Address::from([0x11; 20]),
+            })
+            .block_number(42)
+            .build();
+
+        host.handle_event("handleEvent", &event).unwrap();
+
+        let last = host.instance.get_global(&host.store, "last").unwrap();
+        let ptr = last.get(&host.store).i32().unwrap() as u32;
+        let received: Event<TransferParams> = read_message(&host.store, ptr).unwrap();
+        assert_eq!(received.params.from, Address::from([0x11; 20]));
+        assert_eq!(received.block.number, BigInt::from_u64(42));
+    }
+
+    #[test]
+    fn test_rust_abi_store_round_trip() {
+        clear_store();
+        let mut host = rust_abi_host();
+
+        let mut data = EntityData::new();
+        data.set("id", Value::String("r1".into()));
+        data.set("amount", Value::BigInt(BigInt::from_i64(-5)));
+        let request = write_message(&mut host.store, &("Transfer", "r1", &data)).unwrap();
+        call::<_, ()>(&mut host, "set", request as i32).unwrap();
+        assert_eq!(
+            store_get("Transfer", "r1").unwrap().get_bigint("amount"),
+            BigInt::from_i64(-5)
+        );
+
+        let request = write_message(&mut host.store, &("Transfer", "r1")).unwrap();
+        let result = call::<_, i32>(&mut host, "get", request as i32).unwrap();
+        let loaded: EntityData = read_message(&host.store, result as u32).unwrap();
+        assert_eq!(loaded.get_string("id"), "r1");
+
+        let request = write_message(&mut host.store, &("Transfer", "missing")).unwrap();
+        assert_eq!(call::<_, i32>(&mut host, "get", request as i32).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rust_abi_store_load_related() {
+        clear_store();
+        register_derived_field("Account", "tokens", "Token", "holders");
+        let mut data = EntityData::new();
+        data.set("id", Value::String("t1".into()));
+        data.set(
+            "holders",
+            Value::Array(vec![Value::String("a0".into()), Value::String("a1".into())]),
+        );
+        store_set("Token", "t1", &data);
+        let mut host = rust_abi_host();
+
+        let request = write_message(&mut host.store, &("Account", "a1", "tokens")).unwrap();
+        let result = call::<_, i32>(&mut host, "related", request as i32).unwrap();
+        let related: Vec<EntityData> = read_message(&host.store, result as u32).unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].get_string("id"), "t1");
+    }
+
+    #[test]
+    fn test_rust_abi_store_set_rejects_mismatched_id() {
+        clear_store();
+        let mut host = rust_abi_host();
+
+        let mut data = EntityData::new();
+        data.set("id", Value::String("other".into()));
+        let request = write_message(&mut host.store, &("Transfer", "r2", &data)).unwrap();
+        let err = call::<_, ()>(&mut host, "set", request as i32).unwrap_err();
+        assert!(err.to_string().contains("conflicts with ID"));
+    }
+
+    /// An export that fails with `log.critical("boom")`, and one that fails
+    /// the way a handler returning `Err` does.
+    const FAILING_EXPORT: &str = r#"
+        (data (i32.const 32) "\08\00\00\00b\00o\00o\00m\00")
+        (func (export "handleFailure") (param i32)
+          (call $log (i32.const 0) (i32.const 36)))
+
+        ;; `handler::finish` after the handler returned `Err("no pool")`
+        (data (i32.const 64) "\3e\00\00\00h\00a\00n\00d\00l\00e\00R\00e\00j\00e\00c\00t\00i\00o\00n\00 \00f\00a\00i\00l\00e\00d\00:\00 \00n\00o\00 \00p\00o\00o\00l\00")
+        (func (export "handleRejection") (param i32)
+          (call $log (i32.const 0) (i32.const 68))
+          (unreachable))
+    "#;
+
+    impl crate::asc::FromAscPtr for TransferParams {
+        fn from_asc_ptr(_ptr: u32) -> Self {
+            Self::default()
+        }
+    }
+
+    /// Panics natively, so only running the export lets a test pass.
+    #[yogurt_macros::handler]
+    fn handle_event(_event: Event<TransferParams>) {
+        panic!("handleEvent ran natively");
+    }
+
+    #[yogurt_macros::handler]
+    fn handle_failure(_block: Block) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    #[yogurt_macros::handler]
+    fn handle_rejection(_block: Block) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    /// Point [`WASM_MODULE_ENV`] at the test module, like `yogurt test --wasm`.
+    fn set_env_module() {
+        static MODULE: std::sync::Once = std::sync::Once::new();
+        MODULE.call_once(|| {
+            let wat = format!("(module {} {} {} {})", IMPORTS, ALLOCATOR, EXPORTS, FAILING_EXPORT);
+            let path = std::env::temp_dir().join(format!("yogurt-wasm-host-{}.wasm", std::process::id()));
+            std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+            // SAFETY: tests only touch the environment through `std::env`,
+            // which serialises access
+            unsafe { std::env::set_var(WASM_MODULE_ENV, &path) };
+        });
+    }
+
+    #[test]
+    fn test_handler_function_runs_env_module_export() {
+        set_env_module();
+
+        // A native test, unchanged
+        let mut event = EventBuilder::<TransferParams>::new().build();
+        event.log_type = Some("mined".into());
+        handle_event(event);
+
+        let logs = with_env_host(|host| host.logs().to_vec()).unwrap();
+        let messages: Vec<_> = logs.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, ["mined", "from"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "handleFailure failed in the WASM module: boom")]
+    fn test_handler_function_panics_when_export_fails() {
+        set_env_module();
+        let _ = handle_failure(crate::testing::mock_block(1, 0));
+    }
+
+    #[test]
+    fn test_handler_function_returns_err_from_export() {
+        set_env_module();
+
+        let err = handle_rejection(crate::testing::mock_block(1, 0)).unwrap_err();
+        assert_eq!(err.to_string(), "no pool");
+    }
+
+    #[test]
+    fn test_handler_function_runs_in_fresh_host_after_trap() {
+        set_env_module();
+        assert!(handle_rejection(crate::testing::mock_block(1, 0)).is_err());
+
+        let mut event = EventBuilder::<TransferParams>::new().build();
+        event.log_type = Some("mined".into());
+        handle_event(event);
+
+        // The rejection's critical log went with the trapped instance
+        let logs = with_env_host(|host| host.logs().to_vec()).unwrap();
+        let messages: Vec<_> = logs.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, ["mined", "from"]);
+    }
+
+    /// The erc20-transfer example built for wasm32, to run the runtime's own
+    /// exports and panic handler rather than hand-written WAT.
+    fn erc20_transfer_host() -> WasmHost {
+        static MODULE: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+        let module = MODULE.get_or_init(|| {
+            let project =
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/integration/erc20-transfer");
+            let status = std::process::Command::new(env!("CARGO"))
+                .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+                .current_dir(&project)
+                .status()
+                .expect("failed to run cargo build");
+            assert!(status.success(), "failed to build {}", project.display());
+            std::fs::read(project.join("target/wasm32-unknown-unknown/release/erc20_transfer.wasm"))
+                .unwrap()
+        });
+        WasmHost::from_bytes(module).unwrap()
+    }
+
+    /// The critical log a failed call left.
+    fn critical_log(host: &WasmHost) -> &str {
+        let log = host.logs().last().expect("no log before the trap");
+        assert_eq!(log.level, Level::Critical);
+        &log.message
+    }
+
+    /// Whether `message` ends with `at <file>:<line>:<column>` in `file`.
+    fn ends_with_location(message: &str, file: &str) -> bool {
+        let Some((_, location)) = message.rsplit_once(" at ") else {
+            return false;
+        };
+        let mut parts = location.rsplitn(3, ':');
+        let (Some(column), Some(line), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            return false;
+        };
+        path.ends_with(file) && line.parse::<u32>().is_ok() && column.parse::<u32>().is_ok()
+    }
+
+    #[test]
+    fn test_panic_reports_message_and_location() {
+        let mut host = erc20_transfer_host();
+        let err = host.handle_file("handleMetadata", &Bytes::from(&b"name: USDC"[..])).unwrap_err();
+
+        let message = critical_log(&host);
+        assert!(err.to_string().contains(message), "{}", err);
+        assert!(message.starts_with("token metadata is not a JSON object at "), "{}", message);
+        assert!(ends_with_location(message, "src/mappings/mod.rs"), "{}", message);
+
+        // A later handler runs normally
+        host.handle_file("handleMetadata", &Bytes::from(&b"{}"[..])).unwrap();
+    }
+
+    #[test]
+    fn test_abort_export_reports_message_and_location() {
+        let mut host = erc20_transfer_host();
+        let msg = host.store.write_string("assertion failed").unwrap();
+        let file = host.store.write_string("~lib/array.ts").unwrap();
+        let err = call::<_, ()>(&mut host, "abort", (msg as i32, file as i32, 12, 7)).unwrap_err();
+        assert_eq!(critical_log(&host), "assertion failed at ~lib/array.ts:12:7");
+        assert!(err.to_string().contains("assertion failed at ~lib/array.ts:12:7"), "{}", err);
+    }
+
+    #[test]
+    fn test_abort_export_accepts_null_pointers() {
+        let mut host = erc20_transfer_host();
+        // Fails on the critical log, not on a memory access
+        let err = call::<_, ()>(&mut host, "abort", (0, 0, 3, 1)).unwrap_err();
+        assert_eq!(critical_log(&host), "(null) at (null):3:1");
+        assert!(err.to_string().contains("(null) at (null):3:1"), "{}", err);
+    }
+
+    #[test]
+    fn test_to_base58() {
+        assert_eq!(to_base58(b"hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(to_base58(&[0, 0, 1]), "112");
+        assert_eq!(to_base58(&[]), "");
+    }
+}
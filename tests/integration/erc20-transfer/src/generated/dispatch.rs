@@ -0,0 +1,43 @@
+//! Auto-generated dispatch table of the manifest's handlers — do not edit
+
+/// The manifest's data sources and templates as a
+/// `yogurt_runtime::testing::DispatchTable`, for `testing::Chain`.
+///
+/// Runs the handler functions of `crate::mappings`, which are named
+/// after the manifest's handlers in snake case:
+///
+/// ```ignore
+/// let mut chain = Chain::new(dispatch_table());
+/// ```
+#[allow(dead_code)]
+pub fn dispatch_table() -> yogurt_runtime::testing::DispatchTable {
+yogurt_runtime::testing::DispatchTable {
+data_sources: alloc::vec![
+yogurt_runtime::testing::DataSourceSpec {
+name: "ERC20",
+address: Some(yogurt_runtime::prelude::Address::from_string("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")
+.expect("data source `ERC20` has an invalid address")),
+start_block: 24621150,
+events: alloc::vec![
+yogurt_runtime::testing::EventHandlerSpec {
+handler: "handleTransfer",
+filter: yogurt_runtime::prelude::TopicFilter::ANY,
+run: |log, block, transaction| {
+yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::handle_transfer)
+},
+},
+],
+calls: alloc::vec![
+yogurt_runtime::testing::CallHandlerSpec {
+handler: "handleTransferCall",
+run: |call, block| yogurt_runtime::testing::run_call(call, block, crate::mappings::handle_transfer_call),
+},
+],
+blocks: alloc::vec![
+],
+},
+],
+templates: alloc::vec![
+],
+}
+}
@@ -7,7 +7,7 @@ use alloc::vec;
 #[allow(unused_imports)]
 use alloc::vec::Vec;
 use yogurt_runtime::prelude::*;
-use yogurt_runtime::ethereum::{Call, Event, SmartContractCall, Token};
+use yogurt_runtime::ethereum::{Call, CallError, ContractError, Event, FromLog, Log, SmartContractCall, Token};
 
 /// Approval(address owner, address spender, uint256 value)
 pub struct ApprovalParams {
@@ -16,51 +16,130 @@ pub struct ApprovalParams {
     pub value: BigInt,
 }
 
+impl ApprovalParams {
+/// First topic of its logs, the keccak256 hash of `Approval(address,address,uint256)`.
+pub const TOPIC0: [u8; 32] = [0x8c, 0x5b, 0xe1, 0xe5, 0xeb, 0xec, 0x7d, 0x5b, 0xd1, 0x4f, 0x71, 0x42, 0x7d, 0x1e, 0x84, 0xf3, 0xdd, 0x03, 0x14, 0xc0, 0xf7, 0xb2, 0x29, 0x1e, 0x5b, 0x20, 0x0a, 0xc8, 0xc7, 0xc3, 0xb9, 0x25];
+}
+
+impl FromLog for ApprovalParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 2 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+    abi::decode_value(&ParamType::parse("address")?, topics[1].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    owner: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    spender: match indexed.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    value: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for ApprovalParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.owner.clone())]).unwrap_or_default()),
+    Bytes::from(abi::encode(&[Token::Address(self.spender.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.value.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type ApprovalEvent = Event<ApprovalParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for ApprovalParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let owner = if param0_ptr == 0 {
+Address::zero()
+} else {
 let owner_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let owner_bytes = asc_to_bytes(AscPtr::new(owner_value_ptr));
-let owner = Address::from(owner_bytes.as_slice());
+if owner_value_ptr == 0 {
+Address::zero()
+} else {
+let owner_payload = read_u64_at(owner_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let owner_bytes = asc_to_bytes(AscPtr::new(owner_payload));
+Address::from(owner_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let spender = if param1_ptr == 0 {
+Address::zero()
+} else {
 let spender_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let spender_bytes = asc_to_bytes(AscPtr::new(spender_value_ptr));
-let spender = Address::from(spender_bytes.as_slice());
+if spender_value_ptr == 0 {
+Address::zero()
+} else {
+let spender_payload = read_u64_at(spender_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let spender_bytes = asc_to_bytes(AscPtr::new(spender_payload));
+Address::from(spender_bytes.as_slice())
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let value = if param2_ptr == 0 {
+BigInt::zero()
+} else {
 let value_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let value = BigInt::from_ptr(AscPtr::new(value_value_ptr));
+if value_value_ptr == 0 {
+BigInt::zero()
+} else {
+let value_payload = read_u64_at(value_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(value_payload)
+}
+};
 
 Self { owner, spender, value }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for ApprovalParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for ApprovalParams {
 fn default() -> Self {
 Self {
@@ -71,6 +150,31 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for ApprovalParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "owner".into(), value: Token::Address(self.owner.clone()) },
+                EventParam { name: "spender".into(), value: Token::Address(self.spender.clone()) },
+                EventParam { name: "value".into(), value: Token::Uint(self.value.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for ApprovalParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                owner: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                spender: match params.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                value: match params.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
 /// Transfer(address from, address to, uint256 value)
 pub struct TransferParams {
     pub from: Address,
@@ -78,51 +182,130 @@ pub struct TransferParams {
     pub value: BigInt,
 }
 
+impl TransferParams {
+/// First topic of its logs, the keccak256 hash of `Transfer(address,address,uint256)`.
+pub const TOPIC0: [u8; 32] = [0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa, 0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef];
+}
+
+impl FromLog for TransferParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 2 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+    abi::decode_value(&ParamType::parse("address")?, topics[1].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    from: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    to: match indexed.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    value: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for TransferParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.from.clone())]).unwrap_or_default()),
+    Bytes::from(abi::encode(&[Token::Address(self.to.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.value.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type TransferEvent = Event<TransferParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for TransferParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let from = if param0_ptr == 0 {
+Address::zero()
+} else {
 let from_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let from_bytes = asc_to_bytes(AscPtr::new(from_value_ptr));
-let from = Address::from(from_bytes.as_slice());
+if from_value_ptr == 0 {
+Address::zero()
+} else {
+let from_payload = read_u64_at(from_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let from_bytes = asc_to_bytes(AscPtr::new(from_payload));
+Address::from(from_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let to = if param1_ptr == 0 {
+Address::zero()
+} else {
 let to_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let to_bytes = asc_to_bytes(AscPtr::new(to_value_ptr));
-let to = Address::from(to_bytes.as_slice());
+if to_value_ptr == 0 {
+Address::zero()
+} else {
+let to_payload = read_u64_at(to_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let to_bytes = asc_to_bytes(AscPtr::new(to_payload));
+Address::from(to_bytes.as_slice())
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let value = if param2_ptr == 0 {
+BigInt::zero()
+} else {
 let value_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let value = BigInt::from_ptr(AscPtr::new(value_value_ptr));
+if value_value_ptr == 0 {
+BigInt::zero()
+} else {
+let value_payload = read_u64_at(value_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(value_payload)
+}
+};
 
 Self { from, to, value }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for TransferParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for TransferParams {
 fn default() -> Self {
 Self {
@@ -133,6 +316,31 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for TransferParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "from".into(), value: Token::Address(self.from.clone()) },
+                EventParam { name: "to".into(), value: Token::Address(self.to.clone()) },
+                EventParam { name: "value".into(), value: Token::Uint(self.value.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for TransferParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                from: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                to: match params.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                value: match params.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
 /// Call handler types for `transfer(address,uint256)`
 pub struct TransferInputs {
     pub to: Address,
@@ -145,44 +353,65 @@ pub struct TransferOutputs {
 
 pub type TransferCall = Call<TransferInputs, TransferOutputs>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for TransferInputs {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let to = if param0_ptr == 0 {
+Address::zero()
+} else {
 let to_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let to_bytes = asc_to_bytes(AscPtr::new(to_value_ptr));
-let to = Address::from(to_bytes.as_slice());
+if to_value_ptr == 0 {
+Address::zero()
+} else {
+let to_payload = read_u64_at(to_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let to_bytes = asc_to_bytes(AscPtr::new(to_payload));
+Address::from(to_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let value = if param1_ptr == 0 {
+BigInt::zero()
+} else {
 let value_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let value = BigInt::from_ptr(AscPtr::new(value_value_ptr));
+if value_value_ptr == 0 {
+BigInt::zero()
+} else {
+let value_payload = read_u64_at(value_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(value_payload)
+}
+};
 
 Self { to, value }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for TransferInputs {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for TransferInputs {
 fn default() -> Self {
 Self {
@@ -192,39 +421,51 @@ Self {
 }
 }
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for TransferOutputs {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let output0 = if param0_ptr == 0 {
+false
+} else {
 let output0_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let output0 = output0_value_ptr != 0;
+if output0_value_ptr == 0 {
+false
+} else {
+let output0_payload = read_u64_at(output0_value_ptr, ETHEREUM_VALUE_DATA_OFFSET);
+output0_payload != 0
+}
+};
 
 Self { output0 }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for TransferOutputs {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for TransferOutputs {
 fn default() -> Self {
 Self {
@@ -233,6 +474,109 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for TransferInputs {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "to".into(), value: Token::Address(self.to.clone()) },
+                EventParam { name: "value".into(), value: Token::Uint(self.value.clone()) },
+            ]
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for TransferOutputs {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "output0".into(), value: Token::Bool(self.output0) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for TransferInputs {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                to: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                value: match params.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for TransferOutputs {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                output0: match params.get(0) { Some(Token::Bool(b)) => *b, _ => false },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::CallData for TransferInputs {
+fn from_call_data(data: &[u8]) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let data = data.strip_prefix(&[0xa9, 0x05, 0x9c, 0xbb])?;
+#[allow(unused_variables)]
+let values = abi::decode(&[ParamType::parse("address")?, ParamType::parse("uint256")?], data)?;
+Some(Self {
+    to: match values.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    value: match values.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+
+fn to_call_data(&self) -> Vec<u8> {
+let mut data = vec![0xa9, 0x05, 0x9c, 0xbb];
+data.extend(yogurt_runtime::ethereum::abi::encode(&[Token::Address(self.to.clone()), Token::Uint(self.value.clone())]).unwrap_or_default());
+data
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::CallData for TransferOutputs {
+fn from_call_data(data: &[u8]) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+#[allow(unused_variables)]
+let values = abi::decode(&[ParamType::parse("bool")?], data)?;
+Some(Self {
+    output0: match values.get(0) { Some(Token::Bool(b)) => *b, _ => false },
+})
+}
+
+fn to_call_data(&self) -> Vec<u8> {
+let mut data = Vec::new();
+data.extend(yogurt_runtime::ethereum::abi::encode(&[Token::Bool(self.output0)]).unwrap_or_default());
+data
+}
+}
+
+/// Custom errors of the ERC20 contract, decoded from the revert data of
+/// its `try_*` calls.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ERC20Error {
+}
+
+impl ContractError for ERC20Error {
+fn decode(data: &[u8]) -> Option<Self> {
+let _ = data;
+None
+}
+
+fn encode(&self) -> Vec<u8> {
+match *self {}
+}
+}
+
 /// Binding for the ERC20 contract.
 pub struct ERC20 {
 address: Address,
@@ -246,7 +590,13 @@ Self { address }
 
     /// Call `balanceOf`
 pub fn balance_of(&self, owner: Address) -> Result<BigInt, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_balance_of(owner).map_err(|e| e.to_string())
+}
+
+/// Try to call `balanceOf`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_balance_of(&self, owner: Address) -> Result<BigInt, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -256,19 +606,21 @@ function_signature: "balanceOf(address)".into(),
 function_params: vec![Token::Address(owner.clone())],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() };
 Ok(value)
-}
-
-/// Try to call `balanceOf`, returning None on revert.
-pub fn try_balance_of(&self, owner: Address) -> Option<BigInt> {
-self.balance_of(owner).ok()
 }
 
     /// Call `decimals`
 pub fn decimals(&self) -> Result<u64, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_decimals().map_err(|e| e.to_string())
+}
+
+/// Try to call `decimals`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_decimals(&self) -> Result<u64, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -278,19 +630,21 @@ function_signature: "decimals()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => { let s = n.to_string(); s.parse::<u64>().unwrap_or(0) }, _ => 0 };
 Ok(value)
-}
-
-/// Try to call `decimals`, returning None on revert.
-pub fn try_decimals(&self) -> Option<u64> {
-self.decimals().ok()
 }
 
     /// Call `name`
 pub fn name(&self) -> Result<String, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_name().map_err(|e| e.to_string())
+}
+
+/// Try to call `name`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_name(&self) -> Result<String, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -300,19 +654,21 @@ function_signature: "name()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::String(s)) => s.clone(), _ => String::new() };
 Ok(value)
-}
-
-/// Try to call `name`, returning None on revert.
-pub fn try_name(&self) -> Option<String> {
-self.name().ok()
 }
 
     /// Call `symbol`
 pub fn symbol(&self) -> Result<String, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_symbol().map_err(|e| e.to_string())
+}
+
+/// Try to call `symbol`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_symbol(&self) -> Result<String, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -322,19 +678,21 @@ function_signature: "symbol()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::String(s)) => s.clone(), _ => String::new() };
 Ok(value)
-}
-
-/// Try to call `symbol`, returning None on revert.
-pub fn try_symbol(&self) -> Option<String> {
-self.symbol().ok()
 }
 
     /// Call `totalSupply`
 pub fn total_supply(&self) -> Result<BigInt, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_total_supply().map_err(|e| e.to_string())
+}
+
+/// Try to call `totalSupply`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_total_supply(&self) -> Result<BigInt, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -344,14 +702,10 @@ function_signature: "totalSupply()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() };
 Ok(value)
 }
 
-/// Try to call `totalSupply`, returning None on revert.
-pub fn try_total_supply(&self) -> Option<BigInt> {
-self.total_supply().ok()
-}
-
 }
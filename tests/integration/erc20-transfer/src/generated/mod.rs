@@ -2,7 +2,11 @@
 
 mod schema;
 mod erc20;
+#[cfg(not(target_arch = "wasm32"))]
+mod dispatch;
 pub mod templates;
 
 pub use schema::*;
 pub use erc20::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use dispatch::*;
@@ -1,6 +1,7 @@
 //! Auto-generated data source templates — do not edit
 
-use alloc::string::ToString;
+#[allow(unused_imports)]
+use alloc::string::{String, ToString};
 use yogurt_runtime::prelude::Address;
 use yogurt_runtime::data_source;
 
@@ -30,6 +30,9 @@ pub fn handle_transfer_call(_call: crate::generated::TransferCall) {
 
 /// Handle token metadata from IPFS.
 #[handler]
-pub fn handle_metadata(_content: Bytes) {
-    // IPFS handler not needed for this example
+pub fn handle_metadata(content: Bytes) {
+    // Metadata is not indexed in this example, but a file that is not a JSON
+    // object fails the handler, which graph-node reports with the panic's
+    // message and location
+    assert!(content.as_slice().starts_with(b"{"), "token metadata is not a JSON object");
 }
@@ -0,0 +1,78 @@
+//! Auto-generated dispatch table of the manifest's handlers — do not edit
+
+/// The manifest's data sources and templates as a
+/// `yogurt_runtime::testing::DispatchTable`, for `testing::Chain`.
+///
+/// Runs the handler functions of `crate::mappings`, which are named
+/// after the manifest's handlers in snake case:
+///
+/// ```ignore
+/// let mut chain = Chain::new(dispatch_table());
+/// ```
+#[allow(dead_code)]
+pub fn dispatch_table() -> yogurt_runtime::testing::DispatchTable {
+yogurt_runtime::testing::DispatchTable {
+data_sources: alloc::vec![
+yogurt_runtime::testing::DataSourceSpec {
+name: "Factory",
+address: Some(yogurt_runtime::prelude::Address::from_string("0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f")
+.expect("data source `Factory` has an invalid address")),
+start_block: 10000835,
+events: alloc::vec![
+yogurt_runtime::testing::EventHandlerSpec {
+handler: "handlePairCreated",
+filter: yogurt_runtime::prelude::TopicFilter::ANY,
+run: |log, block, transaction| {
+yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::handle_pair_created)
+},
+},
+],
+calls: alloc::vec![
+],
+blocks: alloc::vec![
+],
+},
+],
+templates: alloc::vec![
+yogurt_runtime::testing::DataSourceSpec {
+name: "Pair",
+address: None,
+start_block: 0,
+events: alloc::vec![
+yogurt_runtime::testing::EventHandlerSpec {
+handler: "handleSwap",
+filter: yogurt_runtime::prelude::TopicFilter::ANY,
+run: |log, block, transaction| {
+yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::handle_swap)
+},
+},
+yogurt_runtime::testing::EventHandlerSpec {
+handler: "handleMint",
+filter: yogurt_runtime::prelude::TopicFilter::ANY,
+run: |log, block, transaction| {
+yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::handle_mint)
+},
+},
+yogurt_runtime::testing::EventHandlerSpec {
+handler: "handleBurn",
+filter: yogurt_runtime::prelude::TopicFilter::ANY,
+run: |log, block, transaction| {
+yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::handle_burn)
+},
+},
+yogurt_runtime::testing::EventHandlerSpec {
+handler: "handleSync",
+filter: yogurt_runtime::prelude::TopicFilter::ANY,
+run: |log, block, transaction| {
+yogurt_runtime::testing::run_event(log, block, transaction, crate::mappings::handle_sync)
+},
+},
+],
+calls: alloc::vec![
+],
+blocks: alloc::vec![
+],
+},
+],
+}
+}
@@ -7,7 +7,24 @@ use alloc::vec;
 #[allow(unused_imports)]
 use alloc::vec::Vec;
 use yogurt_runtime::prelude::*;
-use yogurt_runtime::ethereum::{Call, Event, SmartContractCall, Token};
+use yogurt_runtime::ethereum::{Call, CallError, ContractError, Event, FromLog, Log, SmartContractCall, Token};
+
+/// Custom errors of the ERC20 contract, decoded from the revert data of
+/// its `try_*` calls.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ERC20Error {
+}
+
+impl ContractError for ERC20Error {
+fn decode(data: &[u8]) -> Option<Self> {
+let _ = data;
+None
+}
+
+fn encode(&self) -> Vec<u8> {
+match *self {}
+}
+}
 
 /// Binding for the ERC20 contract.
 pub struct ERC20 {
@@ -22,7 +39,13 @@ Self { address }
 
     /// Call `decimals`
 pub fn decimals(&self) -> Result<u64, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_decimals().map_err(|e| e.to_string())
+}
+
+/// Try to call `decimals`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_decimals(&self) -> Result<u64, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -32,19 +55,21 @@ function_signature: "decimals()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => { let s = n.to_string(); s.parse::<u64>().unwrap_or(0) }, _ => 0 };
 Ok(value)
-}
-
-/// Try to call `decimals`, returning None on revert.
-pub fn try_decimals(&self) -> Option<u64> {
-self.decimals().ok()
 }
 
     /// Call `name`
 pub fn name(&self) -> Result<String, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_name().map_err(|e| e.to_string())
+}
+
+/// Try to call `name`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_name(&self) -> Result<String, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -54,19 +79,21 @@ function_signature: "name()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::String(s)) => s.clone(), _ => String::new() };
 Ok(value)
-}
-
-/// Try to call `name`, returning None on revert.
-pub fn try_name(&self) -> Option<String> {
-self.name().ok()
 }
 
     /// Call `symbol`
 pub fn symbol(&self) -> Result<String, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_symbol().map_err(|e| e.to_string())
+}
+
+/// Try to call `symbol`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_symbol(&self) -> Result<String, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -76,19 +103,21 @@ function_signature: "symbol()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::String(s)) => s.clone(), _ => String::new() };
 Ok(value)
-}
-
-/// Try to call `symbol`, returning None on revert.
-pub fn try_symbol(&self) -> Option<String> {
-self.symbol().ok()
 }
 
     /// Call `totalSupply`
 pub fn total_supply(&self) -> Result<BigInt, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_total_supply().map_err(|e| e.to_string())
+}
+
+/// Try to call `totalSupply`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_total_supply(&self) -> Result<BigInt, CallError<ERC20Error>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "ERC20".into(),
@@ -98,14 +127,10 @@ function_signature: "totalSupply()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() };
 Ok(value)
 }
 
-/// Try to call `totalSupply`, returning None on revert.
-pub fn try_total_supply(&self) -> Option<BigInt> {
-self.total_supply().ok()
-}
-
 }
@@ -4,9 +4,13 @@ mod schema;
 mod uniswapv2factory;
 mod uniswapv2pair;
 mod erc20;
+#[cfg(not(target_arch = "wasm32"))]
+mod dispatch;
 pub mod templates;
 
 pub use schema::*;
 pub use uniswapv2factory::*;
 pub use uniswapv2pair::*;
 pub use erc20::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use dispatch::*;
@@ -6,6 +6,8 @@ use alloc::string::String;
 use alloc::string::ToString;
 #[allow(unused_imports)]
 use alloc::vec::Vec;
+#[allow(unused_imports)]
+use alloc::boxed::Box;
 use yogurt_runtime::prelude::*;
 use yogurt_runtime::store;
 use yogurt_runtime::types::{EntityData, Value};
@@ -52,25 +54,27 @@ Self { data }
 }
 
 impl Entity for Factory {
+type Id = String;
+
 const ENTITY_TYPE: &'static str = "Factory";
 
-fn id(&self) -> &str {
-self.data.get_string("id")
+fn id(&self) -> &Self::Id {
+self.data.get_id()
 }
 
 fn save(&self) {
-store::set(Self::ENTITY_TYPE, self.id(), &self.data);
+store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);
 }
 
-fn load(id: &str) -> Option<Self> {
-store::get(Self::ENTITY_TYPE, id).map(|data| Self { data })
+fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {
+store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self { data })
 }
 
-fn remove(id: &str) {
-store::remove(Self::ENTITY_TYPE, id);
+fn remove(id: impl EntityKey<Self::Id>) {
+store::remove(Self::ENTITY_TYPE, &id.to_key());
 }
 
-fn create(id: impl Into<String>) -> Self {
+fn create(id: impl Into<Self::Id>) -> Self {
 Self::new(id)
 }
 }
@@ -213,25 +217,27 @@ Self { data }
 }
 
 impl Entity for Token {
+type Id = String;
+
 const ENTITY_TYPE: &'static str = "Token";
 
-fn id(&self) -> &str {
-self.data.get_string("id")
+fn id(&self) -> &Self::Id {
+self.data.get_id()
 }
 
 fn save(&self) {
-store::set(Self::ENTITY_TYPE, self.id(), &self.data);
+store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);
 }
 
-fn load(id: &str) -> Option<Self> {
-store::get(Self::ENTITY_TYPE, id).map(|data| Self { data })
+fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {
+store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self { data })
 }
 
-fn remove(id: &str) {
-store::remove(Self::ENTITY_TYPE, id);
+fn remove(id: impl EntityKey<Self::Id>) {
+store::remove(Self::ENTITY_TYPE, &id.to_key());
 }
 
-fn create(id: impl Into<String>) -> Self {
+fn create(id: impl Into<Self::Id>) -> Self {
 Self::new(id)
 }
 }
@@ -408,25 +414,27 @@ Self { data }
 }
 
 impl Entity for Pair {
+type Id = String;
+
 const ENTITY_TYPE: &'static str = "Pair";
 
-fn id(&self) -> &str {
-self.data.get_string("id")
+fn id(&self) -> &Self::Id {
+self.data.get_id()
 }
 
 fn save(&self) {
-store::set(Self::ENTITY_TYPE, self.id(), &self.data);
+store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);
 }
 
-fn load(id: &str) -> Option<Self> {
-store::get(Self::ENTITY_TYPE, id).map(|data| Self { data })
+fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {
+store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self { data })
 }
 
-fn remove(id: &str) {
-store::remove(Self::ENTITY_TYPE, id);
+fn remove(id: impl EntityKey<Self::Id>) {
+store::remove(Self::ENTITY_TYPE, &id.to_key());
 }
 
-fn create(id: impl Into<String>) -> Self {
+fn create(id: impl Into<Self::Id>) -> Self {
 Self::new(id)
 }
 }
@@ -632,25 +640,27 @@ Self { data }
 }
 
 impl Entity for Swap {
+type Id = String;
+
 const ENTITY_TYPE: &'static str = "Swap";
 
-fn id(&self) -> &str {
-self.data.get_string("id")
+fn id(&self) -> &Self::Id {
+self.data.get_id()
 }
 
 fn save(&self) {
-store::set(Self::ENTITY_TYPE, self.id(), &self.data);
+store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);
 }
 
-fn load(id: &str) -> Option<Self> {
-store::get(Self::ENTITY_TYPE, id).map(|data| Self { data })
+fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {
+store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self { data })
 }
 
-fn remove(id: &str) {
-store::remove(Self::ENTITY_TYPE, id);
+fn remove(id: impl EntityKey<Self::Id>) {
+store::remove(Self::ENTITY_TYPE, &id.to_key());
 }
 
-fn create(id: impl Into<String>) -> Self {
+fn create(id: impl Into<Self::Id>) -> Self {
 Self::new(id)
 }
 }
@@ -852,25 +862,27 @@ Self { data }
 }
 
 impl Entity for Mint {
+type Id = String;
+
 const ENTITY_TYPE: &'static str = "Mint";
 
-fn id(&self) -> &str {
-self.data.get_string("id")
+fn id(&self) -> &Self::Id {
+self.data.get_id()
 }
 
 fn save(&self) {
-store::set(Self::ENTITY_TYPE, self.id(), &self.data);
+store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);
 }
 
-fn load(id: &str) -> Option<Self> {
-store::get(Self::ENTITY_TYPE, id).map(|data| Self { data })
+fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {
+store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self { data })
 }
 
-fn remove(id: &str) {
-store::remove(Self::ENTITY_TYPE, id);
+fn remove(id: impl EntityKey<Self::Id>) {
+store::remove(Self::ENTITY_TYPE, &id.to_key());
 }
 
-fn create(id: impl Into<String>) -> Self {
+fn create(id: impl Into<Self::Id>) -> Self {
 Self::new(id)
 }
 }
@@ -1060,25 +1072,27 @@ Self { data }
 }
 
 impl Entity for Burn {
+type Id = String;
+
 const ENTITY_TYPE: &'static str = "Burn";
 
-fn id(&self) -> &str {
-self.data.get_string("id")
+fn id(&self) -> &Self::Id {
+self.data.get_id()
 }
 
 fn save(&self) {
-store::set(Self::ENTITY_TYPE, self.id(), &self.data);
+store::set(Self::ENTITY_TYPE, &Entity::id(self).to_key(), &self.data);
 }
 
-fn load(id: &str) -> Option<Self> {
-store::get(Self::ENTITY_TYPE, id).map(|data| Self { data })
+fn load(id: impl EntityKey<Self::Id>) -> Option<Self> {
+store::get(Self::ENTITY_TYPE, &id.to_key()).map(|data| Self { data })
 }
 
-fn remove(id: &str) {
-store::remove(Self::ENTITY_TYPE, id);
+fn remove(id: impl EntityKey<Self::Id>) {
+store::remove(Self::ENTITY_TYPE, &id.to_key());
 }
 
-fn create(id: impl Into<String>) -> Self {
+fn create(id: impl Into<Self::Id>) -> Self {
 Self::new(id)
 }
 }
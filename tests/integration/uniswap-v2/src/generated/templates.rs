@@ -1,9 +1,14 @@
 //! Auto-generated data source templates — do not edit
 
-use alloc::string::ToString;
+#[allow(unused_imports)]
+use alloc::string::{String, ToString};
 use yogurt_runtime::prelude::Address;
 use yogurt_runtime::data_source;
 
+#[allow(unused_imports)]
+use yogurt_runtime::prelude::{BigDecimal, BigInt, Bytes, Value};
+use yogurt_runtime::types::EntityData;
+
 /// Data source template: `Pair`
 ///
 /// Kind: `ethereum`
@@ -13,6 +18,50 @@ impl Pair {
     /// Create a new data source instance for the given contract address.
 pub fn create(address: &Address) {
 data_source::create("Pair", &[address.to_hex()]);
+}
+
+    /// Create a new data source instance for the given contract address, with
+/// a context its handlers read with `PairContext::current()`.
+pub fn create_with_context(address: &Address, context: PairContext) {
+data_source::create_with_context("Pair", &[address.to_hex()], context.to_entity());
+}
+}
+
+/// Context of `Pair` data sources, declared under `context` in the
+/// manifest and set by `Pair::create_with_context`.
+#[derive(Clone, Debug)]
+pub struct PairContext {
+    pub token0: Address,
+    pub token1: Address,
+}
+
+impl PairContext {
+/// The context of the current data source, or `None` if a field is
+/// missing or of another type, as in a data source created without it.
+pub fn current() -> Option<Self> {
+Self::from_entity(&data_source::context())
+}
+
+/// Read the fields from a context entity.
+pub fn from_entity(context: &EntityData) -> Option<Self> {
+Some(Self {
+token0: match context.get("token0")? {
+Value::Bytes(v) => Address::from(v.as_slice()),
+_ => return None,
+},
+token1: match context.get("token1")? {
+Value::Bytes(v) => Address::from(v.as_slice()),
+_ => return None,
+},
+})
+}
+
+/// The context entity graph-node stores with the data source.
+pub fn to_entity(&self) -> EntityData {
+let mut context = EntityData::new();
+context.set("token0", Value::Bytes(Bytes::from(&self.token0)));
+context.set("token1", Value::Bytes(Bytes::from(&self.token1)));
+context
 }
 }
 
@@ -7,7 +7,7 @@ use alloc::vec;
 #[allow(unused_imports)]
 use alloc::vec::Vec;
 use yogurt_runtime::prelude::*;
-use yogurt_runtime::ethereum::{Call, Event, SmartContractCall, Token};
+use yogurt_runtime::ethereum::{Call, CallError, ContractError, Event, FromLog, Log, SmartContractCall, Token};
 
 /// PairCreated(address token0, address token1, address pair, uint256 pairIndex)
 pub struct PairCreatedParams {
@@ -17,56 +17,145 @@ pub struct PairCreatedParams {
     pub pair_index: BigInt,
 }
 
+impl PairCreatedParams {
+/// First topic of its logs, the keccak256 hash of `PairCreated(address,address,address,uint256)`.
+pub const TOPIC0: [u8; 32] = [0x0d, 0x36, 0x48, 0xbd, 0x0f, 0x6b, 0xa8, 0x01, 0x34, 0xa3, 0x3b, 0xa9, 0x27, 0x5a, 0xc5, 0x85, 0xd9, 0xd3, 0x15, 0xf0, 0xad, 0x83, 0x55, 0xcd, 0xde, 0xfd, 0xe3, 0x1a, 0xfa, 0x28, 0xd0, 0xe9];
+}
+
+impl FromLog for PairCreatedParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 2 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+    abi::decode_value(&ParamType::parse("address")?, topics[1].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("address")?, ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    token0: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    token1: match indexed.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    pair: match data.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    pair_index: match data.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for PairCreatedParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.token0.clone())]).unwrap_or_default()),
+    Bytes::from(abi::encode(&[Token::Address(self.token1.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Address(self.pair.clone()), Token::Uint(self.pair_index.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type PairCreatedEvent = Event<PairCreatedParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for PairCreatedParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let token0 = if param0_ptr == 0 {
+Address::zero()
+} else {
 let token0_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let token0_bytes = asc_to_bytes(AscPtr::new(token0_value_ptr));
-let token0 = Address::from(token0_bytes.as_slice());
+if token0_value_ptr == 0 {
+Address::zero()
+} else {
+let token0_payload = read_u64_at(token0_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let token0_bytes = asc_to_bytes(AscPtr::new(token0_payload));
+Address::from(token0_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let token1 = if param1_ptr == 0 {
+Address::zero()
+} else {
 let token1_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let token1_bytes = asc_to_bytes(AscPtr::new(token1_value_ptr));
-let token1 = Address::from(token1_bytes.as_slice());
+if token1_value_ptr == 0 {
+Address::zero()
+} else {
+let token1_payload = read_u64_at(token1_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let token1_bytes = asc_to_bytes(AscPtr::new(token1_payload));
+Address::from(token1_bytes.as_slice())
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let pair = if param2_ptr == 0 {
+Address::zero()
+} else {
 let pair_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let pair_bytes = asc_to_bytes(AscPtr::new(pair_value_ptr));
-let pair = Address::from(pair_bytes.as_slice());
+if pair_value_ptr == 0 {
+Address::zero()
+} else {
+let pair_payload = read_u64_at(pair_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let pair_bytes = asc_to_bytes(AscPtr::new(pair_payload));
+Address::from(pair_bytes.as_slice())
+}
+};
 
                     let param3_ptr = read_u32_at(buffer_ptr, 12);
+let pair_index = if param3_ptr == 0 {
+BigInt::zero()
+} else {
 let pair_index_value_ptr = read_u32_at(param3_ptr, EVENT_PARAM_VALUE_OFFSET);
-let pair_index = BigInt::from_ptr(AscPtr::new(pair_index_value_ptr));
+if pair_index_value_ptr == 0 {
+BigInt::zero()
+} else {
+let pair_index_payload = read_u64_at(pair_index_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(pair_index_payload)
+}
+};
 
 Self { token0, token1, pair, pair_index }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for PairCreatedParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for PairCreatedParams {
 fn default() -> Self {
 Self {
@@ -78,6 +167,50 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for PairCreatedParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "token0".into(), value: Token::Address(self.token0.clone()) },
+                EventParam { name: "token1".into(), value: Token::Address(self.token1.clone()) },
+                EventParam { name: "pair".into(), value: Token::Address(self.pair.clone()) },
+                EventParam { name: "pairIndex".into(), value: Token::Uint(self.pair_index.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for PairCreatedParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                token0: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                token1: match params.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                pair: match params.get(2) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                pair_index: match params.get(3) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+/// Custom errors of the UniswapV2Factory contract, decoded from the revert data of
+/// its `try_*` calls.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniswapV2FactoryError {
+}
+
+impl ContractError for UniswapV2FactoryError {
+fn decode(data: &[u8]) -> Option<Self> {
+let _ = data;
+None
+}
+
+fn encode(&self) -> Vec<u8> {
+match *self {}
+}
+}
+
 /// Binding for the UniswapV2Factory contract.
 pub struct UniswapV2Factory {
 address: Address,
@@ -91,7 +224,13 @@ Self { address }
 
     /// Call `allPairs`
 pub fn all_pairs(&self, param0: BigInt) -> Result<Address, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_all_pairs(param0).map_err(|e| e.to_string())
+}
+
+/// Try to call `allPairs`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_all_pairs(&self, param0: BigInt) -> Result<Address, CallError<UniswapV2FactoryError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Factory".into(),
@@ -101,19 +240,21 @@ function_signature: "allPairs(uint256)".into(),
 function_params: vec![Token::Uint(param0.clone())],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() };
 Ok(value)
-}
-
-/// Try to call `allPairs`, returning None on revert.
-pub fn try_all_pairs(&self, param0: BigInt) -> Option<Address> {
-self.all_pairs(param0).ok()
 }
 
     /// Call `allPairsLength`
 pub fn all_pairs_length(&self) -> Result<BigInt, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_all_pairs_length().map_err(|e| e.to_string())
+}
+
+/// Try to call `allPairsLength`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_all_pairs_length(&self) -> Result<BigInt, CallError<UniswapV2FactoryError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Factory".into(),
@@ -123,19 +264,21 @@ function_signature: "allPairsLength()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() };
 Ok(value)
-}
-
-/// Try to call `allPairsLength`, returning None on revert.
-pub fn try_all_pairs_length(&self) -> Option<BigInt> {
-self.all_pairs_length().ok()
 }
 
     /// Call `getPair`
 pub fn get_pair(&self, param0: Address, param1: Address) -> Result<Address, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_get_pair(param0, param1).map_err(|e| e.to_string())
+}
+
+/// Try to call `getPair`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_get_pair(&self, param0: Address, param1: Address) -> Result<Address, CallError<UniswapV2FactoryError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Factory".into(),
@@ -145,14 +288,10 @@ function_signature: "getPair(address,address)".into(),
 function_params: vec![Token::Address(param0.clone()), Token::Address(param1.clone())],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() };
 Ok(value)
 }
 
-/// Try to call `getPair`, returning None on revert.
-pub fn try_get_pair(&self, param0: Address, param1: Address) -> Option<Address> {
-self.get_pair(param0, param1).ok()
-}
-
 }
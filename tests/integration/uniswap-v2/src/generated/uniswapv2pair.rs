@@ -7,7 +7,7 @@ use alloc::vec;
 #[allow(unused_imports)]
 use alloc::vec::Vec;
 use yogurt_runtime::prelude::*;
-use yogurt_runtime::ethereum::{Call, Event, SmartContractCall, Token};
+use yogurt_runtime::ethereum::{Call, CallError, ContractError, Event, FromLog, Log, SmartContractCall, Token};
 
 /// Burn(address sender, uint256 amount0, uint256 amount1, address to)
 pub struct BurnParams {
@@ -17,55 +17,144 @@ pub struct BurnParams {
     pub to: Address,
 }
 
+impl BurnParams {
+/// First topic of its logs, the keccak256 hash of `Burn(address,uint256,uint256,address)`.
+pub const TOPIC0: [u8; 32] = [0xdc, 0xcd, 0x41, 0x2f, 0x0b, 0x12, 0x52, 0x81, 0x9c, 0xb1, 0xfd, 0x33, 0x0b, 0x93, 0x22, 0x4c, 0xa4, 0x26, 0x12, 0x89, 0x2b, 0xb3, 0xf4, 0xf7, 0x89, 0x97, 0x6e, 0x6d, 0x81, 0x93, 0x64, 0x96];
+}
+
+impl FromLog for BurnParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 2 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+    abi::decode_value(&ParamType::parse("address")?, topics[1].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("uint256")?, ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    sender: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    amount0: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    amount1: match data.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    to: match indexed.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for BurnParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.sender.clone())]).unwrap_or_default()),
+    Bytes::from(abi::encode(&[Token::Address(self.to.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.amount0.clone()), Token::Uint(self.amount1.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type BurnEvent = Event<BurnParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for BurnParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let sender = if param0_ptr == 0 {
+Address::zero()
+} else {
 let sender_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let sender_bytes = asc_to_bytes(AscPtr::new(sender_value_ptr));
-let sender = Address::from(sender_bytes.as_slice());
+if sender_value_ptr == 0 {
+Address::zero()
+} else {
+let sender_payload = read_u64_at(sender_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let sender_bytes = asc_to_bytes(AscPtr::new(sender_payload));
+Address::from(sender_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let amount0 = if param1_ptr == 0 {
+BigInt::zero()
+} else {
 let amount0_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount0 = BigInt::from_ptr(AscPtr::new(amount0_value_ptr));
+if amount0_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount0_payload = read_u64_at(amount0_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount0_payload)
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let amount1 = if param2_ptr == 0 {
+BigInt::zero()
+} else {
 let amount1_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount1 = BigInt::from_ptr(AscPtr::new(amount1_value_ptr));
+if amount1_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount1_payload = read_u64_at(amount1_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount1_payload)
+}
+};
 
                     let param3_ptr = read_u32_at(buffer_ptr, 12);
+let to = if param3_ptr == 0 {
+Address::zero()
+} else {
 let to_value_ptr = read_u32_at(param3_ptr, EVENT_PARAM_VALUE_OFFSET);
-let to_bytes = asc_to_bytes(AscPtr::new(to_value_ptr));
-let to = Address::from(to_bytes.as_slice());
+if to_value_ptr == 0 {
+Address::zero()
+} else {
+let to_payload = read_u64_at(to_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let to_bytes = asc_to_bytes(AscPtr::new(to_payload));
+Address::from(to_bytes.as_slice())
+}
+};
 
 Self { sender, amount0, amount1, to }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for BurnParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for BurnParams {
 fn default() -> Self {
 Self {
@@ -77,6 +166,33 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for BurnParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "sender".into(), value: Token::Address(self.sender.clone()) },
+                EventParam { name: "amount0".into(), value: Token::Uint(self.amount0.clone()) },
+                EventParam { name: "amount1".into(), value: Token::Uint(self.amount1.clone()) },
+                EventParam { name: "to".into(), value: Token::Address(self.to.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for BurnParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                sender: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                amount0: match params.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                amount1: match params.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                to: match params.get(3) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+})
+}
+}
+
 /// Mint(address sender, uint256 amount0, uint256 amount1)
 pub struct MintParams {
     pub sender: Address,
@@ -84,50 +200,127 @@ pub struct MintParams {
     pub amount1: BigInt,
 }
 
+impl MintParams {
+/// First topic of its logs, the keccak256 hash of `Mint(address,uint256,uint256)`.
+pub const TOPIC0: [u8; 32] = [0x4c, 0x20, 0x9b, 0x5f, 0xc8, 0xad, 0x50, 0x75, 0x8f, 0x13, 0xe2, 0xe1, 0x08, 0x8b, 0xa5, 0x6a, 0x56, 0x0d, 0xff, 0x69, 0x0a, 0x1c, 0x6f, 0xef, 0x26, 0x39, 0x4f, 0x4c, 0x03, 0x82, 0x1c, 0x4f];
+}
+
+impl FromLog for MintParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 1 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("uint256")?, ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    sender: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    amount0: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    amount1: match data.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for MintParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.sender.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.amount0.clone()), Token::Uint(self.amount1.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type MintEvent = Event<MintParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for MintParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let sender = if param0_ptr == 0 {
+Address::zero()
+} else {
 let sender_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let sender_bytes = asc_to_bytes(AscPtr::new(sender_value_ptr));
-let sender = Address::from(sender_bytes.as_slice());
+if sender_value_ptr == 0 {
+Address::zero()
+} else {
+let sender_payload = read_u64_at(sender_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let sender_bytes = asc_to_bytes(AscPtr::new(sender_payload));
+Address::from(sender_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let amount0 = if param1_ptr == 0 {
+BigInt::zero()
+} else {
 let amount0_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount0 = BigInt::from_ptr(AscPtr::new(amount0_value_ptr));
+if amount0_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount0_payload = read_u64_at(amount0_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount0_payload)
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let amount1 = if param2_ptr == 0 {
+BigInt::zero()
+} else {
 let amount1_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount1 = BigInt::from_ptr(AscPtr::new(amount1_value_ptr));
+if amount1_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount1_payload = read_u64_at(amount1_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount1_payload)
+}
+};
 
 Self { sender, amount0, amount1 }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for MintParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for MintParams {
 fn default() -> Self {
 Self {
@@ -138,6 +331,31 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for MintParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "sender".into(), value: Token::Address(self.sender.clone()) },
+                EventParam { name: "amount0".into(), value: Token::Uint(self.amount0.clone()) },
+                EventParam { name: "amount1".into(), value: Token::Uint(self.amount1.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for MintParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                sender: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                amount0: match params.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                amount1: match params.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
 /// Swap(address sender, uint256 amount0In, uint256 amount1In, uint256 amount0Out, uint256 amount1Out, address to)
 pub struct SwapParams {
     pub sender: Address,
@@ -148,63 +366,172 @@ pub struct SwapParams {
     pub to: Address,
 }
 
+impl SwapParams {
+/// First topic of its logs, the keccak256 hash of `Swap(address,uint256,uint256,uint256,uint256,address)`.
+pub const TOPIC0: [u8; 32] = [0xd7, 0x8a, 0xd9, 0x5f, 0xa4, 0x6c, 0x99, 0x4b, 0x65, 0x51, 0xd0, 0xda, 0x85, 0xfc, 0x27, 0x5f, 0xe6, 0x13, 0xce, 0x37, 0x65, 0x7f, 0xb8, 0xd5, 0xe3, 0xd1, 0x30, 0x84, 0x01, 0x59, 0xd8, 0x22];
+}
+
+impl FromLog for SwapParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 2 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+    abi::decode_value(&ParamType::parse("address")?, topics[1].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("uint256")?, ParamType::parse("uint256")?, ParamType::parse("uint256")?, ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    sender: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    amount0_in: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    amount1_in: match data.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    amount0_out: match data.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    amount1_out: match data.get(3) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    to: match indexed.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for SwapParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.sender.clone())]).unwrap_or_default()),
+    Bytes::from(abi::encode(&[Token::Address(self.to.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.amount0_in.clone()), Token::Uint(self.amount1_in.clone()), Token::Uint(self.amount0_out.clone()), Token::Uint(self.amount1_out.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type SwapEvent = Event<SwapParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for SwapParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let sender = if param0_ptr == 0 {
+Address::zero()
+} else {
 let sender_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let sender_bytes = asc_to_bytes(AscPtr::new(sender_value_ptr));
-let sender = Address::from(sender_bytes.as_slice());
+if sender_value_ptr == 0 {
+Address::zero()
+} else {
+let sender_payload = read_u64_at(sender_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let sender_bytes = asc_to_bytes(AscPtr::new(sender_payload));
+Address::from(sender_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let amount0_in = if param1_ptr == 0 {
+BigInt::zero()
+} else {
 let amount0_in_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount0_in = BigInt::from_ptr(AscPtr::new(amount0_in_value_ptr));
+if amount0_in_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount0_in_payload = read_u64_at(amount0_in_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount0_in_payload)
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let amount1_in = if param2_ptr == 0 {
+BigInt::zero()
+} else {
 let amount1_in_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount1_in = BigInt::from_ptr(AscPtr::new(amount1_in_value_ptr));
+if amount1_in_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount1_in_payload = read_u64_at(amount1_in_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount1_in_payload)
+}
+};
 
                     let param3_ptr = read_u32_at(buffer_ptr, 12);
+let amount0_out = if param3_ptr == 0 {
+BigInt::zero()
+} else {
 let amount0_out_value_ptr = read_u32_at(param3_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount0_out = BigInt::from_ptr(AscPtr::new(amount0_out_value_ptr));
+if amount0_out_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount0_out_payload = read_u64_at(amount0_out_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount0_out_payload)
+}
+};
 
                     let param4_ptr = read_u32_at(buffer_ptr, 16);
+let amount1_out = if param4_ptr == 0 {
+BigInt::zero()
+} else {
 let amount1_out_value_ptr = read_u32_at(param4_ptr, EVENT_PARAM_VALUE_OFFSET);
-let amount1_out = BigInt::from_ptr(AscPtr::new(amount1_out_value_ptr));
+if amount1_out_value_ptr == 0 {
+BigInt::zero()
+} else {
+let amount1_out_payload = read_u64_at(amount1_out_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(amount1_out_payload)
+}
+};
 
                     let param5_ptr = read_u32_at(buffer_ptr, 20);
+let to = if param5_ptr == 0 {
+Address::zero()
+} else {
 let to_value_ptr = read_u32_at(param5_ptr, EVENT_PARAM_VALUE_OFFSET);
-let to_bytes = asc_to_bytes(AscPtr::new(to_value_ptr));
-let to = Address::from(to_bytes.as_slice());
+if to_value_ptr == 0 {
+Address::zero()
+} else {
+let to_payload = read_u64_at(to_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let to_bytes = asc_to_bytes(AscPtr::new(to_payload));
+Address::from(to_bytes.as_slice())
+}
+};
 
 Self { sender, amount0_in, amount1_in, amount0_out, amount1_out, to }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for SwapParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for SwapParams {
 fn default() -> Self {
 Self {
@@ -218,51 +545,145 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for SwapParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "sender".into(), value: Token::Address(self.sender.clone()) },
+                EventParam { name: "amount0In".into(), value: Token::Uint(self.amount0_in.clone()) },
+                EventParam { name: "amount1In".into(), value: Token::Uint(self.amount1_in.clone()) },
+                EventParam { name: "amount0Out".into(), value: Token::Uint(self.amount0_out.clone()) },
+                EventParam { name: "amount1Out".into(), value: Token::Uint(self.amount1_out.clone()) },
+                EventParam { name: "to".into(), value: Token::Address(self.to.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for SwapParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                sender: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                amount0_in: match params.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                amount1_in: match params.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                amount0_out: match params.get(3) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                amount1_out: match params.get(4) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                to: match params.get(5) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+})
+}
+}
+
 /// Sync(uint112 reserve0, uint112 reserve1)
 pub struct SyncParams {
     pub reserve0: BigInt,
     pub reserve1: BigInt,
 }
 
+impl SyncParams {
+/// First topic of its logs, the keccak256 hash of `Sync(uint112,uint112)`.
+pub const TOPIC0: [u8; 32] = [0x1c, 0x41, 0x1e, 0x9a, 0x96, 0xe0, 0x71, 0x24, 0x1c, 0x2f, 0x21, 0xf7, 0x72, 0x6b, 0x17, 0xae, 0x89, 0xe3, 0xca, 0xb4, 0xc7, 0x8b, 0xe5, 0x0e, 0x06, 0x2b, 0x03, 0xa9, 0xff, 0xfb, 0xba, 0xd1];
+}
+
+impl FromLog for SyncParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 0 {
+return None;
+}
+let data = abi::decode(&[ParamType::parse("uint112")?, ParamType::parse("uint112")?], log.data.as_slice())?;
+Some(Self {
+    reserve0: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+    reserve1: match data.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for SyncParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.reserve0.clone()), Token::Uint(self.reserve1.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type SyncEvent = Event<SyncParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for SyncParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let reserve0 = if param0_ptr == 0 {
+BigInt::zero()
+} else {
 let reserve0_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let reserve0 = BigInt::from_ptr(AscPtr::new(reserve0_value_ptr));
+if reserve0_value_ptr == 0 {
+BigInt::zero()
+} else {
+let reserve0_payload = read_u64_at(reserve0_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(reserve0_payload)
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let reserve1 = if param1_ptr == 0 {
+BigInt::zero()
+} else {
 let reserve1_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let reserve1 = BigInt::from_ptr(AscPtr::new(reserve1_value_ptr));
+if reserve1_value_ptr == 0 {
+BigInt::zero()
+} else {
+let reserve1_payload = read_u64_at(reserve1_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(reserve1_payload)
+}
+};
 
 Self { reserve0, reserve1 }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for SyncParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for SyncParams {
 fn default() -> Self {
 Self {
@@ -272,6 +693,29 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for SyncParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "reserve0".into(), value: Token::Uint(self.reserve0.clone()) },
+                EventParam { name: "reserve1".into(), value: Token::Uint(self.reserve1.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for SyncParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                reserve0: match params.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+                reserve1: match params.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
 /// Transfer(address from, address to, uint256 value)
 pub struct TransferParams {
     pub from: Address,
@@ -279,51 +723,130 @@ pub struct TransferParams {
     pub value: BigInt,
 }
 
+impl TransferParams {
+/// First topic of its logs, the keccak256 hash of `Transfer(address,address,uint256)`.
+pub const TOPIC0: [u8; 32] = [0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa, 0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef];
+}
+
+impl FromLog for TransferParams {
+fn from_log(log: &Log) -> Option<Self> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi::{self, ParamType};
+
+let topics = match log.topics.split_first() {
+Some((topic0, topics)) if topic0.as_slice() == Self::TOPIC0 => topics,
+_ => return None,
+};
+if topics.len() != 2 {
+return None;
+}
+let indexed = [
+    abi::decode_value(&ParamType::parse("address")?, topics[0].as_slice())?,
+    abi::decode_value(&ParamType::parse("address")?, topics[1].as_slice())?,
+];
+let data = abi::decode(&[ParamType::parse("uint256")?], log.data.as_slice())?;
+Some(Self {
+    from: match indexed.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    to: match indexed.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+    value: match data.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToLog for TransferParams {
+fn to_log(&self, address: Address) -> Log {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::abi;
+
+Log {
+address,
+topics: vec![
+    Bytes::from(Self::TOPIC0),
+    Bytes::from(abi::encode(&[Token::Address(self.from.clone())]).unwrap_or_default()),
+    Bytes::from(abi::encode(&[Token::Address(self.to.clone())]).unwrap_or_default()),
+],
+data: Bytes::from(abi::encode(&[Token::Uint(self.value.clone())]).unwrap_or_default()),
+..Default::default()
+}
+}
+}
+
 pub type TransferEvent = Event<TransferParams>;
 
-#[cfg(target_arch = "wasm32")]
 impl FromAscPtr for TransferParams {
 fn from_asc_ptr(ptr: u32) -> Self {
-use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, AscArrayHeader, AscPtr};
+#[allow(unused_imports)]
+use yogurt_runtime::asc::{asc_to_bytes, read_u32_at, read_u64_at, AscPtr};
 
 if ptr == 0 {
 return Self::default();
 }
 
 unsafe {
-// ptr points to Array<EventParam>
-let array_header = ptr as *const AscArrayHeader;
-let buffer_ptr = (*array_header).buffer;
+// ptr points to Array<EventParam>: {buffer, buffer_data_start, ...}
+// Use buffer_data_start, not buffer - buffer points to ArrayBuffer object,
+// buffer_data_start points directly to the data
+let buffer_ptr = read_u32_at(ptr, 4);
+
+// Defensive null check on buffer
+if buffer_ptr == 0 {
+return Self::default();
+}
 
 // EventParam layout: name (offset 0), value (offset 4)
+// value points to ethereum.Value enum (graph-node's AscEnum):
+// kind (i32) + _padding (u32) + payload (u64)
 const EVENT_PARAM_VALUE_OFFSET: usize = 4;
+#[allow(dead_code)]
+const ETHEREUM_VALUE_DATA_OFFSET: usize = 8;
 
                     let param0_ptr = read_u32_at(buffer_ptr, 0);
+let from = if param0_ptr == 0 {
+Address::zero()
+} else {
 let from_value_ptr = read_u32_at(param0_ptr, EVENT_PARAM_VALUE_OFFSET);
-let from_bytes = asc_to_bytes(AscPtr::new(from_value_ptr));
-let from = Address::from(from_bytes.as_slice());
+if from_value_ptr == 0 {
+Address::zero()
+} else {
+let from_payload = read_u64_at(from_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let from_bytes = asc_to_bytes(AscPtr::new(from_payload));
+Address::from(from_bytes.as_slice())
+}
+};
 
                     let param1_ptr = read_u32_at(buffer_ptr, 4);
+let to = if param1_ptr == 0 {
+Address::zero()
+} else {
 let to_value_ptr = read_u32_at(param1_ptr, EVENT_PARAM_VALUE_OFFSET);
-let to_bytes = asc_to_bytes(AscPtr::new(to_value_ptr));
-let to = Address::from(to_bytes.as_slice());
+if to_value_ptr == 0 {
+Address::zero()
+} else {
+let to_payload = read_u64_at(to_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+let to_bytes = asc_to_bytes(AscPtr::new(to_payload));
+Address::from(to_bytes.as_slice())
+}
+};
 
                     let param2_ptr = read_u32_at(buffer_ptr, 8);
+let value = if param2_ptr == 0 {
+BigInt::zero()
+} else {
 let value_value_ptr = read_u32_at(param2_ptr, EVENT_PARAM_VALUE_OFFSET);
-let value = BigInt::from_ptr(AscPtr::new(value_value_ptr));
+if value_value_ptr == 0 {
+BigInt::zero()
+} else {
+let value_payload = read_u64_at(value_value_ptr, ETHEREUM_VALUE_DATA_OFFSET) as u32;
+BigInt::from_asc_ptr(value_payload)
+}
+};
 
 Self { from, to, value }
 }
 }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-impl FromAscPtr for TransferParams {
-fn from_asc_ptr(_ptr: u32) -> Self {
-Self::default()
-}
-}
-
 impl Default for TransferParams {
 fn default() -> Self {
 Self {
@@ -334,6 +857,48 @@ Self {
 }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl yogurt_runtime::ethereum::ToEventParams for TransferParams {
+fn to_event_params(&self) -> Vec<yogurt_runtime::ethereum::EventParam> {
+#[allow(unused_imports)]
+use yogurt_runtime::ethereum::EventParam;
+vec![
+                EventParam { name: "from".into(), value: Token::Address(self.from.clone()) },
+                EventParam { name: "to".into(), value: Token::Address(self.to.clone()) },
+                EventParam { name: "value".into(), value: Token::Uint(self.value.clone()) },
+            ]
+}
+}
+
+impl yogurt_runtime::rust_abi::Decode for TransferParams {
+fn decode(input: &mut &[u8]) -> Result<Self, yogurt_runtime::rust_abi::DecodeError> {
+#[allow(unused_variables)]
+let params = yogurt_runtime::rust_abi::decode_event_params(input)?;
+Ok(Self {
+                from: match params.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                to: match params.get(1) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() },
+                value: match params.get(2) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() },
+})
+}
+}
+
+/// Custom errors of the UniswapV2Pair contract, decoded from the revert data of
+/// its `try_*` calls.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniswapV2PairError {
+}
+
+impl ContractError for UniswapV2PairError {
+fn decode(data: &[u8]) -> Option<Self> {
+let _ = data;
+None
+}
+
+fn encode(&self) -> Vec<u8> {
+match *self {}
+}
+}
+
 /// Binding for the UniswapV2Pair contract.
 pub struct UniswapV2Pair {
 address: Address,
@@ -347,7 +912,13 @@ Self { address }
 
     /// Call `factory`
 pub fn factory(&self) -> Result<Address, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_factory().map_err(|e| e.to_string())
+}
+
+/// Try to call `factory`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_factory(&self) -> Result<Address, CallError<UniswapV2PairError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Pair".into(),
@@ -357,19 +928,21 @@ function_signature: "factory()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() };
 Ok(value)
-}
-
-/// Try to call `factory`, returning None on revert.
-pub fn try_factory(&self) -> Option<Address> {
-self.factory().ok()
 }
 
     /// Call `getReserves`
 pub fn get_reserves(&self) -> Result<(BigInt, BigInt, u64), String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_get_reserves().map_err(|e| e.to_string())
+}
+
+/// Try to call `getReserves`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_get_reserves(&self) -> Result<(BigInt, BigInt, u64), CallError<UniswapV2PairError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Pair".into(),
@@ -379,18 +952,20 @@ function_signature: "getReserves()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 Ok((match result.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() }, match result.get(1) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() }, match result.get(2) { Some(Token::Uint(n)) => { let s = n.to_string(); s.parse::<u64>().unwrap_or(0) }, _ => 0 }),)
-}
-
-/// Try to call `getReserves`, returning None on revert.
-pub fn try_get_reserves(&self) -> Option<(BigInt, BigInt, u64)> {
-self.get_reserves().ok()
 }
 
     /// Call `token0`
 pub fn token0(&self) -> Result<Address, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_token0().map_err(|e| e.to_string())
+}
+
+/// Try to call `token0`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_token0(&self) -> Result<Address, CallError<UniswapV2PairError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Pair".into(),
@@ -400,19 +975,21 @@ function_signature: "token0()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() };
 Ok(value)
-}
-
-/// Try to call `token0`, returning None on revert.
-pub fn try_token0(&self) -> Option<Address> {
-self.token0().ok()
 }
 
     /// Call `token1`
 pub fn token1(&self) -> Result<Address, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_token1().map_err(|e| e.to_string())
+}
+
+/// Try to call `token1`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_token1(&self) -> Result<Address, CallError<UniswapV2PairError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Pair".into(),
@@ -422,19 +999,21 @@ function_signature: "token1()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Address(a)) => a.clone(), _ => Address::zero() };
 Ok(value)
-}
-
-/// Try to call `token1`, returning None on revert.
-pub fn try_token1(&self) -> Option<Address> {
-self.token1().ok()
 }
 
     /// Call `totalSupply`
 pub fn total_supply(&self) -> Result<BigInt, String> {
-use yogurt_runtime::ethereum::{SmartContractCall, Token, call};
+self.try_total_supply().map_err(|e| e.to_string())
+}
+
+/// Try to call `totalSupply`, telling a revert reason, a custom error
+/// and an unknown revert apart.
+pub fn try_total_supply(&self) -> Result<BigInt, CallError<UniswapV2PairError>> {
+use yogurt_runtime::ethereum::{SmartContractCall, Token, try_call};
 
 let call_data = SmartContractCall {
 contract_name: "UniswapV2Pair".into(),
@@ -444,14 +1023,10 @@ function_signature: "totalSupply()".into(),
 function_params: vec![],
 };
 
-let result = call(call_data).ok_or_else(|| "Call reverted".to_string())?;
+let result = try_call(call_data)
+.map_err(|data| CallError::from_revert_data(data.as_slice()))?;
 let value = match result.get(0) { Some(Token::Uint(n)) => n.clone(), _ => BigInt::zero() };
 Ok(value)
 }
 
-/// Try to call `totalSupply`, returning None on revert.
-pub fn try_total_supply(&self) -> Option<BigInt> {
-self.total_supply().ok()
-}
-
 }
@@ -1,14 +1,14 @@
 //! Uniswap V2 Subgraph — yogurt example
 //!
 //! Demonstrates:
-//! - Data source templates (Factory spawns Pair watchers)
+//! - Data source templates (Factory spawns Pair watchers) with a typed context
 //! - Contract calls (token0, token1, getReserves)
 //! - Multiple related entities
 //! - Immutable entities (Swap, Mint, Burn)
 
-#![no_std]
+#![cfg_attr(target_arch = "wasm32", no_std)]
 
 extern crate alloc;
 
-mod generated;
-mod mappings;
+pub mod generated;
+pub mod mappings;
@@ -17,7 +17,7 @@ use crate::generated::{
     // Contract bindings
     ERC20,
     // Templates
-    templates::Pair as PairTemplate,
+    templates::{Pair as PairTemplate, PairContext},
 };
 
 /// Factory address constant
@@ -61,8 +61,15 @@ pub fn handle_pair_created(event: PairCreatedEvent) {
     pair.set_created_at_block_number(event.block.number);
     pair.save();
 
-    // Spawn a data source template to watch the new pair
-    PairTemplate::create(&pair_address);
+    // Spawn a data source template to watch the new pair, passing it the
+    // pair's tokens so its handlers need not load the Pair entity
+    PairTemplate::create_with_context(
+        &pair_address,
+        PairContext {
+            token0: token0_address.clone(),
+            token1: token1_address.clone(),
+        },
+    );
 
     yogurt_runtime::log_info!(
         "New pair created: {} (tokens: {}, {})",
@@ -88,10 +95,10 @@ fn fetch_or_create_token(address: &Address) -> Token {
 
     // Create new token with fetched metadata
     let mut token = Token::new(&token_id);
-    token.set_symbol(contract.try_symbol().unwrap_or_else(|| "???".into()));
-    token.set_name(contract.try_name().unwrap_or_else(|| "Unknown".into()));
+    token.set_symbol(contract.try_symbol().unwrap_or_else(|_| "???".into()));
+    token.set_name(contract.try_name().unwrap_or_else(|_| "Unknown".into()));
     token.set_decimals(BigInt::from(contract.try_decimals().unwrap_or(18)));
-    token.set_total_supply(contract.try_total_supply().unwrap_or_else(BigInt::zero));
+    token.set_total_supply(contract.try_total_supply().unwrap_or_else(|_| BigInt::zero()));
     token.set_trade_volume(BigDecimal::zero());
     token.set_tx_count(BigInt::zero());
     token.save();
@@ -122,6 +129,15 @@ pub fn handle_swap(event: SwapEvent) {
     Pair::update(&pair_id, |p| {
         p.set_tx_count(p.tx_count() + BigInt::from(1));
     });
+
+    // Count the swap for both tokens, read from the template context
+    if let Some(context) = PairContext::current() {
+        for token in [context.token0, context.token1] {
+            Token::update(&token.to_hex(), |t| {
+                t.set_tx_count(t.tx_count() + BigInt::from(1));
+            });
+        }
+    }
 }
 
 /// Handle Mint (add liquidity) event from a Pair contract.
@@ -0,0 +1,86 @@
+//! Tests that run the manifest's handlers through the generated dispatch
+//! table, the way graph-node routes logs to data sources and templates.
+
+use yogurt_runtime::prelude::*;
+use yogurt_runtime::testing::*;
+use yogurt_runtime::types::Entity;
+
+use uniswap_v2::generated::{
+    dispatch_table, Pair, PairCreatedParams, SwapParams, SyncParams, Token,
+};
+
+const FACTORY: &str = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f";
+const TOKEN0: [u8; 20] = [0x11; 20];
+const TOKEN1: [u8; 20] = [0x22; 20];
+const PAIR: [u8; 20] = [0xAA; 20];
+
+fn pair_created() -> PairCreatedParams {
+    PairCreatedParams {
+        token0: Address::from(TOKEN0),
+        token1: Address::from(TOKEN1),
+        pair: Address::from(PAIR),
+        pair_index: BigInt::from(1),
+    }
+}
+
+fn swap() -> SwapParams {
+    SwapParams {
+        sender: Address::from([0x33; 20]),
+        amount0_in: BigInt::from(1000),
+        amount1_in: BigInt::zero(),
+        amount0_out: BigInt::zero(),
+        amount1_out: BigInt::from(997),
+        to: Address::from([0x44; 20]),
+    }
+}
+
+#[test]
+fn test_chain_routes_pair_events_to_template() {
+    clear_store();
+    let factory = Address::from_string(FACTORY).unwrap();
+    let pair = Address::from(PAIR);
+
+    let mut chain = Chain::new(dispatch_table());
+    chain
+        .run([
+            // Before the factory's start block
+            ChainBlock::new(create_block(10_000_834, 1_588_610_030)).event(factory.clone(), &pair_created()),
+            // The new pair processes the block it is created in, after the
+            // factory, so its earlier swap is indexed too
+            ChainBlock::new(create_block(10_000_835, 1_588_610_042))
+                .event(pair.clone(), &swap())
+                .event(factory.clone(), &pair_created()),
+            ChainBlock::new(create_block(10_000_836, 1_588_610_054))
+                .event(pair.clone(), &swap())
+                .event(
+                    pair.clone(),
+                    &SyncParams {
+                        reserve0: BigInt::from(5000),
+                        reserve1: BigInt::from(4003),
+                    },
+                )
+                // Not a pair
+                .event(Address::from([0xBB; 20]), &swap()),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        chain.history(),
+        [
+            (10_000_835, "handlePairCreated"),
+            (10_000_835, "handleSwap"),
+            (10_000_836, "handleSwap"),
+            (10_000_836, "handleSync"),
+        ]
+    );
+    assert_eq!(chain.template_instances("Pair"), vec![pair.clone()]);
+
+    let pair = Pair::load(&pair.to_hex()).expect("Pair should exist");
+    assert_eq!(pair.tx_count(), BigInt::from(2));
+    assert_eq!(pair.reserve0(), BigDecimal::from_big_int(&BigInt::from(5000)));
+    // The swap read the tokens from the template context the chain set
+    for token in [TOKEN0, TOKEN1] {
+        let token = Token::load(&Address::from(token).to_hex()).expect("Token should exist");
+        assert_eq!(token.tx_count(), BigInt::from(2));
+    }
+}
@@ -0,0 +1,96 @@
+//! Tests for the Pair template and its typed context.
+//!
+//! These tests run natively (no WASM) using yogurt's testing framework.
+
+use yogurt_runtime::prelude::*;
+use yogurt_runtime::testing::*;
+use yogurt_runtime::types::Entity;
+
+use uniswap_v2::generated::templates::PairContext;
+use uniswap_v2::generated::{PairCreatedEvent, PairCreatedParams, SwapEvent, SwapParams, Token};
+use uniswap_v2::mappings::{handle_pair_created, handle_swap};
+
+const TOKEN0: [u8; 20] = [0x11; 20];
+const TOKEN1: [u8; 20] = [0x22; 20];
+const PAIR: [u8; 20] = [0xAA; 20];
+
+fn create_pair() {
+    let event: PairCreatedEvent = EventBuilder::new()
+        .address(Address::from([0x5C; 20]))
+        .block_number(10_000_835)
+        .params(PairCreatedParams {
+            token0: Address::from(TOKEN0),
+            token1: Address::from(TOKEN1),
+            pair: Address::from(PAIR),
+            pair_index: BigInt::from(1),
+        })
+        .build();
+
+    handle_pair_created(event);
+}
+
+#[test]
+fn test_pair_created_passes_tokens_in_context() {
+    clear_store();
+    clear_created_data_sources();
+
+    create_pair();
+
+    let created = created_data_sources();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].template, "Pair");
+    assert_eq!(created[0].params, [Address::from(PAIR).to_hex()]);
+
+    let context = PairContext::from_entity(created[0].context.as_ref().expect("Pair should have a context"))
+        .expect("context should have token0 and token1");
+    assert_eq!(context.token0, Address::from(TOKEN0));
+    assert_eq!(context.token1, Address::from(TOKEN1));
+}
+
+#[test]
+fn test_swap_reads_template_context() {
+    clear_store();
+    clear_created_data_sources();
+    clear_data_source_mocks();
+
+    create_pair();
+
+    // Run the template handler as the data source the factory created
+    let created = created_data_sources().remove(0);
+    mock_data_source_address(Address::from(PAIR));
+    mock_data_source_context(created.context.unwrap());
+
+    let context = PairContext::current().expect("PairContext should be set");
+    assert_eq!(context.token0, Address::from(TOKEN0));
+    assert_eq!(context.token1, Address::from(TOKEN1));
+
+    let event: SwapEvent = EventBuilder::new()
+        .address(Address::from(PAIR))
+        .transaction_hash([0x01; 32])
+        .log_index(3)
+        .params(SwapParams {
+            sender: Address::from([0x33; 20]),
+            amount0_in: BigInt::from(1000),
+            amount1_in: BigInt::zero(),
+            amount0_out: BigInt::zero(),
+            amount1_out: BigInt::from(997),
+            to: Address::from([0x44; 20]),
+        })
+        .build();
+
+    handle_swap(event);
+
+    for token in [TOKEN0, TOKEN1] {
+        let token = Token::load(&Address::from(token).to_hex()).expect("Token should exist");
+        assert_eq!(token.tx_count(), BigInt::from(1));
+    }
+
+    clear_data_source_mocks();
+}
+
+#[test]
+fn test_context_is_none_without_create_with_context() {
+    clear_data_source_mocks();
+
+    assert!(PairContext::current().is_none());
+}